@@ -0,0 +1,21 @@
+//! Feeds arbitrary content to the level parser, which should either load it
+//! or reject it with an error, but never panic.
+//!
+//! Run with `cargo fuzz run level_parser` from the repository root.
+
+#![no_main]
+
+use chunky_challenge::levels::Level;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|content: &str| {
+    let Ok(level) = content.parse::<Level>() else {
+        return;
+    };
+
+    // Whatever the parser accepts, it should also accept once saved again.
+    let saved = level.save();
+    if let Err(error) = saved.parse::<Level>() {
+        panic!("saved level doesn't load: {error}\n{saved}");
+    }
+});
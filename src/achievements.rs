@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_object::{Player, Position, Pushable},
+    game_state::GameState,
+    on_game_event,
+    toast::ShowToast,
+};
+
+/// Achievements that can be unlocked in the game itself, without relying on
+/// any platform such as Steam.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Achievement {
+    FinishLevel,
+    FinishTenLevels,
+    PushHundredBlocks,
+    PushFiveHundredBlocks,
+    SinkCreature,
+    FindSecretExit,
+    CompleteGame,
+}
+
+impl Achievement {
+    pub const ALL: [Self; 7] = [
+        Self::FinishLevel,
+        Self::FinishTenLevels,
+        Self::PushHundredBlocks,
+        Self::PushFiveHundredBlocks,
+        Self::SinkCreature,
+        Self::FindSecretExit,
+        Self::CompleteGame,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::FinishLevel => "First Steps",
+            Self::FinishTenLevels => "Getting Chunky",
+            Self::PushHundredBlocks => "Heavy Lifting",
+            Self::PushFiveHundredBlocks => "Block Mover",
+            Self::SinkCreature => "Swim Lessons",
+            Self::FindSecretExit => "Off the Beaten Path",
+            Self::CompleteGame => "Chunky Champion",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::FinishLevel => "Finish a level",
+            Self::FinishTenLevels => "Finish 10 levels",
+            Self::PushHundredBlocks => "Push 100 blocks",
+            Self::PushFiveHundredBlocks => "Push 500 blocks",
+            Self::SinkCreature => "Sink a creature",
+            Self::FindSecretExit => "Find a secret exit",
+            Self::CompleteGame => "Finish every level",
+        }
+    }
+
+    /// Returns the progress towards the achievement and the progress needed
+    /// to unlock it.
+    pub fn progress(self, game_state: &GameState) -> (u32, u32) {
+        let num_finished = game_state
+            .finished_levels
+            .iter()
+            .filter(|level| !game_state.is_hub_level(**level))
+            .count() as u32;
+        let counters = &game_state.achievement_counters;
+
+        match self {
+            Self::FinishLevel => (num_finished, 1),
+            Self::FinishTenLevels => (num_finished, 10),
+            Self::PushHundredBlocks => (counters.blocks_pushed, 100),
+            Self::PushFiveHundredBlocks => (counters.blocks_pushed, 500),
+            Self::SinkCreature => (counters.creatures_sunk, 1),
+            Self::FindSecretExit => (game_state.secret_exits.len() as u32, 1),
+            Self::CompleteGame => (game_state.completed as u32, 1),
+        }
+    }
+}
+
+/// Counters of what the player has done, for the achievements that aren't
+/// based on the levels they have finished.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AchievementCounters {
+    pub blocks_pushed: u32,
+    pub creatures_sunk: u32,
+}
+
+/// Reports something the player did that may unlock an achievement.
+#[derive(Clone, Copy, Event)]
+pub enum AchievementProgress {
+    BlocksPushed(u32),
+    CreatureSunk,
+    LevelFinished,
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AchievementProgress>()
+            .add_observer(on_achievement_progress)
+            .add_systems(Update, count_pushed_blocks.after(on_game_event));
+    }
+}
+
+/// Counts the blocks that were moved along with the player.
+fn count_pushed_blocks(
+    mut commands: Commands,
+    player_query: Query<Ref<Position>, With<Player>>,
+    pushable_query: Query<Ref<Position>, (With<Pushable>, Without<Player>)>,
+) {
+    let has_moved = player_query
+        .iter()
+        .any(|position| position.is_changed() && !position.is_added());
+    if !has_moved {
+        return;
+    }
+
+    let num_pushed = pushable_query
+        .iter()
+        .filter(|position| position.is_changed() && !position.is_added())
+        .count() as u32;
+    if num_pushed > 0 {
+        commands.trigger(AchievementProgress::BlocksPushed(num_pushed));
+    }
+}
+
+/// Updates the counters for the reported progress and announces every
+/// achievement that got unlocked by it.
+fn on_achievement_progress(
+    trigger: Trigger<AchievementProgress>,
+    mut commands: Commands,
+    mut game_state: ResMut<GameState>,
+) {
+    match trigger.event() {
+        AchievementProgress::BlocksPushed(count) => game_state.record_blocks_pushed(*count),
+        AchievementProgress::CreatureSunk => game_state.record_creature_sunk(),
+        AchievementProgress::LevelFinished => {}
+    }
+
+    for achievement in Achievement::ALL {
+        if game_state.achievements.contains(&achievement) {
+            continue;
+        }
+
+        let (progress, required) = achievement.progress(&game_state);
+        if progress >= required && game_state.unlock_achievement(achievement) {
+            commands.trigger(ShowToast::new(format!(
+                "Achievement unlocked: {}",
+                achievement.title()
+            )));
+        }
+    }
+}
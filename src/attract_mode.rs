@@ -0,0 +1,176 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    credits::CreditsState,
+    cutscene::CutsceneState,
+    game_state::GameState,
+    menu::{MenuKind, MenuState},
+    on_game_event, GameEvent, LoadLevel,
+};
+
+/// How long the start menu needs to sit idle before the demo starts.
+const IDLE_DURATION: Duration = Duration::from_secs(30);
+
+/// Time between the moves of the demo.
+const MOVE_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Time the demo waits after its last move before it starts over.
+const RESTART_DELAY: Duration = Duration::from_secs(3);
+
+const DEMO_LEVEL: u16 = 1;
+
+/// Moves of the demo, as a canned replay through the maze of the demo level.
+///
+/// The replay stops one step short of the exit, so the demo never finishes
+/// the level.
+const DEMO_MOVES: &str = "RRRDRRURRDDLDLDDRRDDLLLLLDDDLDLDDLLULLDDDLL";
+
+/// Plays a demo of an early level when the start menu sits idle, until there
+/// is any input, after which the start menu is shown again.
+#[derive(Resource)]
+struct AttractMode {
+    idle_timer: Timer,
+    demo: Option<Demo>,
+}
+
+struct Demo {
+    moves: VecDeque<(i16, i16)>,
+    timer: Timer,
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        Self {
+            idle_timer: Timer::new(IDLE_DURATION, TimerMode::Once),
+            demo: None,
+        }
+    }
+}
+
+impl Demo {
+    fn new() -> Self {
+        Self {
+            moves: DEMO_MOVES
+                .chars()
+                .filter_map(|direction| match direction {
+                    'U' => Some((0, -1)),
+                    'R' => Some((1, 0)),
+                    'D' => Some((0, 1)),
+                    'L' => Some((-1, 0)),
+                    _ => None,
+                })
+                .collect(),
+            timer: Timer::new(MOVE_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+pub struct AttractModePlugin;
+
+impl Plugin for AttractModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AttractMode>()
+            .add_systems(PreUpdate, on_attract_mode_input.after(InputSystem))
+            .add_systems(Update, (start_demo, play_demo.before(on_game_event)));
+    }
+}
+
+/// Stops the demo on any input, which is consumed so it doesn't also act on
+/// the start menu that is shown again.
+#[expect(clippy::too_many_arguments)]
+fn on_attract_mode_input(
+    mut commands: Commands,
+    mut attract_mode: ResMut<AttractMode>,
+    mut game_state: ResMut<GameState>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut menu_state: ResMut<MenuState>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    touches: Res<Touches>,
+) {
+    let has_input = keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+        || touches.any_just_pressed()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+    if !has_input {
+        return;
+    }
+
+    attract_mode.idle_timer.reset();
+    if attract_mode.demo.take().is_none() {
+        return;
+    }
+
+    keys.reset_all();
+    mouse_buttons.reset_all();
+
+    game_state.is_playing_demo = false;
+    commands.trigger(LoadLevel(0));
+    menu_state.set_open(MenuKind::Hub);
+}
+
+fn start_demo(
+    mut commands: Commands,
+    mut attract_mode: ResMut<AttractMode>,
+    mut game_state: ResMut<GameState>,
+    mut menu_state: ResMut<MenuState>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    time: Res<Time>,
+) {
+    if attract_mode.demo.is_some() {
+        return;
+    }
+
+    let is_idle = menu_state.is_in_hub_menu()
+        && game_state.is_in_hub()
+        && !credits_state.is_playing()
+        && !cutscene_state.is_playing();
+    if !is_idle {
+        attract_mode.idle_timer.reset();
+        return;
+    }
+
+    attract_mode.idle_timer.tick(time.delta());
+    if !attract_mode.idle_timer.finished() {
+        return;
+    }
+
+    attract_mode.demo = Some(Demo::new());
+    game_state.is_playing_demo = true;
+    commands.trigger(LoadLevel(DEMO_LEVEL));
+    menu_state.close();
+}
+
+fn play_demo(
+    mut commands: Commands,
+    mut attract_mode: ResMut<AttractMode>,
+    mut game_events: EventWriter<GameEvent>,
+    time: Res<Time>,
+) {
+    let Some(demo) = attract_mode.demo.as_mut() else {
+        return;
+    };
+
+    demo.timer.tick(time.delta());
+    if !demo.timer.just_finished() {
+        return;
+    }
+
+    match demo.moves.pop_front() {
+        Some((dx, dy)) => {
+            game_events.send(GameEvent::MovePlayer(dx, dy));
+            if demo.moves.is_empty() {
+                demo.timer = Timer::new(RESTART_DELAY, TimerMode::Once);
+            }
+        }
+        None => {
+            *demo = Demo::new();
+            commands.trigger(LoadLevel(DEMO_LEVEL));
+        }
+    }
+}
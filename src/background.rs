@@ -3,16 +3,37 @@ use std::time::Duration;
 use bevy::prelude::*;
 
 use crate::{
-    constants::*, editor::EditorState, levels::*, load_level, menu::MenuState, on_player_moved,
-    on_resize, ui_state::UiState, utils::load_repeating_asset, ExitState, LoadLevel, Player,
-    Position,
+    constants::*, editor::EditorState, game_state::GameState, levels::*, load_level,
+    menu::MenuState, on_keyboard_input, on_mouse_input, on_player_moved, on_resize,
+    photo_mode::PhotoMode, settings::Settings, ui_state::UiState, utils::load_repeating_asset,
+    ExitState, LoadLevel, Player, Position,
 };
 
 const BACKGROUND_ASSET: &[u8] = include_bytes!("../assets/sprites/background.png");
+const CAVE_BACKGROUND_ASSET: &[u8] = include_bytes!("../assets/sprites/background_cave.png");
+const DESERT_BACKGROUND_ASSET: &[u8] = include_bytes!("../assets/sprites/background_desert.png");
+const ICE_BACKGROUND_ASSET: &[u8] = include_bytes!("../assets/sprites/background_ice.png");
 
 const INITIAL_HUB_FOCUS: (i16, i16) = (33, 26);
 const INITIAL_HUB_ZOOM_FACTOR: f32 = 0.32768;
 
+/// Duration of the pan across a level before the player takes control.
+const LEVEL_PREVIEW_DURATION_MS: u64 = 3000;
+
+/// Range of zoom factors a level starts with. Small levels are shown bigger
+/// without their tiles becoming blurry, and large levels are only zoomed out
+/// as far as their tiles remain readable.
+const MIN_DEFAULT_ZOOM_FACTOR: f32 = 0.5;
+const MAX_DEFAULT_ZOOM_FACTOR: f32 = 2.;
+
+/// Number of tiles that are kept free around a level that is zoomed to fit
+/// the window.
+const FIT_MARGIN: f32 = 1.;
+
+/// Number of tiles around the window for which objects remain visible, so
+/// they don't pop in while the background is moving.
+const CULLING_MARGIN: f32 = 2.;
+
 #[derive(Component)]
 pub struct Background;
 
@@ -27,6 +48,13 @@ pub enum BackgroundTransformAnimation {
     },
 }
 
+/// Whether the camera is panning across the level for the player to scout it.
+/// All input is consumed while it is, and any press skips to the end.
+#[derive(Default, Resource)]
+pub struct LevelPreviewState {
+    pub is_active: bool,
+}
+
 #[derive(Eq, Event, Ord, PartialEq, PartialOrd)]
 pub enum UpdateBackgroundTransform {
     Immediate,
@@ -34,6 +62,23 @@ pub enum UpdateBackgroundTransform {
     HubIntro,
     LevelExit,
     LevelEntrance,
+
+    /// Pans across the level, from the point furthest from the player to the
+    /// player, before the player takes control. Levels that fit in the window
+    /// are entered as usual instead.
+    LevelPreview,
+
+    /// Pans to the player after fast-traveling across the hub.
+    FastTravel,
+
+    /// Slowly pans over the hub while the credits are playing.
+    Credits,
+
+    /// Pans to the given position as part of a cutscene.
+    Cutscene {
+        focus: (i16, i16),
+        duration_ms: u64,
+    },
 }
 
 pub struct BackgroundPlugin;
@@ -43,7 +88,9 @@ impl Plugin for BackgroundPlugin {
         app.add_systems(Startup, setup_background)
             .init_resource::<BackgroundAsset>()
             .init_resource::<BackgroundTransformAnimation>()
+            .init_resource::<LevelPreviewState>()
             .add_event::<UpdateBackgroundTransform>()
+            .add_observer(start_level_preview)
             .add_systems(
                 Update,
                 resize_background
@@ -51,20 +98,52 @@ impl Plugin for BackgroundPlugin {
                     .after(on_resize)
                     .after(on_player_moved),
             )
+            .add_systems(Update, restore_zoom_factor.after(load_level))
             .add_systems(
                 Update,
-                on_update_background_transform.after(resize_background),
+                on_update_background_transform
+                    .after(resize_background)
+                    .after(restore_zoom_factor),
             )
             .add_systems(
                 Update,
                 on_background_transform_animation.after(on_update_background_transform),
-            );
+            )
+            .add_systems(
+                Update,
+                cull_offscreen_objects.after(on_background_transform_animation),
+            )
+            .add_systems(
+                Update,
+                skip_level_preview
+                    .before(on_keyboard_input)
+                    .before(on_mouse_input),
+            )
+            .add_systems(Update, update_background_theme.after(load_level));
     }
 }
 
+/// Background tiles for every [Theme].
+///
+/// The themes other than grass use the same tile, recolored to their own
+/// palette.
 #[derive(Clone, Default, Resource)]
 struct BackgroundAsset {
-    pub background: Handle<Image>,
+    pub grass: Handle<Image>,
+    pub cave: Handle<Image>,
+    pub ice: Handle<Image>,
+    pub desert: Handle<Image>,
+}
+
+impl BackgroundAsset {
+    fn get(&self, theme: Theme) -> Handle<Image> {
+        match theme {
+            Theme::Grass => self.grass.clone(),
+            Theme::Cave => self.cave.clone(),
+            Theme::Ice => self.ice.clone(),
+            Theme::Desert => self.desert.clone(),
+        }
+    }
 }
 
 fn setup_background(
@@ -72,9 +151,26 @@ fn setup_background(
     mut asset: ResMut<BackgroundAsset>,
     mut image_assets: ResMut<Assets<Image>>,
 ) {
-    asset.background = image_assets.add(load_repeating_asset(BACKGROUND_ASSET));
+    asset.grass = image_assets.add(load_repeating_asset(BACKGROUND_ASSET));
+    asset.cave = image_assets.add(load_repeating_asset(CAVE_BACKGROUND_ASSET));
+    asset.ice = image_assets.add(load_repeating_asset(ICE_BACKGROUND_ASSET));
+    asset.desert = image_assets.add(load_repeating_asset(DESERT_BACKGROUND_ASSET));
+
+    commands.spawn((Background, Sprite::from_image(asset.grass.clone())));
+}
+
+fn update_background_theme(
+    mut background_query: Query<&mut Sprite, With<Background>>,
+    asset: Res<BackgroundAsset>,
+    theme: Res<Theme>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
 
-    commands.spawn((Background, Sprite::from_image(asset.background.clone())));
+    for mut sprite in &mut background_query {
+        sprite.image = asset.get(*theme);
+    }
 }
 
 fn resize_background(
@@ -90,43 +186,149 @@ fn resize_background(
         .get_single_mut()
         .expect("there should be only one background");
 
-    sprite.rect = Some(Rect::new(
-        0.,
-        0.,
-        (dimensions.width * GRID_SIZE) as f32,
-        (dimensions.height * GRID_SIZE) as f32,
-    ));
+    let size = dimensions.size_in_pixels();
+    sprite.rect = Some(Rect::new(0., 0., size.x, size.y));
 
     commands.send_event(UpdateBackgroundTransform::Immediate);
 }
 
+/// Remembers the zoom factor of the level the player leaves, and restores the
+/// one they used for the level they enter.
+///
+/// Levels the player hasn't zoomed in yet are zoomed to fit the window
+/// whenever they are entered, as far as the default range allows. Hubs are
+/// meant to be explored instead, so they aren't zoomed out to fit.
+fn restore_zoom_factor(
+    window_query: Query<&Window>,
+    mut entered_level: Local<Option<(u16, f32)>>,
+    mut ui_state: ResMut<UiState>,
+    dimensions: Res<Dimensions>,
+    editor_state: Res<EditorState>,
+    game_state: Res<GameState>,
+    ui_scale: Res<UiScale>,
+) {
+    let level = game_state.current_level;
+    if entered_level.is_some_and(|(entered_level, _)| entered_level == level) {
+        return;
+    }
+
+    // Only zoom factors the player has chosen are remembered, so levels that
+    // are left alone keep fitting the window.
+    if let Some((previous_level, default_zoom_factor)) = *entered_level {
+        let zoom_factor = ui_state.zoom_factor;
+        if zoom_factor != default_zoom_factor {
+            ui_state.zoom_factors.insert(previous_level, zoom_factor);
+        }
+    }
+
+    let default_zoom_factor = window_query
+        .get_single()
+        .ok()
+        .filter(|_| !game_state.is_in_hub())
+        .map(|window| {
+            let available_size = window.size() - Vec2::new(editor_state.width(&ui_scale), 0.);
+            fitting_zoom_factor(&dimensions, available_size)
+        })
+        .unwrap_or(1.);
+    *entered_level = Some((level, default_zoom_factor));
+
+    let zoom_factor = ui_state
+        .zoom_factors
+        .get(&level)
+        .copied()
+        .unwrap_or(default_zoom_factor);
+    if ui_state.zoom_factor != zoom_factor {
+        ui_state.zoom_factor = zoom_factor;
+    }
+}
+
+/// Previews levels other than the hubs when the player has enabled it. Sent
+/// alongside the regular entrance, which it takes precedence over.
+fn start_level_preview(
+    trigger: Trigger<LoadLevel>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    game_state: Res<GameState>,
+    menu_state: Res<MenuState>,
+    settings: Res<Settings>,
+) {
+    let LoadLevel(level) = trigger.event();
+    if settings.level_preview && !game_state.is_hub_level(*level) && !menu_state.is_in_hub_menu() {
+        background_events.send(UpdateBackgroundTransform::LevelPreview);
+    }
+}
+
+/// Skips to the end of the level preview on any press, and keeps all input
+/// from reaching the game while the preview is shown.
+fn skip_level_preview(
+    mut gamepads: Query<&mut Gamepad>,
+    mut animation: ResMut<BackgroundTransformAnimation>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    preview_state: Res<LevelPreviewState>,
+    touches: Res<Touches>,
+) {
+    if !preview_state.is_active {
+        return;
+    }
+
+    let mut is_skipped = keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+        || touches.any_just_pressed();
+    for mut gamepad in &mut gamepads {
+        for button in gamepad.get_just_pressed().copied().collect::<Vec<_>>() {
+            is_skipped = true;
+            gamepad.digital_mut().clear_just_pressed(button);
+        }
+    }
+    keys.reset_all();
+    mouse_buttons.reset_all();
+
+    if is_skipped {
+        if let BackgroundTransformAnimation::Active { timer, .. } = animation.as_mut() {
+            let duration = timer.duration();
+            timer.set_elapsed(duration);
+        }
+    }
+}
+
 #[expect(clippy::too_many_arguments)]
 fn on_update_background_transform(
     mut reader: EventReader<UpdateBackgroundTransform>,
     mut background_query: Query<&mut Transform, With<Background>>,
     mut animation: ResMut<BackgroundTransformAnimation>,
+    mut preview_state: ResMut<LevelPreviewState>,
     player_query: Query<&Position, With<Player>>,
     window_query: Query<&Window>,
     dimensions: Res<Dimensions>,
     editor_state: Res<EditorState>,
     menu_state: Res<MenuState>,
-    ui_state: Res<UiState>,
+    photo_mode: Res<PhotoMode>,
+    ui_scale: Res<UiScale>,
+    mut ui_state: ResMut<UiState>,
 ) {
     let event = reader.read().reduce(|slowest, event| event.max(slowest));
-    let duration_ms = match event {
+    let mut duration_ms = match event {
         Some(UpdateBackgroundTransform::Immediate) => 0,
         Some(UpdateBackgroundTransform::Fast) => 200,
         Some(UpdateBackgroundTransform::LevelExit | UpdateBackgroundTransform::LevelEntrance) => {
             400
         }
+        Some(UpdateBackgroundTransform::LevelPreview) => LEVEL_PREVIEW_DURATION_MS,
+        Some(UpdateBackgroundTransform::FastTravel) => 1000,
         Some(UpdateBackgroundTransform::HubIntro) => 2000,
+        Some(UpdateBackgroundTransform::Credits) => 10000,
+        Some(UpdateBackgroundTransform::Cutscene { duration_ms, .. }) => *duration_ms,
         None => return,
     };
 
     let Ok(player_position) = player_query.get_single() else {
         return;
     };
-    let focus_position = if menu_state.is_in_hub_menu() {
+    let shows_hub_overview =
+        menu_state.is_in_hub_menu() || event == Some(&UpdateBackgroundTransform::Credits);
+    let focus_position = if let Some(UpdateBackgroundTransform::Cutscene { focus, .. }) = event {
+        *focus
+    } else if shows_hub_overview {
         (INITIAL_HUB_FOCUS.0, INITIAL_HUB_FOCUS.1)
     } else {
         (player_position.x, player_position.y)
@@ -140,31 +342,92 @@ fn on_update_background_transform(
         .expect("there should be only one window");
 
     let window_size = window.size();
-    let zoom_factor = if menu_state.is_in_hub_menu() {
+    let zoom_factor = if shows_hub_overview {
         INITIAL_HUB_ZOOM_FACTOR
     } else if event == Some(&UpdateBackgroundTransform::LevelExit) {
         (window_size.x / GRID_SIZE as f32).max(window_size.y / GRID_SIZE as f32)
     } else {
         ui_state.zoom_factor
     };
-    let (scale, translation) = calculate_background_transform_with_zoom_factor(
+    let editor_width = editor_state.width(&ui_scale);
+    let (scale, mut translation) = calculate_background_transform_with_zoom_factor(
         &dimensions,
-        &editor_state,
+        editor_width,
         focus_position,
         &ui_state,
         window_size,
         zoom_factor,
+        !photo_mode.is_active(),
     );
 
+    // Photo mode may look anywhere, but otherwise the center of the window is
+    // kept within the level.
+    if !photo_mode.is_active() {
+        let grid_size = GRID_SIZE as f32 * zoom_factor;
+        let center_x =
+            0.5 * dimensions.width as f32 + 0.5 - (translation.x + 0.5 * editor_width) / grid_size;
+        let center_y = 0.5 * dimensions.height as f32 + 0.5 + translation.y / grid_size;
+        let excess_x = center_x - center_x.clamp(0.5, dimensions.width as f32 + 0.5);
+        let excess_y = center_y - center_y.clamp(0.5, dimensions.height as f32 + 0.5);
+        if excess_x != 0. || excess_y != 0. {
+            ui_state.camera_offset.0 -= excess_x;
+            ui_state.camera_offset.1 -= excess_y;
+            translation.x += excess_x * grid_size;
+            translation.y -= excess_y * grid_size;
+        }
+    }
+
+    // The preview starts from the corner of the level that is furthest from
+    // the player, unless the whole level is visible from the player anyway.
+    let preview_start = if event == Some(&UpdateBackgroundTransform::LevelPreview) {
+        let corner = (
+            if 2 * player_position.x <= dimensions.width {
+                dimensions.width
+            } else {
+                1
+            },
+            if 2 * player_position.y <= dimensions.height {
+                dimensions.height
+            } else {
+                1
+            },
+        );
+        let (start_scale, start_translation) = calculate_background_transform_with_zoom_factor(
+            &dimensions,
+            editor_width,
+            corner,
+            &ui_state,
+            window_size,
+            zoom_factor,
+            true,
+        );
+        (start_translation.distance(translation) >= GRID_SIZE as f32 * zoom_factor)
+            .then_some((start_scale, start_translation))
+    } else {
+        None
+    };
+    if event == Some(&UpdateBackgroundTransform::LevelPreview) && preview_start.is_none() {
+        duration_ms = 400;
+    }
+    preview_state.is_active = preview_start.is_some();
+
     if duration_ms > 0 {
-        if event == Some(&UpdateBackgroundTransform::LevelEntrance) {
+        if let Some((start_scale, start_translation)) = preview_start {
+            *transform = Transform::from_scale(start_scale).with_translation(start_translation);
+        } else if matches!(
+            event,
+            Some(
+                UpdateBackgroundTransform::LevelEntrance | UpdateBackgroundTransform::LevelPreview
+            )
+        ) {
             let (start_scale, start_translation) = calculate_background_transform_with_zoom_factor(
                 &dimensions,
-                &editor_state,
+                editor_width,
                 (player_position.x, player_position.y),
                 &ui_state,
                 window_size,
                 (window_size.x / GRID_SIZE as f32).max(window_size.y / GRID_SIZE as f32),
+                true,
             );
             *transform = Transform::from_scale(start_scale).with_translation(start_translation);
         }
@@ -182,7 +445,13 @@ fn on_update_background_transform(
                 transform.translation,
                 translation,
                 match event {
-                    Some(UpdateBackgroundTransform::HubIntro) => EaseFunction::QuarticInOut,
+                    Some(
+                        UpdateBackgroundTransform::HubIntro
+                        | UpdateBackgroundTransform::LevelPreview
+                        | UpdateBackgroundTransform::FastTravel
+                        | UpdateBackgroundTransform::Credits
+                        | UpdateBackgroundTransform::Cutscene { .. },
+                    ) => EaseFunction::QuarticInOut,
                     Some(UpdateBackgroundTransform::LevelExit) => EaseFunction::QuarticIn,
                     _ => EaseFunction::QuarticOut,
                 },
@@ -196,10 +465,11 @@ fn on_update_background_transform(
 }
 
 #[expect(clippy::too_many_arguments)]
-fn on_background_transform_animation(
+pub fn on_background_transform_animation(
     mut commands: Commands,
     mut background_query: Query<&mut Transform, With<Background>>,
     mut animation: ResMut<BackgroundTransformAnimation>,
+    mut preview_state: ResMut<LevelPreviewState>,
     exit_state: Res<ExitState>,
     time: Res<Time<Virtual>>,
 ) {
@@ -224,6 +494,7 @@ fn on_background_transform_animation(
 
     if timer.finished() {
         *animation = BackgroundTransformAnimation::Paused;
+        preview_state.is_active = false;
 
         if let Some(next_level) = exit_state.next_level {
             commands.trigger(LoadLevel(next_level));
@@ -231,37 +502,97 @@ fn on_background_transform_animation(
     }
 }
 
+/// Hides the objects that are outside the window, so that huge levels don't
+/// need to render all their objects.
+fn cull_offscreen_objects(
+    background_query: Query<Ref<Transform>, With<Background>>,
+    mut objects_query: Query<(Ref<Position>, &mut Visibility)>,
+    window_query: Query<Ref<Window>>,
+    dimensions: Res<Dimensions>,
+) {
+    let (Ok(transform), Ok(window)) = (background_query.get_single(), window_query.get_single())
+    else {
+        return;
+    };
+
+    let view_changed = transform.is_changed() || window.is_changed() || dimensions.is_changed();
+
+    let grid_size = GRID_SIZE as f32 * transform.scale.x;
+    let half_width = 0.5 * window.width() / grid_size + CULLING_MARGIN;
+    let half_height = 0.5 * window.height() / grid_size + CULLING_MARGIN;
+
+    // The position at the center of the window, in grid coordinates.
+    let center_x = 0.5 * dimensions.width as f32 + 0.5 - transform.translation.x / grid_size;
+    let center_y = 0.5 * dimensions.height as f32 + 0.5 + transform.translation.y / grid_size;
+
+    for (position, mut visibility) in &mut objects_query {
+        if !view_changed && !position.is_changed() {
+            continue;
+        }
+
+        let visible = (position.x as f32 - center_x).abs() <= half_width
+            && (position.y as f32 - center_y).abs() <= half_height;
+        visibility.set_if_neq(if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+/// Calculates the scale and translation of the background.
+///
+/// Unless `is_clamped` is `false`, the camera is kept from moving beyond the
+/// edges of the level, and levels that fit in the window are centered.
+/// Returns the zoom factor at which the whole level fits in the given size,
+/// limited to the range levels start with.
+fn fitting_zoom_factor(dimensions: &Dimensions, available_size: Vec2) -> f32 {
+    let level_size = dimensions.size_in_pixels() + Vec2::splat(2. * FIT_MARGIN * GRID_SIZE as f32);
+    let fit = available_size / level_size;
+    fit.x
+        .min(fit.y)
+        .clamp(MIN_DEFAULT_ZOOM_FACTOR, MAX_DEFAULT_ZOOM_FACTOR)
+}
+
 fn calculate_background_transform_with_zoom_factor(
     dimensions: &Dimensions,
-    editor_state: &EditorState,
+    editor_width: f32,
     (focus_x, focus_y): (i16, i16),
     ui_state: &UiState,
     window_size: Vec2,
     zoom_factor: f32,
+    is_clamped: bool,
 ) -> (Vec3, Vec3) {
     let scale = Vec3::new(zoom_factor, zoom_factor, 1.);
 
-    let editor_open = editor_state.is_open;
-    let editor_width = if editor_open { EDITOR_WIDTH as f32 } else { 0. };
-    let level_width = (dimensions.width * GRID_SIZE) as f32 * zoom_factor;
-    let x = if level_width > window_size.x - editor_width {
-        let max = 0.5 * (level_width - (window_size.x - editor_width));
+    let level_size = dimensions.size_in_pixels() * zoom_factor;
+    let level_width = level_size.x;
+    let x = if !is_clamped || level_width > window_size.x - editor_width {
+        let max = if is_clamped {
+            0.5 * (level_width - (window_size.x - editor_width))
+        } else {
+            f32::INFINITY
+        };
         (zoom_factor * ((-focus_x as f32 + 0.5 * dimensions.width as f32) + 0.5) * GRID_SIZE as f32)
             .clamp(-max, max)
             - (zoom_factor * ui_state.camera_offset.0 * GRID_SIZE as f32)
     } else {
         0.
     };
-    let level_height = (dimensions.height * GRID_SIZE) as f32 * zoom_factor;
-    let y = if level_height > window_size.y {
-        let max = 0.5 * (level_height - window_size.y);
+    let level_height = level_size.y;
+    let y = if !is_clamped || level_height > window_size.y {
+        let max = if is_clamped {
+            0.5 * (level_height - window_size.y)
+        } else {
+            f32::INFINITY
+        };
         (zoom_factor * ((focus_y as f32 - 0.5 * dimensions.height as f32) - 0.5) * GRID_SIZE as f32)
             .clamp(-max, max)
             + (zoom_factor * ui_state.camera_offset.1 * GRID_SIZE as f32)
     } else {
         0.
     };
-    let translation = Vec3::new(x - if editor_open { 0.5 * editor_width } else { 0. }, y, 1.);
+    let translation = Vec3::new(x - 0.5 * editor_width, y, 1.);
 
     (scale, translation)
 }
@@ -0,0 +1,129 @@
+use bevy::{prelude::*, window::WindowMode};
+
+use crate::{game_object::Direction, levels::LEVELS};
+
+const USAGE: &str = "Usage: ChunkyChallenge [OPTIONS]
+
+Options:
+  --level <NUMBER>          Launch directly into the given level
+  --windowed                Start in windowed mode
+  --fullscreen              Start in (borderless) fullscreen mode
+  --resolution <WxH>        Initial window size, e.g. 1280x720
+  --seed <NUMBER>           Seed all randomness with the given number, to reproduce an attempt
+  --validate                Validate all levels and exit without opening a window
+  --simulate <LEVEL> <MOVES>
+                            Play a level with comma-separated moves, e.g. Up,Left,Left,
+                            and print the outcome without opening a window
+  -h, --help                Print this help";
+
+/// Options passed on the command line.
+#[derive(Clone, Default, Resource)]
+pub struct CliOptions {
+    /// Level to load on launch, instead of the hub.
+    pub level: Option<u16>,
+
+    /// Overrides the window mode we would otherwise pick for the platform.
+    pub window_mode: Option<WindowMode>,
+
+    /// Initial window size in pixels.
+    pub resolution: Option<(u16, u16)>,
+
+    /// Fixed seed for every attempt, instead of a random one.
+    pub seed: Option<u64>,
+
+    /// Validates the levels instead of launching the game.
+    pub validate: bool,
+
+    /// Level and moves to simulate instead of launching the game.
+    pub simulate: Option<(u16, Vec<Direction>)>,
+}
+
+impl CliOptions {
+    /// Parses the options from the process arguments.
+    ///
+    /// Exits the process if the arguments cannot be parsed.
+    pub fn from_args() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(options) => options,
+            Err(message) => {
+                eprintln!("{message}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--level" => {
+                    let value = args.next().ok_or("Missing value for --level")?;
+                    let level = value
+                        .parse()
+                        .map_err(|_| format!("Invalid level number: {value}"))?;
+                    if !LEVELS.iter().any(|(number, _)| *number == level) {
+                        return Err(format!("Unknown level: {level}"));
+                    }
+                    options.level = Some(level);
+                }
+                "--windowed" => options.window_mode = Some(WindowMode::Windowed),
+                "--fullscreen" => {
+                    options.window_mode =
+                        Some(WindowMode::BorderlessFullscreen(MonitorSelection::Current))
+                }
+                "--resolution" => {
+                    let value = args.next().ok_or("Missing value for --resolution")?;
+                    let resolution = parse_resolution(&value)
+                        .ok_or_else(|| format!("Invalid resolution: {value}"))?;
+                    options.resolution = Some(resolution);
+                }
+                "--seed" => {
+                    let value = args.next().ok_or("Missing value for --seed")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("Invalid seed: {value}"))?;
+                    options.seed = Some(seed);
+                }
+                "--validate" => options.validate = true,
+                "--simulate" => {
+                    let level = args.next().ok_or("Missing level for --simulate")?;
+                    let level = level
+                        .parse()
+                        .map_err(|_| format!("Invalid level number: {level}"))?;
+                    if !LEVELS.iter().any(|(number, _)| *number == level) {
+                        return Err(format!("Unknown level: {level}"));
+                    }
+                    let moves = args.next().ok_or("Missing moves for --simulate")?;
+                    let moves = moves
+                        .split(',')
+                        .map(|direction| {
+                            direction
+                                .trim()
+                                .parse()
+                                .map_err(|_| format!("Invalid move: {direction}"))
+                        })
+                        .collect::<Result<_, _>>()?;
+                    options.simulate = Some((level, moves));
+                }
+                "-h" | "--help" => {
+                    println!("{USAGE}");
+                    std::process::exit(0);
+                }
+                // Platforms may pass arguments of their own, so we don't want
+                // to refuse starting because of them.
+                _ => eprintln!("Ignoring unknown argument: {arg}"),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_resolution(value: &str) -> Option<(u16, u16)> {
+    let (width, height) = value.split_once('x')?;
+    match (width.parse(), height.parse()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ => None,
+    }
+}
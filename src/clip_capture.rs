@@ -0,0 +1,275 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+};
+use image::RgbImage;
+
+use crate::{game_state::GameState, screenshot::screenshots_dir, toast::ShowToast};
+
+/// Time between the frames that are kept for the clip.
+const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of frames in a clip, which makes for about 10 seconds.
+const MAX_FRAMES: usize = 100;
+
+/// Maximum width of the frames in a clip. Frames are downscaled to keep both
+/// the buffer and the resulting files small.
+const MAX_FRAME_WIDTH: u32 = 320;
+
+/// Keeps the most recent frames around, so the last 10 seconds can be saved
+/// as an animated GIF by pressing F10.
+#[derive(Resource)]
+struct ClipCapture {
+    frames: VecDeque<RgbImage>,
+    timer: Timer,
+    is_capturing: bool,
+    results_sender: Sender<Result<PathBuf, String>>,
+    results: Mutex<Receiver<Result<PathBuf, String>>>,
+}
+
+impl Default for ClipCapture {
+    fn default() -> Self {
+        let (results_sender, results) = mpsc::channel();
+        Self {
+            frames: VecDeque::with_capacity(MAX_FRAMES),
+            timer: Timer::new(FRAME_INTERVAL, TimerMode::Repeating),
+            is_capturing: false,
+            results_sender,
+            results: Mutex::new(results),
+        }
+    }
+}
+
+pub struct ClipCapturePlugin;
+
+impl Plugin for ClipCapturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClipCapture>()
+            .add_systems(PreUpdate, on_clip_input.after(InputSystem))
+            .add_systems(Update, (capture_frame, show_saved_clips));
+    }
+}
+
+fn capture_frame(mut commands: Commands, mut capture: ResMut<ClipCapture>, time: Res<Time>) {
+    capture.timer.tick(time.delta());
+    if !capture.timer.just_finished() || capture.is_capturing {
+        return;
+    }
+
+    capture.is_capturing = true;
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(on_frame_captured);
+}
+
+fn on_frame_captured(trigger: Trigger<ScreenshotCaptured>, mut capture: ResMut<ClipCapture>) {
+    capture.is_capturing = false;
+
+    let Ok(image) = trigger.event().0.clone().try_into_dynamic() else {
+        return;
+    };
+
+    let frame = image
+        .thumbnail(
+            MAX_FRAME_WIDTH,
+            MAX_FRAME_WIDTH * image.height() / image.width().max(1),
+        )
+        .to_rgb8();
+
+    // A resized window would result in frames of different sizes.
+    if capture
+        .frames
+        .front()
+        .is_some_and(|first| first.dimensions() != frame.dimensions())
+    {
+        capture.frames.clear();
+    }
+
+    if capture.frames.len() == MAX_FRAMES {
+        capture.frames.pop_front();
+    }
+    capture.frames.push_back(frame);
+}
+
+/// Saves the buffered frames as a clip when F10 is pressed.
+///
+/// Encoding happens on a separate thread, so the game doesn't stutter while
+/// the clip is being saved.
+fn on_clip_input(
+    capture: Res<ClipCapture>,
+    game_state: Res<GameState>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if !keys.just_pressed(KeyCode::F10) || capture.frames.is_empty() {
+        return;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_millis())
+        .unwrap_or_default();
+    let path = screenshots_dir().join(format!(
+        "level{:03}-{timestamp}.gif",
+        game_state.current_level
+    ));
+
+    let frames: Vec<RgbImage> = capture.frames.iter().cloned().collect();
+    let results_sender = capture.results_sender.clone();
+    thread::spawn(move || {
+        let result = fs::write(&path, encode_gif(&frames))
+            .map(|()| path)
+            .map_err(|err| err.to_string());
+        let _ = results_sender.send(result);
+    });
+}
+
+fn show_saved_clips(mut commands: Commands, capture: Res<ClipCapture>) {
+    let Ok(results) = capture.results.lock() else {
+        return;
+    };
+
+    for result in results.try_iter() {
+        commands.trigger(ShowToast::new(match result {
+            Ok(path) => format!("Clip saved to {}", path.display()),
+            Err(err) => format!("Can't save clip: {err}"),
+        }));
+    }
+}
+
+/// Encodes the given frames, which must all have the same size, as a looping
+/// GIF animation.
+///
+/// Colors are mapped onto a fixed palette of 6 levels per channel, which is
+/// good enough for the flat colors of the game.
+fn encode_gif(frames: &[RgbImage]) -> Vec<u8> {
+    let (width, height) = frames.first().map(RgbImage::dimensions).unwrap_or_default();
+    let delay = (FRAME_INTERVAL.as_millis() / 10) as u16;
+
+    let mut gif = Vec::new();
+    gif.extend_from_slice(b"GIF89a");
+    gif.extend_from_slice(&(width as u16).to_le_bytes());
+    gif.extend_from_slice(&(height as u16).to_le_bytes());
+    // Global color table with 256 entries, of which 216 are used.
+    gif.extend_from_slice(&[0xf7, 0, 0]);
+    for index in 0..=255u8 {
+        let (r, g, b) = if index < 216 {
+            (index / 36, index / 6 % 6, index % 6)
+        } else {
+            (0, 0, 0)
+        };
+        gif.extend_from_slice(&[r * 51, g * 51, b * 51]);
+    }
+
+    // Loop forever.
+    gif.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        gif.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0x00, 0x00]);
+
+        gif.push(0x2c);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&(width as u16).to_le_bytes());
+        gif.extend_from_slice(&(height as u16).to_le_bytes());
+        gif.push(0x00);
+
+        let indices: Vec<u8> = frame
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0.map(|channel| (channel as u16 * 5 + 127) / 255);
+                (r * 36 + g * 6 + b) as u8
+            })
+            .collect();
+
+        gif.push(8);
+        for block in lzw_compress(&indices).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0x00);
+    }
+
+    gif.push(0x3b);
+    gif
+}
+
+/// Compresses 8-bit color indices using the variable-length LZW coding used
+/// by GIF.
+fn lzw_compress(indices: &[u8]) -> Vec<u8> {
+    const CLEAR_CODE: u16 = 256;
+    const END_CODE: u16 = 257;
+    const MAX_CODE: u16 = 4096;
+
+    let mut output = Vec::new();
+    let mut bit_buffer = 0u32;
+    let mut num_bits = 0u32;
+    let mut write_code = |code: u16, code_size: u32, output: &mut Vec<u8>| {
+        bit_buffer |= (code as u32) << num_bits;
+        num_bits += code_size;
+        while num_bits >= 8 {
+            output.push(bit_buffer as u8);
+            bit_buffer >>= 8;
+            num_bits -= 8;
+        }
+    };
+
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = END_CODE + 1;
+    let mut code_size = 9;
+    write_code(CLEAR_CODE, code_size, &mut output);
+
+    let Some((first, rest)) = indices.split_first() else {
+        write_code(END_CODE, code_size, &mut output);
+        return output;
+    };
+
+    let mut prefix = *first as u16;
+    for index in rest {
+        if let Some(code) = dictionary.get(&(prefix, *index)) {
+            prefix = *code;
+            continue;
+        }
+
+        write_code(prefix, code_size, &mut output);
+        if next_code == MAX_CODE {
+            write_code(CLEAR_CODE, code_size, &mut output);
+            dictionary.clear();
+            next_code = END_CODE + 1;
+            code_size = 9;
+        } else {
+            // Codes get wider once the dictionary outgrows them, which
+            // decoders only notice after reading the code that follows.
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            dictionary.insert((prefix, *index), next_code);
+            next_code += 1;
+        }
+        prefix = *index as u16;
+    }
+
+    write_code(prefix, code_size, &mut output);
+    if next_code == 1 << code_size && code_size < 12 {
+        code_size += 1;
+    }
+    write_code(END_CODE, code_size, &mut output);
+    if num_bits > 0 {
+        output.push(bit_buffer as u8);
+    }
+
+    output
+}
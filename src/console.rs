@@ -0,0 +1,261 @@
+use std::collections::VecDeque;
+
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+use crate::{
+    background::UpdateBackgroundTransform,
+    constants::*,
+    fonts::Fonts,
+    game_object::{ObjectType, Player, Position, TileIndex},
+    game_state::GameState,
+    levels::{Dimensions, InitialPositionAndMetadata},
+    settings::Settings,
+    setup, LoadLevel, SpawnObject,
+};
+
+/// Maximum number of output lines that are kept in the console.
+const MAX_OUTPUT_LINES: usize = 10;
+
+const HELP: &str = "Commands:
+  level <number> - Loads the given level
+  spawn <type> <x>,<y> - Spawns an object
+  give-finish <number> - Marks the given level as finished
+  tp <x>,<y> - Teleports the player
+  clear - Clears the console";
+
+/// Drop-down console for debugging, toggled with the `~` key.
+///
+/// All keyboard input is consumed while the console is open.
+#[derive(Default, Resource)]
+struct DebugConsole {
+    is_open: bool,
+    input: String,
+    output: VecDeque<String>,
+}
+
+impl DebugConsole {
+    fn print(&mut self, line: impl Into<String>) {
+        for line in line.into().lines() {
+            if self.output.len() == MAX_OUTPUT_LINES {
+                self.output.pop_front();
+            }
+            self.output.push_back(line.to_owned());
+        }
+    }
+}
+
+enum ConsoleCommand {
+    Clear,
+    GiveFinish(u16),
+    Help,
+    Level(u16),
+    Spawn(ObjectType, Position),
+    Teleport(Position),
+}
+
+impl ConsoleCommand {
+    fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+
+        match (command, args.as_slice()) {
+            ("clear", []) => Ok(Self::Clear),
+            ("give-finish", [level]) => parse_level(level).map(Self::GiveFinish),
+            ("help", []) => Ok(Self::Help),
+            ("level", [level]) => parse_level(level).map(Self::Level),
+            ("spawn", [object_type, position]) => Ok(Self::Spawn(
+                object_type
+                    .parse()
+                    .map_err(|_| format!("Unknown object type: {object_type}"))?,
+                parse_position(position)?,
+            )),
+            ("tp", [position]) => parse_position(position).map(Self::Teleport),
+            ("clear" | "give-finish" | "help" | "level" | "spawn" | "tp", _) => Err(format!(
+                "Invalid arguments for {command}, type help for usage"
+            )),
+            _ => Err(format!("Unknown command: {command}")),
+        }
+    }
+}
+
+fn parse_level(value: &str) -> Result<u16, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Cannot parse level number: {value}"))
+}
+
+fn parse_position(value: &str) -> Result<Position, String> {
+    value
+        .split_once(',')
+        .and_then(|(x, y)| {
+            Some(Position {
+                x: x.trim().parse().ok()?,
+                y: y.trim().parse().ok()?,
+            })
+        })
+        .ok_or_else(|| format!("Invalid position: {value}"))
+}
+
+#[derive(Component)]
+struct ConsolePanel;
+
+#[derive(Component)]
+struct ConsoleText;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugConsole>()
+            .add_systems(Startup, setup_console.after(setup))
+            .add_systems(PreUpdate, on_console_input.after(InputSystem))
+            .add_systems(Update, render_console);
+    }
+}
+
+fn setup_console(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            ConsolePanel,
+            BackgroundColor(GRAY_BACKGROUND.with_alpha(0.9)),
+            BorderColor(RED),
+            GlobalZIndex(150),
+            Node {
+                display: Display::None,
+                width: Val::Percent(100.),
+                border: UiRect::bottom(Val::Px(2.)),
+                padding: UiRect::all(Val::Px(10.)),
+                top: Val::Px(0.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                ConsoleText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+            ));
+        });
+}
+
+#[expect(clippy::too_many_arguments)]
+fn on_console_input(
+    mut commands: Commands,
+    mut player_query: Query<(Entity, &mut Position), With<Player>>,
+    mut console: ResMut<DebugConsole>,
+    mut game_state: ResMut<GameState>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut tile_index: ResMut<TileIndex>,
+    dimensions: Res<Dimensions>,
+    settings: Res<Settings>,
+) {
+    if !cfg!(debug_assertions) && !settings.debug_console {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Backquote) {
+        console.is_open = !console.is_open;
+        keyboard_events.clear();
+        keys.reset_all();
+        return;
+    }
+
+    if !console.is_open {
+        // Typing from before the console was opened shouldn't end up in it.
+        keyboard_events.clear();
+        return;
+    }
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(characters) => console.input.push_str(characters),
+            Key::Space => console.input.push(' '),
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Escape => console.is_open = false,
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                console.print(format!("> {line}"));
+                match ConsoleCommand::parse(&line) {
+                    Ok(ConsoleCommand::Clear) => console.output.clear(),
+                    Ok(ConsoleCommand::GiveFinish(level)) => {
+                        game_state.mark_level_finished(level);
+                        console.print(format!("Level {level} marked as finished"));
+                    }
+                    Ok(ConsoleCommand::Help) => console.print(HELP),
+                    Ok(ConsoleCommand::Level(level)) => commands.trigger(LoadLevel(level)),
+                    Ok(ConsoleCommand::Spawn(_, position)) if !dimensions.contains(position) => {
+                        console.print("Position is outside of the level");
+                    }
+                    Ok(ConsoleCommand::Spawn(object_type, position)) => {
+                        commands.trigger(SpawnObject {
+                            object_type,
+                            position: InitialPositionAndMetadata::from(&position),
+                        });
+                    }
+                    Ok(ConsoleCommand::Teleport(position)) if !dimensions.contains(position) => {
+                        console.print("Position is outside of the level");
+                    }
+                    Ok(ConsoleCommand::Teleport(position)) => {
+                        for (entity, mut player_position) in &mut player_query {
+                            *player_position = position;
+                            tile_index.insert(entity, position);
+                        }
+                        commands.send_event(UpdateBackgroundTransform::Fast);
+                    }
+                    Err(message) => console.print(message),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // None of the keys used for typing should reach the game.
+    keys.reset_all();
+}
+
+fn render_console(
+    mut panel_query: Query<&mut Node, With<ConsolePanel>>,
+    mut text_query: Query<&mut Text, With<ConsoleText>>,
+    console: Res<DebugConsole>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    for mut node in &mut panel_query {
+        node.display = if console.is_open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    for mut text in &mut text_query {
+        text.0.clear();
+        for line in &console.output {
+            text.0.push_str(line);
+            text.0.push('\n');
+        }
+        text.0.push_str(&format!("> {}|", console.input));
+    }
+}
@@ -14,3 +14,5 @@ pub const NORMAL_GRAY: Color = Color::srgb(0.12156863, 0.16078432, 0.21568628);
 pub const RED: Color = Color::srgb(0.776, 0., 0.);
 pub const WHITE: Color = Color::srgb(1., 1., 1.);
 pub const ENTRANCE_TEXT: Color = Color::srgb(0.737, 0.38, 0.125);
+pub const LOCKED_ENTRANCE: Color = Color::srgb(0.4, 0.4, 0.4);
+pub const SKIPPED_ENTRANCE: Color = Color::srgb(0.6, 0.6, 1.);
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    background::{BackgroundTransformAnimation, UpdateBackgroundTransform},
+    constants::*,
+    fonts::Fonts,
+    game_state::GameState,
+    on_keyboard_input, on_mouse_input, setup, ExitState,
+};
+
+/// How long it takes for the credits to scroll past.
+const CREDITS_DURATION: Duration = Duration::from_secs(15);
+
+const CREDITS_TEXT: &str = "Congratulations!\n\n\
+    You have finished every level of\nChunky's Challenge\n\n\
+    100%\n\n\n\
+    A game by\nCouch Chilis\n\n\n\
+    Thanks for playing!";
+
+/// Plays the end-game sequence, after the player has finished all levels.
+#[derive(Event)]
+pub struct PlayCredits;
+
+#[derive(Default, Resource)]
+pub enum CreditsState {
+    #[default]
+    Idle,
+
+    /// The credits will start playing once the player is back in the hub.
+    Pending,
+
+    Playing {
+        timer: Timer,
+    },
+}
+
+impl CreditsState {
+    pub fn is_playing(&self) -> bool {
+        matches!(self, Self::Playing { .. })
+    }
+}
+
+#[derive(Component)]
+struct Credits;
+
+#[derive(Component)]
+struct CreditsText;
+
+pub struct CreditsPlugin;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_credits.after(setup))
+            .init_resource::<CreditsState>()
+            .add_event::<PlayCredits>()
+            .add_observer(on_play_credits)
+            .add_systems(
+                Update,
+                (start_credits, scroll_credits)
+                    .chain()
+                    .before(on_keyboard_input)
+                    .before(on_mouse_input),
+            );
+    }
+}
+
+fn setup_credits(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            Credits,
+            BackgroundColor(GRAY_BACKGROUND.with_alpha(0.6)),
+            GlobalZIndex(150),
+            Node {
+                display: Display::None,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                overflow: Overflow::clip(),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                CreditsText,
+                Text::new(CREDITS_TEXT),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(36.),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    width: Val::Percent(100.),
+                    top: Val::Percent(100.),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn on_play_credits(_trigger: Trigger<PlayCredits>, mut credits_state: ResMut<CreditsState>) {
+    *credits_state = CreditsState::Pending;
+}
+
+fn start_credits(
+    mut credits_query: Query<&mut Node, With<Credits>>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut credits_state: ResMut<CreditsState>,
+    animation: Res<BackgroundTransformAnimation>,
+    exit_state: Res<ExitState>,
+    game_state: Res<GameState>,
+) {
+    if !matches!(*credits_state, CreditsState::Pending)
+        || !game_state.is_in_hub()
+        || exit_state.next_level.is_some()
+        || !matches!(*animation, BackgroundTransformAnimation::Paused)
+    {
+        return;
+    }
+
+    for mut node in &mut credits_query {
+        node.display = Display::Flex;
+    }
+
+    background_events.send(UpdateBackgroundTransform::Credits);
+    *credits_state = CreditsState::Playing {
+        timer: Timer::new(CREDITS_DURATION, TimerMode::Once),
+    };
+}
+
+#[expect(clippy::too_many_arguments, clippy::type_complexity)]
+fn scroll_credits(
+    mut credits_query: Query<(&mut Node, &ComputedNode), With<Credits>>,
+    mut text_query: Query<(&mut Node, &ComputedNode), (With<CreditsText>, Without<Credits>)>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut credits_state: ResMut<CreditsState>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    time: Res<Time>,
+) {
+    let CreditsState::Playing { timer } = credits_state.as_mut() else {
+        return;
+    };
+
+    timer.tick(time.delta());
+
+    let Ok((mut credits_node, credits_computed_node)) = credits_query.get_single_mut() else {
+        return;
+    };
+
+    // The input that skips the credits is consumed, so it doesn't also reach
+    // the regular input handling.
+    let skipped = keys.clear_just_pressed(KeyCode::Enter)
+        | keys.clear_just_pressed(KeyCode::Escape)
+        | mouse_buttons.clear_just_pressed(MouseButton::Left)
+        | touches.any_just_pressed();
+
+    if timer.finished() || skipped {
+        credits_node.display = Display::None;
+        *credits_state = CreditsState::Idle;
+
+        // Return the focus to the player.
+        background_events.send(UpdateBackgroundTransform::Fast);
+        return;
+    }
+
+    // Scroll the text from just below the bottom of the window until it has
+    // disappeared at the top.
+    let window_height = credits_computed_node.size().y;
+    for (mut text_node, text_computed_node) in &mut text_query {
+        let text_height = text_computed_node.size().y;
+        text_node.top = Val::Px(window_height - timer.fraction() * (window_height + text_height));
+    }
+}
@@ -0,0 +1,302 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy::prelude::*;
+
+use crate::{
+    background::{BackgroundTransformAnimation, UpdateBackgroundTransform},
+    credits::CreditsState,
+    dialogue::{on_dialogue_input, DialogueState},
+    editor::EditorState,
+    game_object::{Position, TileIndex},
+    game_state::GameState,
+    menu::MenuState,
+    on_keyboard_input, ExitState,
+};
+
+const CUTSCENES: &str = include_str!("../assets/cutscenes.txt");
+
+/// Delay after moving an object, so the player can see what happened.
+const MOVE_DELAY: Duration = Duration::from_millis(300);
+
+/// A scripted sequence that plays in the hub.
+///
+/// Cutscenes are defined in `assets/cutscenes.txt`, with a section for every
+/// cutscene. Every line in a section is a step, except for the `After` key.
+/// The following keys are supported:
+/// - `After=<level>` plays the cutscene after the given level is finished.
+///   Cutscenes without it play when the player first enters the hub.
+/// - `Pan=<x>,<y>;<milliseconds>` pans the camera to the given position.
+/// - `Say=<text>` shows a dialogue box and waits until it's closed. Use `\n`
+///   for line breaks, and an empty line to start a new page.
+/// - `Move=<x>,<y>;<x>,<y>` moves the top-most object from the first position
+///   to the second.
+/// - `Wait=<milliseconds>` waits before continuing with the next step.
+pub struct Cutscene {
+    name: String,
+    after_level: Option<u16>,
+    steps: Vec<CutsceneStep>,
+}
+
+#[derive(Clone)]
+pub enum CutsceneStep {
+    Pan { focus: Position, duration: Duration },
+    Say(String),
+    Move { from: Position, to: Position },
+    Wait(Duration),
+}
+
+#[derive(Resource)]
+pub struct Cutscenes(Vec<Cutscene>);
+
+impl Default for Cutscenes {
+    fn default() -> Self {
+        let (cutscenes, warnings) = Cutscene::parse(CUTSCENES);
+        for warning in warnings {
+            warn!("Cutscenes: {warning}");
+        }
+
+        Self(cutscenes)
+    }
+}
+
+#[derive(Default, Resource)]
+pub enum CutsceneState {
+    #[default]
+    Idle,
+    Playing {
+        steps: VecDeque<CutsceneStep>,
+        timer: Timer,
+    },
+}
+
+impl CutsceneState {
+    pub fn is_playing(&self) -> bool {
+        matches!(self, Self::Playing { .. })
+    }
+}
+
+pub struct CutscenePlugin;
+
+impl Plugin for CutscenePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Cutscenes>()
+            .init_resource::<CutsceneState>()
+            .add_systems(
+                Update,
+                on_cutscene_input
+                    .before(on_dialogue_input)
+                    .before(on_keyboard_input),
+            )
+            .add_systems(Update, (start_cutscene, run_cutscene).chain());
+    }
+}
+
+impl Cutscene {
+    /// Parses the cutscenes from the given file contents.
+    ///
+    /// Returns the cutscenes together with the problems encountered while
+    /// parsing them.
+    fn parse(content: &str) -> (Vec<Self>, Vec<String>) {
+        let mut cutscenes: Vec<Self> = Vec::new();
+        let mut warnings = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+
+            if line.starts_with('[') && line.ends_with(']') {
+                cutscenes.push(Self {
+                    name: line[1..line.len() - 1].to_owned(),
+                    after_level: None,
+                    steps: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let Some(cutscene) = cutscenes.last_mut() else {
+                warnings.push(format!("Step outside of a cutscene: {line}"));
+                continue;
+            };
+
+            let step = match key {
+                "After" => match value.parse() {
+                    Ok(level) => {
+                        cutscene.after_level = Some(level);
+                        continue;
+                    }
+                    Err(_) => Err(format!("Cannot parse level number: {value}")),
+                },
+                "Pan" => parse_position_and_duration(value)
+                    .map(|(focus, duration)| CutsceneStep::Pan { focus, duration }),
+                "Say" => Ok(CutsceneStep::Say(value.replace("\\n", "\n"))),
+                "Move" => value
+                    .split_once(';')
+                    .and_then(|(from, to)| Some((parse_position(from)?, parse_position(to)?)))
+                    .map(|(from, to)| CutsceneStep::Move { from, to })
+                    .ok_or_else(|| format!("Invalid move: {value}")),
+                "Wait" => parse_duration(value)
+                    .map(CutsceneStep::Wait)
+                    .ok_or_else(|| format!("Invalid duration: {value}")),
+                _ => Err(format!("Unknown key: {key}")),
+            };
+
+            match step {
+                Ok(step) => cutscene.steps.push(step),
+                Err(warning) => warnings.push(format!("{} - {warning}", cutscene.name)),
+            }
+        }
+
+        (cutscenes, warnings)
+    }
+}
+
+fn parse_position(value: &str) -> Option<Position> {
+    let (x, y) = value.split_once(',')?;
+    Some(Position {
+        x: x.trim().parse().ok()?,
+        y: y.trim().parse().ok()?,
+    })
+}
+
+fn parse_duration(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_millis)
+}
+
+fn parse_position_and_duration(value: &str) -> Result<(Position, Duration), String> {
+    value
+        .split_once(';')
+        .and_then(|(position, duration)| {
+            Some((parse_position(position)?, parse_duration(duration)?))
+        })
+        .ok_or_else(|| format!("Invalid pan: {value}"))
+}
+
+/// Starts the first cutscene that is due, once the player is free to walk
+/// around the hub.
+#[expect(clippy::too_many_arguments)]
+fn start_cutscene(
+    mut cutscene_state: ResMut<CutsceneState>,
+    mut game_state: ResMut<GameState>,
+    animation: Res<BackgroundTransformAnimation>,
+    credits_state: Res<CreditsState>,
+    cutscenes: Res<Cutscenes>,
+    editor_state: Res<EditorState>,
+    exit_state: Res<ExitState>,
+    menu_state: Res<MenuState>,
+) {
+    if cutscene_state.is_playing()
+        || !game_state.is_in_hub()
+        || editor_state.is_open
+        || menu_state.is_open()
+        || exit_state.next_level.is_some()
+        || !matches!(*credits_state, CreditsState::Idle)
+        || !matches!(*animation, BackgroundTransformAnimation::Paused)
+    {
+        return;
+    }
+
+    let Some(cutscene) = cutscenes.0.iter().find(|cutscene| {
+        !game_state.has_seen_cutscene(&cutscene.name)
+            && cutscene
+                .after_level
+                .is_none_or(|level| game_state.finished_levels.contains(&level))
+    }) else {
+        return;
+    };
+
+    // Skipping a cutscene counts as having seen it.
+    game_state.mark_cutscene_seen(&cutscene.name);
+
+    *cutscene_state = CutsceneState::Playing {
+        steps: cutscene.steps.iter().cloned().collect(),
+        timer: Timer::default(),
+    };
+}
+
+fn run_cutscene(
+    mut objects_query: Query<(Entity, &mut Position, &Transform)>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut cutscene_state: ResMut<CutsceneState>,
+    mut dialogue_state: ResMut<DialogueState>,
+    mut tile_index: ResMut<TileIndex>,
+    time: Res<Time>,
+) {
+    let CutsceneState::Playing { steps, timer } = cutscene_state.as_mut() else {
+        return;
+    };
+
+    timer.tick(time.delta());
+    if !timer.finished() || dialogue_state.is_open() {
+        return;
+    }
+
+    let Some(step) = steps.pop_front() else {
+        *cutscene_state = CutsceneState::Idle;
+
+        // Return the focus to the player.
+        background_events.send(UpdateBackgroundTransform::Fast);
+        return;
+    };
+
+    let delay = match step {
+        CutsceneStep::Pan { focus, duration } => {
+            background_events.send(UpdateBackgroundTransform::Cutscene {
+                focus: (focus.x, focus.y),
+                duration_ms: duration.as_millis() as u64,
+            });
+            duration
+        }
+        CutsceneStep::Say(text) => {
+            dialogue_state.open(&text);
+            Duration::ZERO
+        }
+        CutsceneStep::Move { from, to } => {
+            let top_most = objects_query
+                .iter_many(tile_index.get(from))
+                .max_by(|(_, _, a), (_, _, b)| a.translation.z.total_cmp(&b.translation.z))
+                .map(|(entity, ..)| entity);
+            if let Some(entity) = top_most {
+                if let Ok((_, mut position, _)) = objects_query.get_mut(entity) {
+                    *position = to;
+                    tile_index.insert(entity, to);
+                }
+            }
+            MOVE_DELAY
+        }
+        CutsceneStep::Wait(duration) => duration,
+    };
+
+    *timer = Timer::new(delay, TimerMode::Once);
+}
+
+/// Skips the cutscene when Escape is pressed, and otherwise keeps the player
+/// from moving while it plays.
+///
+/// Input for paging through the dialogue of the cutscene is left alone.
+fn on_cutscene_input(
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut cutscene_state: ResMut<CutsceneState>,
+    mut dialogue_state: ResMut<DialogueState>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    if !cutscene_state.is_playing() {
+        return;
+    }
+
+    if keys.clear_just_pressed(KeyCode::Escape) {
+        *cutscene_state = CutsceneState::Idle;
+        dialogue_state.close();
+        background_events.send(UpdateBackgroundTransform::Fast);
+        return;
+    }
+
+    if !dialogue_state.is_open() {
+        let pressed_keys: Vec<KeyCode> = keys.get_just_pressed().copied().collect();
+        for key in pressed_keys {
+            keys.clear_just_pressed(key);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    background::{on_background_transform_animation, Background},
+    editor::EditorState,
+    game_state::GameState,
+    levels::Dimensions,
+    settings::Settings,
+};
+
+/// Duration of a full day in the hub, in seconds.
+const DAY_DURATION: f32 = 600.;
+
+/// Tints of the hub throughout the day, all spaced evenly. Daytime has no
+/// tint, so the hub looks just like it does without the cycle.
+const TINTS: [Srgba; 4] = [
+    Srgba::new(1., 1., 1., 0.),
+    Srgba::new(1., 0.55, 0.2, 0.15),
+    Srgba::new(0.05, 0.1, 0.35, 0.35),
+    Srgba::new(1., 0.6, 0.7, 0.12),
+];
+
+/// Amount by which the tint is above the level objects.
+const TINT_Z_OFFSET: f32 = 10.;
+
+/// The tint of the hub for the current time of day.
+#[derive(Default, Resource)]
+pub struct AmbientTint(pub Color);
+
+/// Covers the level to tint the background and the objects on it alike, so
+/// the tint doesn't interfere with the colors of individual sprites.
+#[derive(Component)]
+struct AmbientTintOverlay;
+
+pub struct DayNightPlugin;
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AmbientTint>()
+            .add_systems(Startup, setup_day_night)
+            .add_systems(
+                Update,
+                (update_ambient_tint, render_ambient_tint)
+                    .chain()
+                    .after(on_background_transform_animation),
+            );
+    }
+}
+
+fn setup_day_night(mut commands: Commands) {
+    commands.spawn((
+        AmbientTintOverlay,
+        Sprite::from_color(Color::NONE, Vec2::ZERO),
+        Visibility::Hidden,
+    ));
+}
+
+/// Moves through the tints of the day, using real time so the cycle doesn't
+/// stop while the game is paused.
+fn update_ambient_tint(mut tint: ResMut<AmbientTint>, time: Res<Time<Real>>) {
+    let phase = (time.elapsed_secs() / DAY_DURATION).fract() * TINTS.len() as f32;
+    let index = phase as usize % TINTS.len();
+    let next_index = (index + 1) % TINTS.len();
+
+    // Smoothed, so each time of day lingers before fading into the next.
+    let t = phase.fract();
+    let t = t * t * (3. - 2. * t);
+    tint.0 = TINTS[index].mix(&TINTS[next_index], t).into();
+}
+
+/// Shows the tint over the hub, unless the player wants to avoid cosmetic
+/// motion. The editor always shows the level as it is.
+fn render_ambient_tint(
+    background_query: Query<&Transform, (With<Background>, Without<AmbientTintOverlay>)>,
+    mut overlay_query: Query<
+        (&mut Sprite, &mut Transform, &mut Visibility),
+        With<AmbientTintOverlay>,
+    >,
+    dimensions: Res<Dimensions>,
+    editor_state: Res<EditorState>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+    tint: Res<AmbientTint>,
+) {
+    let (Ok(background_transform), Ok((mut sprite, mut transform, mut visibility))) = (
+        background_query.get_single(),
+        overlay_query.get_single_mut(),
+    ) else {
+        return;
+    };
+
+    let is_shown = game_state.is_in_hub() && !editor_state.is_open && !settings.reduce_motion;
+    visibility.set_if_neq(if is_shown {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    if !is_shown {
+        return;
+    }
+
+    sprite.color = tint.0;
+    sprite.custom_size = Some(dimensions.size_in_pixels());
+    *transform = background_transform
+        .with_translation(background_transform.translation + Vec3::new(0., 0., TINT_Z_OFFSET));
+}
@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    fonts::Fonts,
+    game_object::{Npc, Player, Position, TileIndex},
+    input_prompt::InputPrompt,
+    on_game_event, on_keyboard_input, on_mouse_input, setup, GameEvent, LoadLevel,
+};
+
+/// Dialogue of the NPC the player has bumped into, or of a cutscene.
+#[derive(Default, Resource)]
+pub struct DialogueState {
+    pages: Vec<String>,
+    current_page: usize,
+}
+
+impl DialogueState {
+    pub fn is_open(&self) -> bool {
+        !self.pages.is_empty()
+    }
+
+    /// Opens a dialogue with the given text, which is split into pages by
+    /// empty lines.
+    ///
+    /// Nothing is opened if the text is empty.
+    pub fn open(&mut self, text: &str) {
+        *self = Self {
+            pages: text
+                .split("\n\n")
+                .map(str::trim)
+                .filter(|page| !page.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            current_page: 0,
+        };
+    }
+
+    pub fn close(&mut self) {
+        *self = Self::default();
+    }
+
+    fn previous_page(&mut self) {
+        self.current_page = self.current_page.saturating_sub(1);
+    }
+
+    /// Goes to the next page, or closes the dialogue after the last page.
+    fn next_page(&mut self) {
+        if self.current_page + 1 < self.pages.len() {
+            self.current_page += 1;
+        } else {
+            self.close();
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct DialogueBox;
+
+#[derive(Component)]
+struct DialogueText;
+
+#[derive(Component)]
+struct DialogueHint;
+
+pub struct DialoguePlugin;
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_dialogue_box.after(setup))
+            .init_resource::<DialogueState>()
+            .add_observer(on_level_loaded)
+            .add_systems(
+                Update,
+                on_dialogue_input
+                    .before(on_keyboard_input)
+                    .before(on_mouse_input),
+            )
+            .add_systems(
+                Update,
+                open_dialogue.after(on_keyboard_input).before(on_game_event),
+            )
+            .add_systems(Update, render_dialogue_box.after(open_dialogue));
+    }
+}
+
+fn setup_dialogue_box(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            DialogueBox,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Px(500.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::horizontal(Val::Auto),
+                padding: UiRect::all(Val::Px(16.)),
+                row_gap: Val::Px(10.),
+                bottom: Val::Px(180.),
+                left: Val::Px(0.),
+                right: Val::Px(0.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                DialogueText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            cb.spawn((
+                DialogueHint,
+                InputPrompt::new(""),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+fn on_level_loaded(_trigger: Trigger<LoadLevel>, mut dialogue_state: ResMut<DialogueState>) {
+    if dialogue_state.is_open() {
+        dialogue_state.close();
+    }
+}
+
+/// Opens the dialogue of an NPC when the player tries to move into it.
+fn open_dialogue(
+    mut game_events: EventReader<GameEvent>,
+    player_query: Query<&Position, With<Player>>,
+    npc_query: Query<&Npc>,
+    mut dialogue_state: ResMut<DialogueState>,
+    tile_index: Res<TileIndex>,
+) {
+    for event in game_events.read() {
+        let (GameEvent::MovePlayer(dx, dy) | GameEvent::PullPlayer(dx, dy)) = event;
+        let Ok(position) = player_query.get_single() else {
+            continue;
+        };
+
+        let target = Position {
+            x: position.x + dx,
+            y: position.y + dy,
+        };
+        if let Some(Npc(text)) = npc_query.iter_many(tile_index.get(target)).next() {
+            dialogue_state.open(text);
+        }
+    }
+}
+
+/// Pages through the open dialogue.
+///
+/// All input is consumed while the dialogue is open, so the player cannot
+/// move or open the menu until the dialogue is closed.
+pub fn on_dialogue_input(
+    mut dialogue_state: ResMut<DialogueState>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    touches: Res<Touches>,
+) {
+    if !dialogue_state.is_open() {
+        return;
+    }
+
+    for key in keys.get_just_pressed() {
+        use KeyCode::*;
+        match key {
+            Enter | Space | ArrowRight | ArrowDown => dialogue_state.next_page(),
+            ArrowLeft | ArrowUp => dialogue_state.previous_page(),
+            Escape => dialogue_state.close(),
+            _ => continue,
+        }
+
+        if !dialogue_state.is_open() {
+            break;
+        }
+    }
+
+    for gamepad in &gamepads {
+        if gamepad.just_pressed(GamepadButton::South) {
+            dialogue_state.next_page();
+        } else if gamepad.just_pressed(GamepadButton::East) {
+            dialogue_state.close();
+        }
+    }
+
+    if mouse_buttons.clear_just_pressed(MouseButton::Left) || touches.any_just_pressed() {
+        dialogue_state.next_page();
+    }
+
+    let pressed_keys: Vec<KeyCode> = keys.get_just_pressed().copied().collect();
+    for key in pressed_keys {
+        keys.clear_just_pressed(key);
+    }
+}
+
+fn render_dialogue_box(
+    mut dialogue_box_query: Query<&mut Node, With<DialogueBox>>,
+    mut text_query: Query<&mut Text, With<DialogueText>>,
+    mut hint_query: Query<&mut InputPrompt, With<DialogueHint>>,
+    dialogue_state: Res<DialogueState>,
+) {
+    if !dialogue_state.is_changed() {
+        return;
+    }
+
+    let Ok(mut dialogue_box_node) = dialogue_box_query.get_single_mut() else {
+        return;
+    };
+
+    let Some(page) = dialogue_state.pages.get(dialogue_state.current_page) else {
+        dialogue_box_node.display = Display::None;
+        return;
+    };
+
+    for mut text in &mut text_query {
+        text.0.clone_from(page);
+    }
+
+    let num_pages = dialogue_state.pages.len();
+    for mut hint in &mut hint_query {
+        hint.template = if dialogue_state.current_page + 1 < num_pages {
+            format!(
+                "{}/{num_pages} - {{confirm}} to continue",
+                dialogue_state.current_page + 1
+            )
+        } else {
+            "{confirm} to close".to_owned()
+        };
+    }
+
+    dialogue_box_node.display = Display::Flex;
+}
@@ -1,17 +1,21 @@
 mod editor_button;
 mod editor_component;
+mod editor_focus;
 mod editor_system;
+mod inspector;
 mod number_input;
 mod object_selector;
 mod object_selector_system;
 
 use bevy::prelude::*;
 pub use editor_component::*;
+use editor_focus::*;
 pub use editor_system::*;
+use inspector::*;
 pub use object_selector::*;
 use object_selector_system::*;
 
-use crate::game_object::Position;
+use crate::{constants::EDITOR_WIDTH, game_object::Position, LoadLevel, RestartLevel};
 
 pub struct EditorPlugin;
 
@@ -25,8 +29,29 @@ impl Plugin for EditorPlugin {
                 on_editor_number_input_interaction,
                 on_object_selector_input,
                 on_selected_object_change,
+                render_level_warnings,
             ),
         )
+        .add_systems(
+            Update,
+            (on_inspector_button_interaction, render_inspector).chain(),
+        )
+        .add_systems(
+            Update,
+            (on_editor_focus_input, on_editor_focus_confirm)
+                .chain()
+                .before(on_editor_button_interaction)
+                .before(on_editor_number_input_interaction)
+                .before(on_inspector_button_interaction)
+                .before(on_object_selector_input),
+        )
+        .add_systems(
+            Update,
+            render_editor_focus
+                .after(on_editor_focus_input)
+                .after(on_selected_object_change),
+        )
+        .init_resource::<EditorFocus>()
         .init_resource::<EditorState>()
         .add_event::<ActivateSelection>()
         .add_event::<ChangeHeight>()
@@ -36,16 +61,20 @@ impl Plugin for EditorPlugin {
         .add_event::<DeselectObject>()
         .add_event::<MoveAllObjects>()
         .add_event::<SelectObject>()
+        .add_event::<SelectTab>()
         .add_event::<ToggleEditor>()
         .add_event::<ToggleSelection>()
         .add_observer(change_height)
         .add_observer(change_identifier)
         .add_observer(change_level)
         .add_observer(change_width)
+        .add_observer(discard_unsaved_changes::<LoadLevel>)
+        .add_observer(discard_unsaved_changes::<RestartLevel>)
         .add_observer(move_all_objects)
         .add_observer(on_activate_selection)
         .add_observer(on_deselect_object)
         .add_observer(on_select_object)
+        .add_observer(on_select_tab)
         .add_observer(on_toggle_editor)
         .add_observer(on_toggle_selection);
     }
@@ -57,6 +86,21 @@ pub struct EditorState {
     pub selected_object: Option<Position>,
     pub selected_object_type: Option<EditorObjectType>,
     pub selection: SelectionState,
+
+    /// Whether the level has been edited since it was last saved to disk.
+    pub has_unsaved_changes: bool,
+}
+
+impl EditorState {
+    /// Returns the width of the window that is taken up by the editor panel,
+    /// which grows with the UI scale.
+    pub fn width(&self, ui_scale: &UiScale) -> f32 {
+        if self.is_open {
+            EDITOR_WIDTH as f32 * ui_scale.0
+        } else {
+            0.
+        }
+    }
 }
 
 #[derive(Clone, Copy, Default, Eq, PartialEq)]
@@ -98,6 +142,9 @@ pub struct MoveAllObjects {
 #[derive(Event)]
 pub struct SelectObject(Position);
 
+#[derive(Event)]
+pub struct SelectTab(EditorTab);
+
 #[derive(Event)]
 pub struct ToggleEditor;
 
@@ -5,15 +5,23 @@ use crate::{
     fonts::Fonts,
 };
 
+use super::EditorTab;
+
 #[derive(Clone, Component, Copy, Eq, PartialEq)]
 pub enum EditorButton {
     Save,
+    Revert,
     Select,
+    Tab(EditorTab),
 }
 
 impl EditorButton {
     #[expect(clippy::new_ret_no_self)]
     pub fn new(marker: impl Bundle) -> impl Bundle {
+        Self::with_width(marker, 150.)
+    }
+
+    pub fn with_width(marker: impl Bundle, width: f32) -> impl Bundle {
         (
             marker,
             Button,
@@ -21,7 +29,7 @@ impl EditorButton {
             BorderRadius::all(Val::Px(4.)),
             Node {
                 height: Val::Px(30.),
-                width: Val::Px(150.),
+                width: Val::Px(width),
                 align_content: AlignContent::Center,
                 ..Default::default()
             },
@@ -2,10 +2,15 @@ use bevy::prelude::*;
 
 use crate::{constants::*, fonts::Fonts, game_object::GameObjectAssets, levels::Dimensions};
 
-use super::{editor_button::EditorButton, number_input::NumberInput, ObjectSelector};
+use super::{
+    editor_button::EditorButton, inspector::Inspector, number_input::NumberInput, EditorTab,
+    ObjectSelector,
+};
 
 const BORDER_WIDTH: f32 = 2.;
 
+const TAB_BUTTON_WIDTH: f32 = 100.;
+
 #[derive(Clone, Component, Copy, Eq, PartialEq)]
 pub enum Input {
     Width,
@@ -23,6 +28,10 @@ pub struct LevelInput;
 #[derive(Component)]
 pub struct SelectionOverlay;
 
+/// Lists the warnings encountered while loading the level.
+#[derive(Component)]
+pub struct WarningsList;
+
 #[derive(Component)]
 #[require(Node)]
 pub struct Editor;
@@ -65,12 +74,34 @@ impl Editor {
             NumberInput::populate(cb, Input::Height, "Height:", dimensions.height, fonts)
         });
 
-        cb.spawn(ObjectSelector::new())
-            .with_children(|cb| ObjectSelector::populate(cb, assets));
+        cb.spawn(Node {
+            column_gap: Val::Px(8.),
+            ..default()
+        })
+        .with_children(|cb| {
+            for (tab, label) in [
+                (EditorTab::Objects, "Objects"),
+                (EditorTab::Decorations, "Decorations"),
+            ] {
+                let button = EditorButton::Tab(tab);
+                cb.spawn(EditorButton::with_width(button, TAB_BUTTON_WIDTH))
+                    .with_children(|cb| EditorButton::populate(cb, button, label, fonts));
+            }
+        });
+
+        for tab in [EditorTab::Objects, EditorTab::Decorations] {
+            cb.spawn(ObjectSelector::new(tab))
+                .with_children(|cb| ObjectSelector::populate(cb, assets, tab));
+        }
 
         cb.spawn(EditorButton::new(EditorButton::Save))
             .with_children(|cb| EditorButton::populate(cb, EditorButton::Save, "Save", fonts));
 
+        cb.spawn(EditorButton::new(EditorButton::Revert))
+            .with_children(|cb| {
+                EditorButton::populate(cb, EditorButton::Revert, "Revert Level", fonts)
+            });
+
         cb.spawn(EditorButton::new(EditorButton::Select))
             .with_children(|cb| EditorButton::populate(cb, EditorButton::Select, "Select", fonts));
 
@@ -81,5 +112,19 @@ impl Editor {
             .with_children(|cb| {
                 NumberInput::populate(cb, Input::Identifier, "Teleporter:", 0, fonts)
             });
+
+        cb.spawn(Inspector::new());
+
+        cb.spawn((
+            WarningsList,
+            Text::default(),
+            TextColor(WHITE),
+            TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            Node {
+                display: Display::None,
+                width: Val::Percent(100.),
+                ..default()
+            },
+        ));
     }
 }
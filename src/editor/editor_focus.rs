@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    game_object::{ObjectType, Position},
+    levels::Dimensions,
+    Background,
+};
+
+use super::{
+    editor_system::spawn_selected_object, Editor, EditorObjectType, EditorState, SelectObject,
+};
+
+const FOCUS_OUTLINE_WIDTH: f32 = 2.;
+
+/// Where keyboard and gamepad input goes in the editor, so levels can be
+/// built without a mouse.
+///
+/// The focus is either on one of the widgets of the editor panel, which get
+/// pressed when the focus is confirmed, or on a tile of the level, where the
+/// selected object gets placed.
+#[derive(Clone, Copy, Default, PartialEq, Resource)]
+pub enum EditorFocus {
+    #[default]
+    None,
+    Widget(Entity),
+    Tile(Position),
+}
+
+/// Marks the tile that has the focus.
+#[derive(Component)]
+pub struct TileCursor;
+
+/// Moves the focus through the widgets of the editor panel using `Tab` and
+/// `Shift+Tab`, or the D-pad of a gamepad. The north button of a gamepad
+/// switches between the editor panel and the tiles of the level.
+#[expect(clippy::too_many_arguments)]
+pub fn on_editor_focus_input(
+    editor_query: Query<Entity, With<Editor>>,
+    children_query: Query<&Children>,
+    widget_query: Query<(&GlobalTransform, &ComputedNode), With<Interaction>>,
+    gamepads: Query<&Gamepad>,
+    mut focus: ResMut<EditorFocus>,
+    dimensions: Res<Dimensions>,
+    editor_state: Res<EditorState>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    let Ok(editor) = editor_query.get_single() else {
+        if *focus != EditorFocus::None {
+            *focus = EditorFocus::None;
+        }
+        return;
+    };
+
+    if !editor_state.is_open {
+        return;
+    }
+
+    let mut widgets: Vec<(Entity, Vec2)> = children_query
+        .iter_descendants(editor)
+        .filter_map(|entity| {
+            let (transform, node) = widget_query.get(entity).ok()?;
+            (!node.is_empty()).then(|| (entity, transform.translation().truncate()))
+        })
+        .collect();
+    widgets.sort_by(|(_, a), (_, b)| match a.y.total_cmp(&b.y) {
+        Ordering::Equal => a.x.total_cmp(&b.x),
+        ordering => ordering,
+    });
+
+    // Widgets may have been hidden or despawned since they got the focus.
+    if let EditorFocus::Widget(entity) = *focus {
+        if !widgets.iter().any(|(widget, _)| *widget == entity) {
+            *focus = EditorFocus::None;
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Tab) && !widgets.is_empty() {
+        let index = match *focus {
+            EditorFocus::Widget(entity) => widgets.iter().position(|(widget, _)| *widget == entity),
+            _ => None,
+        };
+        let len = widgets.len() as isize;
+        let delta = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            -1
+        } else {
+            1
+        };
+        let new_index = match index {
+            Some(index) => (index as isize + delta).rem_euclid(len),
+            None if delta < 0 => len - 1,
+            None => 0,
+        };
+        *focus = EditorFocus::Widget(widgets[new_index as usize].0);
+    }
+
+    for gamepad in &gamepads {
+        if gamepad.just_pressed(GamepadButton::North) {
+            *focus = match *focus {
+                EditorFocus::Tile(_) => widgets.first().map_or(EditorFocus::None, |(widget, _)| {
+                    EditorFocus::Widget(*widget)
+                }),
+                _ => EditorFocus::Tile(Position {
+                    x: (dimensions.width + 1) / 2,
+                    y: (dimensions.height + 1) / 2,
+                }),
+            };
+            continue;
+        }
+
+        let Some((dx, dy)) = [
+            (GamepadButton::DPadUp, (0, -1)),
+            (GamepadButton::DPadRight, (1, 0)),
+            (GamepadButton::DPadDown, (0, 1)),
+            (GamepadButton::DPadLeft, (-1, 0)),
+        ]
+        .into_iter()
+        .find_map(|(button, direction)| gamepad.just_pressed(button).then_some(direction)) else {
+            continue;
+        };
+
+        *focus = match *focus {
+            EditorFocus::None => widgets.first().map_or(EditorFocus::None, |(widget, _)| {
+                EditorFocus::Widget(*widget)
+            }),
+            EditorFocus::Widget(entity) => {
+                let from = widgets
+                    .iter()
+                    .find(|(widget, _)| *widget == entity)
+                    .map(|(_, position)| *position)
+                    .unwrap_or_default();
+                let direction = Vec2::new(dx as f32, dy as f32);
+                find_widget_in_direction(&widgets, from, direction)
+                    .map_or(*focus, EditorFocus::Widget)
+            }
+            EditorFocus::Tile(Position { x, y }) => EditorFocus::Tile(Position {
+                x: (x + dx).clamp(1, dimensions.width),
+                y: (y + dy).clamp(1, dimensions.height),
+            }),
+        };
+    }
+}
+
+/// Returns the widget closest to `from` in the given direction, preferring
+/// widgets that are in line with it.
+fn find_widget_in_direction(
+    widgets: &[(Entity, Vec2)],
+    from: Vec2,
+    direction: Vec2,
+) -> Option<Entity> {
+    widgets
+        .iter()
+        .filter_map(|(widget, position)| {
+            let offset = *position - from;
+            let distance = offset.dot(direction);
+            let deviation = offset.perp_dot(direction).abs();
+            (distance > 1.).then_some((*widget, distance + 2. * deviation))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(widget, _)| widget)
+}
+
+/// Confirms the focus with `Enter` or the south button of a gamepad.
+///
+/// Widgets are pressed by setting their [Interaction], so they respond just
+/// like they do to a click. The interaction is released again on the next
+/// frame.
+#[expect(clippy::too_many_arguments)]
+pub fn on_editor_focus_confirm(
+    mut commands: Commands,
+    mut interaction_query: Query<&mut Interaction>,
+    objects: Query<(Entity, &ObjectType, &Position)>,
+    gamepads: Query<&Gamepad>,
+    mut pressed_widget: Local<Option<Entity>>,
+    editor_state: ResMut<EditorState>,
+    dimensions: Res<Dimensions>,
+    focus: Res<EditorFocus>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if let Some(entity) = pressed_widget.take() {
+        if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    let is_confirmed = keys.just_pressed(KeyCode::Enter)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    if !is_confirmed || !editor_state.is_open {
+        return;
+    }
+
+    match *focus {
+        EditorFocus::None => {}
+        EditorFocus::Widget(entity) => {
+            if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+                *interaction = Interaction::Pressed;
+                *pressed_widget = Some(entity);
+            }
+        }
+        EditorFocus::Tile(position) => {
+            if editor_state.selected_object_type.is_some() {
+                spawn_selected_object(commands, editor_state, objects, dimensions, position);
+            } else {
+                commands.trigger(SelectObject(position));
+            }
+        }
+    }
+}
+
+/// Outlines the widget that has the focus, and shows the cursor on the tile
+/// that has it.
+///
+/// The object selector uses outlines for the selected object type as well,
+/// which is restored when the focus moves on.
+#[expect(clippy::too_many_arguments)]
+pub fn render_editor_focus(
+    mut commands: Commands,
+    mut cursor_query: Query<(Entity, &mut Transform), With<TileCursor>>,
+    mut outline_query: Query<(&mut Outline, Option<&EditorObjectType>), With<Interaction>>,
+    background_query: Query<Entity, With<Background>>,
+    mut previous_widget: Local<Option<Entity>>,
+    dimensions: Res<Dimensions>,
+    editor_state: Res<EditorState>,
+    focus: Res<EditorFocus>,
+) {
+    let focused_widget = match *focus {
+        EditorFocus::Widget(entity) => Some(entity),
+        _ => None,
+    };
+
+    if *previous_widget != focused_widget || editor_state.is_changed() {
+        if let Some(entity) = previous_widget.take() {
+            if let Ok((mut outline, object_type)) = outline_query.get_mut(entity) {
+                let is_selected_object_type =
+                    object_type.is_some_and(|ty| editor_state.selected_object_type == Some(*ty));
+                outline.color = if is_selected_object_type {
+                    RED
+                } else {
+                    Color::NONE
+                };
+            }
+        }
+
+        if let Some(entity) = focused_widget {
+            match outline_query.get_mut(entity) {
+                Ok((mut outline, _)) => outline.color = WHITE,
+                Err(_) => {
+                    commands.entity(entity).insert(Outline::new(
+                        Val::Px(FOCUS_OUTLINE_WIDTH),
+                        Val::ZERO,
+                        WHITE,
+                    ));
+                }
+            }
+        }
+
+        *previous_widget = focused_widget;
+    }
+
+    let EditorFocus::Tile(Position { x, y }) = *focus else {
+        for (cursor, _) in &cursor_query {
+            commands.entity(cursor).despawn();
+        }
+        return;
+    };
+
+    let grid_size = GRID_SIZE as f32;
+    let translation = Vec3::new(
+        (x as f32 - 0.5 * dimensions.width as f32 - 0.5) * grid_size,
+        (0.5 * dimensions.height as f32 - y as f32 + 0.5) * grid_size,
+        99.,
+    );
+
+    if let Ok((_, mut transform)) = cursor_query.get_single_mut() {
+        if transform.translation != translation {
+            transform.translation = translation;
+        }
+    } else if let Ok(background) = background_query.get_single() {
+        commands.entity(background).with_children(|cb| {
+            cb.spawn((
+                TileCursor,
+                Sprite {
+                    color: WHITE.with_alpha(0.3),
+                    custom_size: Some(Vec2::splat(grid_size)),
+                    ..default()
+                },
+                Transform::from_translation(translation),
+            ));
+        });
+    }
+}
@@ -4,21 +4,25 @@ use crate::{
     background::UpdateBackgroundTransform,
     constants::*,
     fonts::Fonts,
-    game_object::{Entrance, GameObjectAssets, ObjectType, Position, Teleporter},
-    levels::{Dimensions, InitialPositionAndMetadata},
-    timers::{MovementTimer, TemporaryTimer, TransporterTimer},
+    game_object::{Entrance, GameObjectAssets, ObjectType, Openable, Position, Teleporter},
+    levels::{Dimensions, InitialPositionAndMetadata, LevelWarnings, MAX_LEVEL_SIZE},
+    timers::{MovementTimer, SlideTimer, TemporaryTimer, TransporterTimer},
     ui_state::UiState,
     utils::level_coords_from_pointer_coords,
-    Background, ChangeZoom, LoadRelativeLevel, ResetLevel, SaveLevel, SpawnObject,
+    Background, ChangeZoom, LoadRelativeLevel, ResetLevel, RevertLevel, SaveLevel, SaveTarget,
+    SpawnObject,
 };
 
 use super::{
     editor_button::EditorButton, number_input::NumberInput, ActivateSelection, ChangeHeight,
     ChangeIdentifier, ChangeLevel, ChangeWidth, DeselectObject, Editor, EditorObjectType,
-    EditorState, IdentifierInput, Input, LevelInput, MoveAllObjects, SelectObject,
-    SelectionOverlay, SelectionState, ToggleEditor, ToggleSelection,
+    EditorState, IdentifierInput, Input, LevelInput, MoveAllObjects, ObjectSelector, SelectObject,
+    SelectTab, SelectionOverlay, SelectionState, ToggleEditor, ToggleSelection, WarningsList,
 };
 
+/// Amount by which number inputs change while Shift is held.
+const LARGE_STEP: i16 = 8;
+
 pub fn on_editor_button_interaction(
     mut commands: Commands,
     mut interaction_query: Query<
@@ -31,8 +35,12 @@ pub fn on_editor_button_interaction(
             Interaction::Pressed => {
                 *color = WHITE.into();
                 match button {
-                    EditorButton::Save => commands.trigger(SaveLevel { save_to_disk: true }),
+                    EditorButton::Save => commands.trigger(SaveLevel {
+                        target: SaveTarget::Disk,
+                    }),
+                    EditorButton::Revert => commands.trigger(RevertLevel),
                     EditorButton::Select => commands.trigger(ToggleSelection),
+                    EditorButton::Tab(tab) => commands.trigger(SelectTab(*tab)),
                 }
             }
             Interaction::Hovered => {
@@ -58,11 +66,19 @@ pub fn on_editor_number_input_interaction(
             Interaction::Pressed => {
                 *color = WHITE.into();
 
-                let abs_delta = if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
-                    2
+                // Shift takes bigger steps, which makes resizing large
+                // levels bearable. Control doubles the step, which resizes
+                // levels on both sides.
+                let step = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                    LARGE_STEP
                 } else {
                     1
                 };
+                let abs_delta = if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+                    2 * step
+                } else {
+                    step
+                };
                 match (input, number_input) {
                     (Input::Width, NumberInput::Increase) => {
                         commands.trigger(ChangeWidth(abs_delta))
@@ -128,6 +144,7 @@ pub fn on_editor_mouse_input(
     editor_state: ResMut<EditorState>,
     buttons: Res<ButtonInput<MouseButton>>,
     dimensions: Res<Dimensions>,
+    ui_scale: Res<UiScale>,
 ) {
     if !editor_state.is_open {
         return;
@@ -148,7 +165,7 @@ pub fn on_editor_mouse_input(
 
     let window_size = window.size();
 
-    if cursor_position.x >= window_size.x - EDITOR_WIDTH as f32 {
+    if cursor_position.x >= window_size.x - editor_state.width(&ui_scale) {
         return;
     }
 
@@ -194,8 +211,10 @@ fn extend_selection(
     let grid_size = GRID_SIZE as f32;
     let half_grid_size = HALF_GRID_SIZE as f32;
 
-    let center_x = (dimensions.width * HALF_GRID_SIZE) as f32;
-    let center_y = (dimensions.height * HALF_GRID_SIZE) as f32;
+    let Vec2 {
+        x: center_x,
+        y: center_y,
+    } = 0.5 * dimensions.size_in_pixels();
 
     let min_x = start.x.min(current.x) as f32;
     let min_y = start.y.min(current.y) as f32;
@@ -231,7 +250,7 @@ fn extend_selection(
     }
 }
 
-fn spawn_selected_object(
+pub(super) fn spawn_selected_object(
     mut commands: Commands,
     mut editor_state: ResMut<EditorState>,
     objects: Query<(Entity, &ObjectType, &Position)>,
@@ -245,12 +264,23 @@ fn spawn_selected_object(
         Some((object_type, direction)) => (Some(object_type), Some(direction)),
         None => (None, None),
     };
+    let decoration = editor_state
+        .selected_object_type
+        .and_then(EditorObjectType::get_decoration);
 
     for (entity, existing_object_type, object_position) in &objects {
+        // Decorations are on a layer of their own, so placing an object keeps
+        // the decoration underneath, and vice versa. The eraser removes both.
+        let is_same_layer = object_type.is_none_or(|object_type| {
+            (object_type == ObjectType::Decoration)
+                == (*existing_object_type == ObjectType::Decoration)
+        });
+
         if object_type == Some(ObjectType::Player) && *existing_object_type == ObjectType::Player
-            || *object_position == position
+            || *object_position == position && is_same_layer
         {
             commands.entity(entity).despawn_recursive();
+            editor_state.has_unsaved_changes = true;
         }
     }
 
@@ -259,14 +289,21 @@ fn spawn_selected_object(
     }
 
     if let Some(object_type) = object_type {
+        editor_state.has_unsaved_changes = true;
         commands.trigger(SpawnObject {
             object_type,
             position: InitialPositionAndMetadata {
                 position,
+                decoration,
                 direction,
                 identifier: Some(1),
                 level: Some(1),
                 open: false,
+                capacity: None,
+                interval: None,
+                required_count: None,
+                seconds: None,
+                text: None,
             },
         });
 
@@ -319,10 +356,16 @@ pub fn on_editor_keyboard_input(
                 }
             }
             Equal => {
-                commands.trigger(ChangeZoom(1.25));
+                commands.trigger(ChangeZoom {
+                    factor: 1.25,
+                    cursor_position: None,
+                });
             }
             Minus => {
-                commands.trigger(ChangeZoom(0.8));
+                commands.trigger(ChangeZoom {
+                    factor: 0.8,
+                    cursor_position: None,
+                });
             }
             KeyR => {
                 commands.trigger(LoadRelativeLevel(0));
@@ -402,6 +445,7 @@ pub fn on_toggle_editor(
     mut selection_query: Query<Entity, With<SelectionOverlay>>,
     mut editor_state: ResMut<EditorState>,
     mut movement_timer: ResMut<MovementTimer>,
+    mut slide_timer: ResMut<SlideTimer>,
     mut temporary_timer: ResMut<TemporaryTimer>,
     mut transporter_timer: ResMut<TransporterTimer>,
     editor_query: Query<Entity, With<Editor>>,
@@ -419,6 +463,7 @@ pub fn on_toggle_editor(
         }
 
         movement_timer.unpause();
+        slide_timer.unpause();
         temporary_timer.unpause();
         transporter_timer.unpause();
     } else {
@@ -431,6 +476,7 @@ pub fn on_toggle_editor(
             .with_children(|cb| Editor::populate(cb, &assets, &dimensions, &fonts));
 
         movement_timer.pause();
+        slide_timer.pause();
         temporary_timer.pause();
         transporter_timer.pause();
     }
@@ -499,11 +545,15 @@ pub fn change_height(
     trigger: Trigger<ChangeHeight>,
     mut commands: Commands,
     mut dimensions: ResMut<Dimensions>,
+    mut editor_state: ResMut<EditorState>,
 ) {
     let ChangeHeight(delta) = trigger.event();
 
-    if dimensions.height + delta > 0 {
-        dimensions.height += delta;
+    let height = (dimensions.height + delta).clamp(1, MAX_LEVEL_SIZE);
+    let delta = height - dimensions.height;
+    if delta != 0 {
+        dimensions.height = height;
+        editor_state.has_unsaved_changes = true;
 
         if delta.abs() > 1 {
             commands.trigger(MoveAllObjects {
@@ -519,7 +569,7 @@ pub fn change_identifier(
     mut commands: Commands,
     mut teleporters: Query<(&Position, &mut Teleporter)>,
     mut input_query: Query<(&Input, &NumberInput, &mut Text)>,
-    editor_state: Res<EditorState>,
+    mut editor_state: ResMut<EditorState>,
 ) {
     let ChangeIdentifier(delta) = trigger.event();
 
@@ -533,6 +583,7 @@ pub fn change_identifier(
     };
 
     teleporter.0 = teleporter.0.saturating_add_signed(*delta);
+    editor_state.has_unsaved_changes = true;
 
     for (input, number_input, mut text) in &mut input_query {
         if *input == Input::Identifier && *number_input == NumberInput::Value {
@@ -544,13 +595,13 @@ pub fn change_identifier(
 pub fn change_level(
     trigger: Trigger<ChangeLevel>,
     mut commands: Commands,
-    mut entrances: Query<(Entity, &Position, &mut Entrance)>,
+    mut entrances: Query<(Entity, &Position, &mut Entrance, Option<&Openable>)>,
     mut input_query: Query<(&Input, &NumberInput, &mut Text)>,
-    editor_state: Res<EditorState>,
+    mut editor_state: ResMut<EditorState>,
 ) {
     let ChangeLevel(delta) = trigger.event();
 
-    let Some((entity, position, mut entrance)) =
+    let Some((entity, position, mut entrance, openable)) =
         editor_state.selected_object.and_then(|selected_position| {
             entrances
                 .iter_mut()
@@ -562,6 +613,7 @@ pub fn change_level(
     };
 
     entrance.0 = entrance.0.saturating_add_signed(*delta);
+    editor_state.has_unsaved_changes = true;
 
     // Respawn to update the entrance text:
     commands.entity(entity).despawn_recursive();
@@ -569,10 +621,19 @@ pub fn change_level(
         object_type: ObjectType::Entrance,
         position: InitialPositionAndMetadata {
             position: *position,
+            decoration: None,
             direction: None,
             identifier: None,
             level: Some(entrance.0),
             open: false,
+            capacity: None,
+            interval: None,
+            required_count: match openable {
+                Some(Openable::FinishedCount(count)) => Some(*count),
+                _ => None,
+            },
+            seconds: None,
+            text: None,
         },
     });
 
@@ -587,11 +648,15 @@ pub fn change_width(
     trigger: Trigger<ChangeWidth>,
     mut commands: Commands,
     mut dimensions: ResMut<Dimensions>,
+    mut editor_state: ResMut<EditorState>,
 ) {
     let ChangeWidth(delta) = trigger.event();
 
-    if dimensions.width + delta > 0 {
-        dimensions.width += delta;
+    let width = (dimensions.width + delta).clamp(1, MAX_LEVEL_SIZE);
+    let delta = width - dimensions.width;
+    if delta != 0 {
+        dimensions.width = width;
+        editor_state.has_unsaved_changes = true;
 
         if delta.abs() > 1 {
             commands.trigger(MoveAllObjects {
@@ -627,6 +692,7 @@ pub fn move_all_objects(
         position.x += *dx;
         position.y += *dy;
     }
+    editor_state.has_unsaved_changes = true;
 
     if let SelectionState::Active {
         top_left,
@@ -639,3 +705,52 @@ pub fn move_all_objects(
         bottom_right.y += dy;
     }
 }
+
+/// Forgets about unsaved changes once the level is respawned, which discards
+/// them.
+pub fn discard_unsaved_changes<E: Event>(
+    _trigger: Trigger<E>,
+    mut editor_state: ResMut<EditorState>,
+) {
+    if editor_state.has_unsaved_changes {
+        editor_state.has_unsaved_changes = false;
+    }
+}
+
+pub fn on_select_tab(
+    trigger: Trigger<SelectTab>,
+    mut selector_query: Query<(&ObjectSelector, &mut Node)>,
+) {
+    let SelectTab(tab) = trigger.event();
+
+    for (ObjectSelector(selector_tab), mut node) in &mut selector_query {
+        node.display = if selector_tab == tab {
+            Display::Grid
+        } else {
+            Display::None
+        };
+    }
+}
+
+pub fn render_level_warnings(
+    mut warnings_query: Query<(&mut Node, &mut Text, Ref<WarningsList>)>,
+    warnings: Res<LevelWarnings>,
+) {
+    for (mut node, mut text, list) in &mut warnings_query {
+        if !list.is_added() && !warnings.is_changed() {
+            continue;
+        }
+
+        if warnings.0.is_empty() {
+            node.display = Display::None;
+            continue;
+        }
+
+        text.0 = "Warnings:".to_owned();
+        for warning in &warnings.0 {
+            text.0.push_str("\n- ");
+            text.0.push_str(warning);
+        }
+        node.display = Display::Flex;
+    }
+}
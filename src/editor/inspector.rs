@@ -0,0 +1,254 @@
+use bevy::{
+    ecs::{archetype::Archetypes, component::Components, entity::Entities},
+    prelude::*,
+};
+
+use crate::{
+    constants::*,
+    fonts::Fonts,
+    game_object::{
+        Decoration, Direction, GameObjectAssets, Massive, ObjectType, Openable, Position, TileIndex,
+    },
+};
+
+use super::{editor_button::EditorButton, EditorState};
+
+const INSPECTOR_BUTTON_WIDTH: f32 = 200.;
+
+/// Panel listing the components of the selected object.
+///
+/// Only the properties that are persisted when saving the level can be
+/// modified from the inspector.
+#[derive(Component)]
+#[require(Node)]
+pub struct Inspector;
+
+impl Inspector {
+    #[expect(clippy::new_ret_no_self)]
+    pub fn new() -> impl Bundle {
+        (
+            Inspector,
+            Node {
+                display: Display::None,
+                width: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.),
+                ..default()
+            },
+        )
+    }
+}
+
+#[derive(Clone, Component, Copy)]
+pub enum InspectorButton {
+    Decoration,
+    Direction,
+    Open,
+}
+
+type InspectedObject<'a> = (
+    &'a ObjectType,
+    Option<&'a Decoration>,
+    Option<&'a Direction>,
+    Has<Massive>,
+    Has<Openable>,
+);
+
+/// Returns the selected object, if there is one.
+///
+/// `is_object` tells whether the given entity is an object that can be
+/// inspected.
+fn selected_entity(
+    editor_state: &EditorState,
+    tile_index: &TileIndex,
+    is_object: impl Fn(Entity) -> bool,
+) -> Option<Entity> {
+    let position = editor_state.selected_object?;
+    tile_index
+        .get(position)
+        .iter()
+        .copied()
+        .find(|entity| is_object(*entity))
+}
+
+/// Returns the names of all the game object components of the given entity.
+fn component_names(
+    entity: Entity,
+    archetypes: &Archetypes,
+    components: &Components,
+    entities: &Entities,
+) -> Vec<String> {
+    let Some(archetype) = entities
+        .get(entity)
+        .and_then(|location| archetypes.get(location.archetype_id))
+    else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = archetype
+        .components()
+        .filter_map(|id| components.get_info(id))
+        .map(|info| info.name())
+        .filter(|name| name.contains("::game_object::"))
+        .filter_map(|name| name.rsplit("::").next())
+        .map(str::to_owned)
+        .collect();
+    names.sort_unstable();
+    names
+}
+
+#[expect(clippy::too_many_arguments)]
+pub fn render_inspector(
+    mut commands: Commands,
+    mut inspector_query: Query<(Entity, &mut Node), With<Inspector>>,
+    objects_query: Query<InspectedObject, With<Position>>,
+    archetypes: &Archetypes,
+    components: &Components,
+    entities: &Entities,
+    editor_state: Res<EditorState>,
+    fonts: Res<Fonts>,
+    tile_index: Res<TileIndex>,
+) {
+    if !editor_state.is_changed() {
+        return;
+    }
+
+    let Ok((inspector, mut node)) = inspector_query.get_single_mut() else {
+        return;
+    };
+
+    commands.entity(inspector).despawn_descendants();
+
+    let Some(entity) = selected_entity(&editor_state, &tile_index, |entity| {
+        objects_query.contains(entity)
+    }) else {
+        node.display = Display::None;
+        return;
+    };
+    let Ok((object_type, decoration, direction, massive, openable)) = objects_query.get(entity)
+    else {
+        node.display = Display::None;
+        return;
+    };
+
+    let font = TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.);
+    let names = component_names(entity, archetypes, components, entities);
+
+    commands.entity(inspector).with_children(|cb| {
+        cb.spawn((
+            Text::new(object_type.to_string()),
+            TextColor(WHITE),
+            font.clone().with_font_size(18.),
+        ));
+
+        cb.spawn((
+            Text::new(names.join(", ")),
+            TextColor(WHITE),
+            font,
+            TextLayout::new_with_justify(JustifyText::Center),
+        ));
+
+        let mut spawn_button = |button: InspectorButton, label: String| {
+            cb.spawn(EditorButton::with_width(button, INSPECTOR_BUTTON_WIDTH))
+                .with_children(|cb| EditorButton::populate(cb, (), label, &fonts));
+        };
+
+        if let Some(direction) = direction {
+            spawn_button(
+                InspectorButton::Direction,
+                format!("Direction: {direction}"),
+            );
+        }
+        if let Some(decoration) = decoration {
+            spawn_button(
+                InspectorButton::Decoration,
+                format!("Decoration: {decoration}"),
+            );
+        }
+        if openable {
+            let open = if massive { "No" } else { "Yes" };
+            spawn_button(InspectorButton::Open, format!("Open: {open}"));
+        }
+    });
+
+    node.display = Display::Flex;
+}
+
+#[expect(clippy::type_complexity)]
+pub fn on_inspector_button_interaction(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &InspectorButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut objects_query: Query<
+        (
+            &ObjectType,
+            Option<&mut Decoration>,
+            Option<&mut Direction>,
+            Has<Massive>,
+            &mut Sprite,
+        ),
+        With<Position>,
+    >,
+    mut editor_state: ResMut<EditorState>,
+    assets: Res<GameObjectAssets>,
+    tile_index: Res<TileIndex>,
+) {
+    for (interaction, button, mut color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = WHITE.into();
+
+                let Some(entity) = selected_entity(&editor_state, &tile_index, |entity| {
+                    objects_query.contains(entity)
+                }) else {
+                    continue;
+                };
+                let Ok((object_type, decoration, direction, massive, mut sprite)) =
+                    objects_query.get_mut(entity)
+                else {
+                    continue;
+                };
+
+                let atlas_sprite = assets.for_object_type(*object_type);
+                match button {
+                    InspectorButton::Decoration => {
+                        if let Some(mut decoration) = decoration {
+                            *decoration = match *decoration {
+                                Decoration::Flower => Decoration::Rock,
+                                Decoration::Rock => Decoration::Rug,
+                                Decoration::Rug => Decoration::Flower,
+                            };
+                            atlas_sprite.set_frame(&mut sprite, decoration.frame());
+                        }
+                    }
+                    InspectorButton::Direction => {
+                        if let Some(mut direction) = direction {
+                            *direction = direction.right_hand();
+                        }
+                    }
+                    InspectorButton::Open => {
+                        if massive {
+                            commands.entity(entity).remove::<Massive>();
+                            atlas_sprite.set_frame(&mut sprite, 1);
+                        } else {
+                            commands.entity(entity).insert(Massive);
+                            atlas_sprite.set_frame(&mut sprite, 0);
+                        }
+                    }
+                }
+
+                // Refreshes the inspector.
+                editor_state.set_changed();
+            }
+            Interaction::Hovered => {
+                *color = LIGHT_GRAY.into();
+            }
+            Interaction::None => {
+                *color = DARK_GRAY.into();
+            }
+        }
+    }
+}
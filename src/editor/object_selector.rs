@@ -2,16 +2,21 @@ use bevy::prelude::*;
 
 use crate::{
     constants::*,
-    game_object::{Direction, GameObjectAssets, ObjectType},
+    game_object::{Decoration, Direction, GameObjectAssets, ObjectType},
 };
 
 const NUM_OBJECTS: i16 = EditorObjectType::__Last as i16;
 const NUM_COLUMNS: i16 = EDITOR_WIDTH / GRID_SIZE;
-const NUM_ROWS: i16 =
-    NUM_OBJECTS / NUM_COLUMNS + if NUM_OBJECTS % NUM_COLUMNS == 0 { 0 } else { 1 };
 pub const SELECTOR_OUTLINE_WIDTH: i16 = 1;
 const SELECTOR_WIDTH: i16 = NUM_COLUMNS * GRID_SIZE + (NUM_COLUMNS - 1) * SELECTOR_OUTLINE_WIDTH;
-const SELECTOR_HEIGHT: i16 = NUM_ROWS * GRID_SIZE + (NUM_ROWS - 1) * SELECTOR_OUTLINE_WIDTH;
+
+/// Tabs of the editor, each of which has its own object selector.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum EditorTab {
+    #[default]
+    Objects,
+    Decorations,
+}
 
 #[derive(Clone, Component, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 #[require(Interaction)]
@@ -19,11 +24,14 @@ pub enum EditorObjectType {
     Eraser,
     BlueBlock,
     BluePaint,
+    Bomb,
     BouncingBallUp,
     BouncingBallRight,
     BouncingBallDown,
     BouncingBallLeft,
+    Bridge,
     Button,
+    Checkpoint,
     Creature1Up,
     Creature1Right,
     Creature1Down,
@@ -31,21 +39,28 @@ pub enum EditorObjectType {
     Door,
     Entrance,
     Exit,
+    Flower,
     Gate,
     Key,
     Ice,
     Mine,
+    Npc,
     Player,
     PurpleBlock,
     PurplePaint,
     Raft,
     RedBlock,
     RedPaint,
+    Rock,
+    Rug,
+    Sign,
     Teleporter,
+    TimerGate,
     TransporterUp,
     TransporterRight,
     TransporterDown,
     TransporterLeft,
+    Wall,
     Water,
     YellowBlock,
     __Last,
@@ -56,11 +71,14 @@ impl EditorObjectType {
         let object_type = match self {
             Self::BlueBlock => Some(ObjectType::BlueBlock),
             Self::BluePaint => Some(ObjectType::BluePaint),
+            Self::Bomb => Some(ObjectType::Bomb),
             Self::BouncingBallUp
             | Self::BouncingBallRight
             | Self::BouncingBallDown
             | Self::BouncingBallLeft => Some(ObjectType::BouncingBall),
+            Self::Bridge => Some(ObjectType::Bridge),
             Self::Button => Some(ObjectType::Button),
+            Self::Checkpoint => Some(ObjectType::Checkpoint),
             Self::Creature1Up
             | Self::Creature1Right
             | Self::Creature1Down
@@ -68,21 +86,26 @@ impl EditorObjectType {
             Self::Door => Some(ObjectType::Door),
             Self::Entrance => Some(ObjectType::Entrance),
             Self::Exit => Some(ObjectType::Exit),
+            Self::Flower | Self::Rock | Self::Rug => Some(ObjectType::Decoration),
             Self::Gate => Some(ObjectType::Gate),
             Self::Ice => Some(ObjectType::Ice),
             Self::Key => Some(ObjectType::Key),
             Self::Mine => Some(ObjectType::Mine),
+            Self::Npc => Some(ObjectType::Npc),
             Self::Player => Some(ObjectType::Player),
             Self::PurpleBlock => Some(ObjectType::PurpleBlock),
             Self::PurplePaint => Some(ObjectType::PurplePaint),
             Self::Raft => Some(ObjectType::Raft),
             Self::RedBlock => Some(ObjectType::RedBlock),
             Self::RedPaint => Some(ObjectType::RedPaint),
+            Self::Sign => Some(ObjectType::Sign),
             Self::Teleporter => Some(ObjectType::Teleporter),
+            Self::TimerGate => Some(ObjectType::TimerGate),
             Self::TransporterUp
             | Self::TransporterRight
             | Self::TransporterDown
             | Self::TransporterLeft => Some(ObjectType::Transporter),
+            Self::Wall => Some(ObjectType::Wall),
             Self::Water => Some(ObjectType::Water),
             Self::YellowBlock => Some(ObjectType::YellowBlock),
             Self::Eraser | Self::__Last => None,
@@ -107,116 +130,75 @@ impl EditorObjectType {
         object_type.map(|object_type| (object_type, direction))
     }
 
+    pub fn get_decoration(self) -> Option<Decoration> {
+        match self {
+            Self::Flower => Some(Decoration::Flower),
+            Self::Rock => Some(Decoration::Rock),
+            Self::Rug => Some(Decoration::Rug),
+            _ => None,
+        }
+    }
+
+    /// Returns the tab of the editor in which this object type can be
+    /// selected.
+    ///
+    /// The eraser is available in every tab.
+    fn is_in_tab(self, tab: EditorTab) -> bool {
+        match self {
+            Self::Eraser => true,
+            Self::Flower | Self::Rock | Self::Rug => tab == EditorTab::Decorations,
+            _ => tab == EditorTab::Objects,
+        }
+    }
+
     fn get_image_node(self, assets: &GameObjectAssets) -> ImageNode {
-        let image = match self {
-            Self::Eraser => assets.eraser.clone(),
-            Self::BlueBlock => assets.blue_block.clone(),
-            Self::BluePaint => assets.blue_paint.clone(),
-            Self::BouncingBallUp
-            | Self::BouncingBallRight
-            | Self::BouncingBallDown
-            | Self::BouncingBallLeft => assets.bouncing_ball_editor.0.clone(),
-            Self::Button => assets.button.clone(),
-            Self::Creature1Up => assets.creature1.0.clone(),
-            Self::Creature1Right => assets.creature1.0.clone(),
-            Self::Creature1Down => assets.creature1.0.clone(),
-            Self::Creature1Left => assets.creature1.0.clone(),
-            Self::Door => assets.door.0.clone(),
-            Self::Entrance => assets.entrance.0.clone(),
-            Self::Exit => assets.exit.clone(),
-            Self::Gate => assets.gate.0.clone(),
-            Self::Ice => assets.ice.clone(),
-            Self::Key => assets.key.clone(),
-            Self::Mine => assets.mine.clone(),
-            Self::Player => assets.player.clone(),
-            Self::PurpleBlock => assets.purple_block.clone(),
-            Self::PurplePaint => assets.purple_paint.clone(),
-            Self::Raft => assets.raft.clone(),
-            Self::RedBlock => assets.red_block.clone(),
-            Self::RedPaint => assets.red_paint.clone(),
-            Self::Teleporter => assets.teleporter.clone(),
-            Self::TransporterUp
-            | Self::TransporterRight
-            | Self::TransporterDown
-            | Self::TransporterLeft => assets.transporter.0.clone(),
-            Self::Water => assets.water.0.clone(),
-            Self::YellowBlock => assets.yellow_block.clone(),
+        let (sprite, frame) = match self {
+            Self::Eraser => (assets.eraser, 0),
+            Self::BlueBlock => (assets.blue_block, 0),
+            Self::BluePaint => (assets.blue_paint, 0),
+            Self::Bomb => (assets.bomb, 0),
+            Self::BouncingBallUp => (assets.bouncing_ball_editor, 0),
+            Self::BouncingBallRight => (assets.bouncing_ball_editor, 1),
+            Self::BouncingBallDown => (assets.bouncing_ball_editor, 2),
+            Self::BouncingBallLeft => (assets.bouncing_ball_editor, 3),
+            Self::Bridge => (assets.bridge, 0),
+            Self::Button => (assets.button, 0),
+            Self::Checkpoint => (assets.checkpoint, 0),
+            Self::Creature1Up => (assets.creature1, 0),
+            Self::Creature1Right => (assets.creature1, 1),
+            Self::Creature1Down => (assets.creature1, 2),
+            Self::Creature1Left => (assets.creature1, 3),
+            Self::Door => (assets.door, 0),
+            Self::Entrance => (assets.entrance, 0),
+            Self::Exit => (assets.exit, 0),
+            Self::Flower => (assets.decoration, Decoration::Flower.frame()),
+            Self::Gate => (assets.gate, 0),
+            Self::Ice => (assets.ice, 0),
+            Self::Key => (assets.key, 0),
+            Self::Mine => (assets.mine, 0),
+            Self::Npc => (assets.npc, 0),
+            Self::Player => (assets.player, 0),
+            Self::PurpleBlock => (assets.purple_block, 0),
+            Self::PurplePaint => (assets.purple_paint, 0),
+            Self::Raft => (assets.raft, 0),
+            Self::RedBlock => (assets.red_block, 0),
+            Self::RedPaint => (assets.red_paint, 0),
+            Self::Rock => (assets.decoration, Decoration::Rock.frame()),
+            Self::Rug => (assets.decoration, Decoration::Rug.frame()),
+            Self::Sign => (assets.sign, 0),
+            Self::Teleporter => (assets.teleporter, 0),
+            Self::TimerGate => (assets.gate, 1),
+            Self::TransporterUp => (assets.transporter, 0),
+            Self::TransporterRight => (assets.transporter, 1),
+            Self::TransporterDown => (assets.transporter, 2),
+            Self::TransporterLeft => (assets.transporter, 3),
+            Self::Wall => (assets.wall, 0),
+            Self::Water => (assets.water, 0),
+            Self::YellowBlock => (assets.yellow_block, 0),
             Self::__Last => unreachable!(),
         };
 
-        let atlas = match self {
-            Self::BouncingBallUp => Some(TextureAtlas {
-                layout: assets.bouncing_ball_editor.1.clone(),
-                index: 0,
-            }),
-            Self::BouncingBallRight => Some(TextureAtlas {
-                layout: assets.bouncing_ball_editor.1.clone(),
-                index: 1,
-            }),
-            Self::BouncingBallDown => Some(TextureAtlas {
-                layout: assets.bouncing_ball_editor.1.clone(),
-                index: 2,
-            }),
-            Self::BouncingBallLeft => Some(TextureAtlas {
-                layout: assets.bouncing_ball_editor.1.clone(),
-                index: 3,
-            }),
-            Self::Creature1Up => Some(TextureAtlas {
-                layout: assets.creature1.1.clone(),
-                index: 0,
-            }),
-            Self::Creature1Right => Some(TextureAtlas {
-                layout: assets.creature1.1.clone(),
-                index: 1,
-            }),
-            Self::Creature1Down => Some(TextureAtlas {
-                layout: assets.creature1.1.clone(),
-                index: 2,
-            }),
-            Self::Creature1Left => Some(TextureAtlas {
-                layout: assets.creature1.1.clone(),
-                index: 3,
-            }),
-            Self::Door => Some(TextureAtlas {
-                layout: assets.door.1.clone(),
-                index: 0,
-            }),
-            Self::Entrance => Some(TextureAtlas {
-                layout: assets.entrance.1.clone(),
-                index: 0,
-            }),
-            Self::Gate => Some(TextureAtlas {
-                layout: assets.gate.1.clone(),
-                index: 0,
-            }),
-            Self::TransporterUp => Some(TextureAtlas {
-                layout: assets.transporter.1.clone(),
-                index: 0,
-            }),
-            Self::TransporterRight => Some(TextureAtlas {
-                layout: assets.transporter.1.clone(),
-                index: 1,
-            }),
-            Self::TransporterDown => Some(TextureAtlas {
-                layout: assets.transporter.1.clone(),
-                index: 2,
-            }),
-            Self::TransporterLeft => Some(TextureAtlas {
-                layout: assets.transporter.1.clone(),
-                index: 3,
-            }),
-            Self::Water => Some(TextureAtlas {
-                layout: assets.water.1.clone(),
-                index: 0,
-            }),
-            _ => None,
-        };
-
-        if let Some(atlas) = atlas {
-            ImageNode::from_atlas_image(image, atlas)
-        } else {
-            ImageNode { image, ..default() }
-        }
+        assets.image_node(sprite, frame)
     }
 }
 
@@ -257,6 +239,16 @@ impl TryFrom<i16> for EditorObjectType {
             29 => Self::BluePaint,
             30 => Self::Teleporter,
             31 => Self::Entrance,
+            32 => Self::Sign,
+            33 => Self::Npc,
+            34 => Self::Flower,
+            35 => Self::Rock,
+            36 => Self::Rug,
+            37 => Self::Wall,
+            38 => Self::Checkpoint,
+            39 => Self::Bomb,
+            40 => Self::Bridge,
+            41 => Self::TimerGate,
             _ => return Err(()),
         };
         Ok(object_type)
@@ -265,22 +257,31 @@ impl TryFrom<i16> for EditorObjectType {
 
 #[derive(Component)]
 #[require(Node)]
-pub struct ObjectSelector;
+pub struct ObjectSelector(pub EditorTab);
 
 impl ObjectSelector {
     #[expect(clippy::new_ret_no_self)]
-    pub fn new() -> impl Bundle {
+    pub fn new(tab: EditorTab) -> impl Bundle {
+        let num_objects = Self::object_types(tab).count() as i16;
+        let num_rows =
+            num_objects / NUM_COLUMNS + if num_objects % NUM_COLUMNS == 0 { 0 } else { 1 };
+        let height = num_rows * GRID_SIZE + (num_rows - 1) * SELECTOR_OUTLINE_WIDTH;
+
         (
-            ObjectSelector,
+            ObjectSelector(tab),
             BackgroundColor(NORMAL_GRAY),
             Node {
-                display: Display::Grid,
+                display: if tab == EditorTab::default() {
+                    Display::Grid
+                } else {
+                    Display::None
+                },
                 width: Val::Px(SELECTOR_WIDTH as f32),
-                height: Val::Px(SELECTOR_HEIGHT as f32),
+                height: Val::Px(height as f32),
                 grid_template_columns: (0..NUM_COLUMNS)
                     .map(|_| GridTrack::px(GRID_SIZE as f32))
                     .collect(),
-                grid_template_rows: (0..NUM_ROWS)
+                grid_template_rows: (0..num_rows)
                     .map(|_| GridTrack::px(GRID_SIZE as f32))
                     .collect(),
                 row_gap: Val::Px(SELECTOR_OUTLINE_WIDTH as f32),
@@ -290,12 +291,17 @@ impl ObjectSelector {
         )
     }
 
-    pub fn populate(cb: &mut ChildBuilder, assets: &GameObjectAssets) {
-        for i in 0..NUM_OBJECTS {
-            let object_type = EditorObjectType::try_from(i).unwrap();
+    pub fn populate(cb: &mut ChildBuilder, assets: &GameObjectAssets, tab: EditorTab) {
+        for object_type in Self::object_types(tab) {
             let image = object_type.get_image_node(assets);
 
             cb.spawn((object_type, image));
         }
     }
+
+    fn object_types(tab: EditorTab) -> impl Iterator<Item = EditorObjectType> {
+        (0..NUM_OBJECTS)
+            .map(|i| EditorObjectType::try_from(i).unwrap())
+            .filter(move |object_type| object_type.is_in_tab(tab))
+    }
 }
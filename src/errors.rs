@@ -1,5 +1,16 @@
 use std::error::Error;
 
+#[derive(Debug)]
+pub struct UnknownDecoration;
+
+impl std::fmt::Display for UnknownDecoration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown decoration")
+    }
+}
+
+impl Error for UnknownDecoration {}
+
 #[derive(Debug)]
 pub struct UnknownDirection;
 
@@ -21,3 +32,74 @@ impl std::fmt::Display for UnknownObjectType {
 }
 
 impl Error for UnknownObjectType {}
+
+#[derive(Debug)]
+pub struct UnknownTheme;
+
+impl std::fmt::Display for UnknownTheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown theme")
+    }
+}
+
+impl Error for UnknownTheme {}
+
+#[derive(Debug)]
+pub struct UnknownKeyMode;
+
+impl std::fmt::Display for UnknownKeyMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown key mode")
+    }
+}
+
+impl Error for UnknownKeyMode {}
+
+#[derive(Debug)]
+pub struct UnknownWeather;
+
+impl std::fmt::Display for UnknownWeather {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown weather")
+    }
+}
+
+impl Error for UnknownWeather {}
+
+/// Problem that keeps a level from being loaded at all.
+///
+/// Lines are numbered from one, like in a text editor.
+#[derive(Debug)]
+pub enum LevelError {
+    /// A line that is neither a section header nor a key-value pair.
+    UnexpectedLine(usize),
+
+    /// A key-value pair that comes before the first section.
+    KeyOutsideSection(usize),
+
+    InvalidDimension {
+        line: usize,
+        value: String,
+    },
+    InvalidPosition {
+        line: usize,
+        value: String,
+    },
+}
+
+impl std::fmt::Display for LevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedLine(line) => write!(f, "unexpected content on line {line}"),
+            Self::KeyOutsideSection(line) => write!(f, "key outside of a section on line {line}"),
+            Self::InvalidDimension { line, value } => {
+                write!(f, "invalid dimension on line {line}: {value}")
+            }
+            Self::InvalidPosition { line, value } => {
+                write!(f, "invalid position on line {line}: {value}")
+            }
+        }
+    }
+}
+
+impl Error for LevelError {}
@@ -0,0 +1,330 @@
+use bevy::prelude::*;
+
+use crate::{
+    background::UpdateBackgroundTransform,
+    constants::*,
+    fonts::Fonts,
+    game_object::{Entrance, Player, Position, TileIndex},
+    game_state::GameState,
+    input_prompt::InputPrompt,
+    levels::Dimensions,
+    on_keyboard_input, on_mouse_input, setup,
+    toast::ShowToast,
+};
+
+/// Number of destinations per row of the hub map.
+const COLUMNS: usize = 5;
+
+/// Opens the hub map, from which the player can fast-travel to the entrances
+/// they have reached before.
+#[derive(Event)]
+pub struct OpenFastTravel;
+
+#[derive(Default, Resource)]
+struct FastTravel {
+    destinations: Vec<Destination>,
+    selection: usize,
+    is_open: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Destination {
+    level: u16,
+    entrance: Position,
+}
+
+#[derive(Component)]
+struct FastTravelPanel;
+
+#[derive(Component)]
+struct FastTravelGrid;
+
+/// Button for the destination with the given index.
+#[derive(Component)]
+struct FastTravelButton(usize);
+
+pub struct FastTravelPlugin;
+
+impl Plugin for FastTravelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FastTravel>()
+            .add_observer(on_open_fast_travel)
+            .add_systems(Startup, setup_fast_travel.after(setup))
+            .add_systems(
+                Update,
+                on_fast_travel_input
+                    .before(on_keyboard_input)
+                    .before(on_mouse_input),
+            )
+            .add_systems(Update, render_fast_travel.after(on_fast_travel_input));
+    }
+}
+
+fn setup_fast_travel(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            FastTravelPanel,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Px(700.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::all(Val::Auto),
+                padding: UiRect::all(Val::Px(20.)),
+                row_gap: Val::Px(10.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new("Fast Travel"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(32.),
+            ));
+            cb.spawn((
+                FastTravelGrid,
+                Node {
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(10.),
+                    row_gap: Val::Px(10.),
+                    margin: UiRect::vertical(Val::Px(10.)),
+                    ..default()
+                },
+            ));
+            cb.spawn((
+                InputPrompt::new("{confirm} to travel"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+/// Lists the entrances in the hub that the player has reached before.
+fn on_open_fast_travel(
+    _trigger: Trigger<OpenFastTravel>,
+    mut commands: Commands,
+    grid_query: Query<Entity, With<FastTravelGrid>>,
+    entrance_query: Query<(&Entrance, &Position)>,
+    mut fast_travel: ResMut<FastTravel>,
+    fonts: Res<Fonts>,
+    game_state: Res<GameState>,
+) {
+    if !game_state.is_in_hub() {
+        commands.trigger(ShowToast::new("Fast travel is only possible in the hub"));
+        return;
+    }
+
+    let mut destinations: Vec<Destination> = entrance_query
+        .iter()
+        .filter(|(Entrance(level), _)| game_state.discovered_entrances.contains(level))
+        .map(|(Entrance(level), position)| Destination {
+            level: *level,
+            entrance: *position,
+        })
+        .collect();
+    destinations.sort_by_key(|destination| destination.level);
+    destinations.dedup_by_key(|destination| destination.level);
+
+    if destinations.is_empty() {
+        commands.trigger(ShowToast::new(
+            "Reach an entrance first to be able to fast-travel to it",
+        ));
+        return;
+    }
+
+    let Ok(grid) = grid_query.get_single() else {
+        return;
+    };
+
+    commands
+        .entity(grid)
+        .despawn_descendants()
+        .with_children(|cb| {
+            for (index, destination) in destinations.iter().enumerate() {
+                let is_finished = game_state.finished_levels.contains(&destination.level);
+                cb.spawn((
+                    FastTravelButton(index),
+                    Button,
+                    BackgroundColor(BLUE),
+                    Node {
+                        height: Val::Px(44.),
+                        width: Val::Px(120.),
+                        ..default()
+                    },
+                ))
+                .with_children(|cb| {
+                    cb.spawn((
+                        Text::new(format!("Level {}", destination.level)),
+                        TextColor(if is_finished { LIGHT_GRAY } else { WHITE }),
+                        TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                        Node {
+                            margin: UiRect::all(Val::Auto),
+                            ..default()
+                        },
+                    ));
+                });
+            }
+        });
+
+    *fast_travel = FastTravel {
+        destinations,
+        selection: 0,
+        is_open: true,
+    };
+}
+
+/// Lets the player pick a destination on the hub map, using the keyboard, a
+/// gamepad, the mouse or touch.
+///
+/// All input is consumed while the map is shown, so the player doesn't also
+/// move around the hub.
+#[expect(clippy::too_many_arguments)]
+fn on_fast_travel_input(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &FastTravelButton), Changed<Interaction>>,
+    mut player_query: Query<&mut Position, With<Player>>,
+    mut gamepads: Query<&mut Gamepad>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut fast_travel: ResMut<FastTravel>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    dimensions: Res<Dimensions>,
+    tile_index: Res<TileIndex>,
+) {
+    if !fast_travel.is_open {
+        return;
+    }
+
+    let len = fast_travel.destinations.len();
+    let move_selection = |fast_travel: &mut FastTravel, delta: isize| {
+        let index = fast_travel.selection as isize + delta;
+        if (0..len as isize).contains(&index) {
+            fast_travel.selection = index as usize;
+        }
+    };
+
+    let mut is_pressed = false;
+    let mut is_closed = false;
+
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::ArrowUp => move_selection(&mut fast_travel, -(COLUMNS as isize)),
+            KeyCode::ArrowRight => move_selection(&mut fast_travel, 1),
+            KeyCode::ArrowDown => move_selection(&mut fast_travel, COLUMNS as isize),
+            KeyCode::ArrowLeft => move_selection(&mut fast_travel, -1),
+            KeyCode::Enter | KeyCode::Space => is_pressed = true,
+            KeyCode::Escape | KeyCode::KeyM => is_closed = true,
+            _ => {}
+        }
+    }
+    keys.reset_all();
+    mouse_buttons.reset_all();
+
+    for mut gamepad in &mut gamepads {
+        for button in gamepad.get_just_pressed().copied().collect::<Vec<_>>() {
+            match button {
+                GamepadButton::DPadUp => move_selection(&mut fast_travel, -(COLUMNS as isize)),
+                GamepadButton::DPadRight => move_selection(&mut fast_travel, 1),
+                GamepadButton::DPadDown => move_selection(&mut fast_travel, COLUMNS as isize),
+                GamepadButton::DPadLeft => move_selection(&mut fast_travel, -1),
+                GamepadButton::South => is_pressed = true,
+                GamepadButton::East => is_closed = true,
+                _ => {}
+            }
+            gamepad.digital_mut().clear_just_pressed(button);
+        }
+    }
+
+    for (interaction, FastTravelButton(index)) in &button_query {
+        match *interaction {
+            Interaction::Pressed => {
+                fast_travel.selection = *index;
+                is_pressed = true;
+            }
+            Interaction::Hovered => fast_travel.selection = *index,
+            Interaction::None => {}
+        }
+    }
+
+    if is_closed {
+        fast_travel.is_open = false;
+        return;
+    }
+
+    if !is_pressed {
+        return;
+    }
+
+    let Some(destination) = fast_travel.destinations.get(fast_travel.selection).copied() else {
+        return;
+    };
+    fast_travel.is_open = false;
+
+    // Standing on the entrance would enter the level, so the player is
+    // placed next to it instead.
+    let Position { x, y } = destination.entrance;
+    let target = [(0, 1), (0, -1), (-1, 0), (1, 0)]
+        .into_iter()
+        .map(|(dx, dy)| Position {
+            x: x + dx,
+            y: y + dy,
+        })
+        .find(|position| {
+            (1..=dimensions.width).contains(&position.x)
+                && (1..=dimensions.height).contains(&position.y)
+                && tile_index.get(*position).is_empty()
+        });
+    let Some(target) = target else {
+        commands.trigger(ShowToast::new(format!(
+            "Can't travel to level {}, its entrance is blocked",
+            destination.level
+        )));
+        return;
+    };
+
+    for mut player_position in &mut player_query {
+        *player_position = target;
+    }
+    background_events.send(UpdateBackgroundTransform::FastTravel);
+}
+
+fn render_fast_travel(
+    mut panel_query: Query<&mut Node, With<FastTravelPanel>>,
+    mut button_query: Query<(&FastTravelButton, &mut BackgroundColor)>,
+    mut fast_travel: ResMut<FastTravel>,
+    game_state: Res<GameState>,
+) {
+    // The hub map doesn't apply to other levels.
+    if fast_travel.is_open && !game_state.is_in_hub() {
+        fast_travel.is_open = false;
+    }
+
+    if !fast_travel.is_changed() {
+        return;
+    }
+
+    let Ok(mut panel_node) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    panel_node.display = if fast_travel.is_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    for (FastTravelButton(index), mut background_color) in &mut button_query {
+        *background_color = if *index == fast_travel.selection {
+            RED
+        } else {
+            BLUE
+        }
+        .into();
+    }
+}
@@ -4,9 +4,11 @@ mod collission_object;
 mod components;
 mod object_bundles;
 mod object_type;
+mod tile_index;
 
 pub use assets::*;
 pub use collission_object::*;
 pub use components::*;
 pub use object_bundles::*;
 pub use object_type::*;
+pub use tile_index::*;
@@ -1,141 +1,351 @@
-use bevy::prelude::*;
+use std::{fs, path::PathBuf};
 
-use crate::utils::load_asset;
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+use image::{imageops, ImageFormat, RgbaImage};
+
+use super::ObjectType;
+use crate::{skins::PlayerSkin, utils::themes_dir};
 
 pub const PLAYER_ASSET: &[u8] = include_bytes!("../../assets/sprites/player.png");
 
+/// Refers to an embedded sprite by its file name, under which a theme pack
+/// may provide a replacement.
+macro_rules! sprite_asset {
+    ($name:literal) => {
+        (
+            $name,
+            include_bytes!(concat!("../../assets/sprites/", $name)).as_slice(),
+        )
+    };
+}
+
+/// Width and height of a single sprite frame, in pixels.
+const FRAME_SIZE: u32 = 48;
+
+/// Every frame in the atlas is surrounded by a copy of its edge pixels, so that
+/// neighbouring frames don't bleed into it when sprites are scaled.
+const FRAME_PADDING: u32 = 1;
+
+const ATLAS_COLUMNS: u32 = 8;
+
+/// A sprite in the game object atlas, consisting of one or more frames.
+#[derive(Clone, Copy, Default)]
+pub struct AtlasSprite {
+    first_index: usize,
+    num_frames: usize,
+}
+
+impl AtlasSprite {
+    /// Shows the given frame of this sprite, if it has such a frame.
+    pub fn set_frame(self, sprite: &mut Sprite, frame: usize) {
+        if frame < self.num_frames {
+            if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                atlas.index = self.first_index + frame;
+            }
+        }
+    }
+
+    fn index(self, frame: usize) -> usize {
+        self.first_index + frame.min(self.num_frames.saturating_sub(1))
+    }
+}
+
+/// All the sprites for game objects.
+///
+/// The sprites are combined into a single texture atlas, so that entire levels
+/// can be rendered using only a handful of draw calls.
 #[derive(Clone, Default, Resource)]
 pub struct GameObjectAssets {
-    pub blue_block: Handle<Image>,
-    pub blue_paint: Handle<Image>,
-    pub bouncing_ball: Handle<Image>,
-    pub bouncing_ball_editor: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub button: Handle<Image>,
-    pub creature1: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub door: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub entrance: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub eraser: Handle<Image>,
-    pub explosion: Handle<Image>,
-    pub exit: Handle<Image>,
-    pub gate: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub grave: Handle<Image>,
-    pub ice: Handle<Image>,
-    pub key: Handle<Image>,
-    pub mine: Handle<Image>,
-    pub player: Handle<Image>,
-    pub purple_block: Handle<Image>,
-    pub purple_paint: Handle<Image>,
-    pub raft: Handle<Image>,
-    pub red_block: Handle<Image>,
-    pub red_paint: Handle<Image>,
-    pub splash: Handle<Image>,
-    pub teleporter: Handle<Image>,
-    pub transporter: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub water: (Handle<Image>, Handle<TextureAtlasLayout>),
-    pub yellow_block: Handle<Image>,
+    atlas: Handle<Image>,
+    atlas_layout: Handle<TextureAtlasLayout>,
+
+    pub blue_block: AtlasSprite,
+    pub blue_paint: AtlasSprite,
+    pub bomb: AtlasSprite,
+    pub bouncing_ball: AtlasSprite,
+    pub bouncing_ball_editor: AtlasSprite,
+    pub bridge: AtlasSprite,
+    pub button: AtlasSprite,
+    pub checkpoint: AtlasSprite,
+    pub creature1: AtlasSprite,
+    pub decoration: AtlasSprite,
+    pub door: AtlasSprite,
+    pub entrance: AtlasSprite,
+    pub eraser: AtlasSprite,
+    pub explosion: AtlasSprite,
+    pub exit: AtlasSprite,
+    pub gate: AtlasSprite,
+    pub grave: AtlasSprite,
+    pub ice: AtlasSprite,
+    pub key: AtlasSprite,
+    pub mine: AtlasSprite,
+    pub npc: AtlasSprite,
+
+    /// The sprite of the skin the player has chosen.
+    pub player: AtlasSprite,
+    player_skins: [AtlasSprite; PlayerSkin::ALL.len()],
+
+    pub purple_block: AtlasSprite,
+    pub purple_paint: AtlasSprite,
+    pub raft: AtlasSprite,
+    pub red_block: AtlasSprite,
+    pub red_paint: AtlasSprite,
+    pub shoreline: AtlasSprite,
+    pub sign: AtlasSprite,
+    pub splash: AtlasSprite,
+    pub teleporter: AtlasSprite,
+    pub transporter: AtlasSprite,
+    pub wall: AtlasSprite,
+    pub water: AtlasSprite,
+    pub yellow_block: AtlasSprite,
 }
 
 impl GameObjectAssets {
+    /// Loads the sprites, preferring those from the given theme pack in
+    /// `~/.chunky/themes/` over the embedded ones.
     pub fn load(
         images: &mut ResMut<Assets<Image>>,
         texture_atlas_layouts: &mut ResMut<Assets<TextureAtlasLayout>>,
+        theme: Option<&str>,
     ) -> Self {
-        let one_by_two_atlas = {
-            let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 1, 2, None, None);
-            texture_atlas_layouts.add(layout)
-        };
-        let one_by_three_atlas = {
-            let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 1, 3, None, None);
-            texture_atlas_layouts.add(layout)
+        let mut atlas = AtlasBuilder {
+            theme_dir: theme.map(|theme| themes_dir().join(theme)),
+            ..default()
         };
-        let one_by_four_atlas = {
-            let layout = TextureAtlasLayout::from_grid(UVec2::splat(48), 1, 4, None, None);
-            texture_atlas_layouts.add(layout)
+
+        let mut assets = Self {
+            atlas: Handle::default(),
+            atlas_layout: Handle::default(),
+            blue_block: atlas.add(sprite_asset!("blueblock.png")),
+            blue_paint: atlas.add(sprite_asset!("bluepaint.png")),
+            bomb: atlas.add(sprite_asset!("bomb.png")),
+            bouncing_ball: atlas.add(sprite_asset!("greenball.png")),
+            bouncing_ball_editor: atlas.add(sprite_asset!("greenball_editor.png")),
+            bridge: atlas.add(sprite_asset!("bridge.png")),
+            button: atlas.add(sprite_asset!("button.png")),
+            checkpoint: atlas.add(sprite_asset!("checkpoint.png")),
+            creature1: atlas.add(sprite_asset!("creature1.png")),
+            decoration: atlas.add(sprite_asset!("decoration.png")),
+            door: atlas.add(sprite_asset!("door.png")),
+            entrance: atlas.add(sprite_asset!("entrance.png")),
+            eraser: atlas.add(sprite_asset!("eraser.png")),
+            exit: atlas.add(sprite_asset!("exit.png")),
+            explosion: atlas.add(sprite_asset!("explosion.png")),
+            gate: atlas.add(sprite_asset!("gate.png")),
+            grave: atlas.add(sprite_asset!("grave.png")),
+            ice: atlas.add(sprite_asset!("ice.png")),
+            key: atlas.add(sprite_asset!("key.png")),
+            mine: atlas.add(sprite_asset!("mine.png")),
+            npc: atlas.add(sprite_asset!("npc.png")),
+            player: AtlasSprite::default(),
+            player_skins: PlayerSkin::ALL.map(|skin| match skin.tint() {
+                Some(tint) => atlas.add_tinted(("player.png", PLAYER_ASSET), tint),
+                None => atlas.add(("player.png", PLAYER_ASSET)),
+            }),
+            purple_block: atlas.add(sprite_asset!("purpleblock.png")),
+            purple_paint: atlas.add(sprite_asset!("purplepaint.png")),
+            raft: atlas.add(sprite_asset!("raft.png")),
+            red_block: atlas.add(sprite_asset!("redblock.png")),
+            red_paint: atlas.add(sprite_asset!("redpaint.png")),
+            shoreline: atlas.add(sprite_asset!("shoreline.png")),
+            sign: atlas.add(sprite_asset!("sign.png")),
+            splash: atlas.add(sprite_asset!("splash.png")),
+            teleporter: atlas.add(sprite_asset!("teleporter.png")),
+            transporter: atlas.add(sprite_asset!("transporter.png")),
+            wall: atlas.add(sprite_asset!("wall.png")),
+            water: atlas.add(sprite_asset!("water.png")),
+            yellow_block: atlas.add(sprite_asset!("yellowblock.png")),
         };
 
-        Self {
-            blue_block: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/blueblock.png"
-            ))),
-            blue_paint: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/bluepaint.png"
-            ))),
-            bouncing_ball: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/greenball.png"
-            ))),
-            bouncing_ball_editor: (
-                images.add(load_asset(include_bytes!(
-                    "../../assets/sprites/greenball_editor.png"
-                ))),
-                one_by_four_atlas.clone(),
-            ),
-            button: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/button.png"
-            ))),
-            creature1: (
-                images.add(load_asset(include_bytes!(
-                    "../../assets/sprites/creature1.png"
-                ))),
-                one_by_four_atlas.clone(),
-            ),
-            door: (
-                images.add(load_asset(include_bytes!("../../assets/sprites/door.png"))),
-                one_by_two_atlas.clone(),
-            ),
-            entrance: (
-                images.add(load_asset(include_bytes!(
-                    "../../assets/sprites/entrance.png"
-                ))),
-                one_by_two_atlas.clone(),
-            ),
-            eraser: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/eraser.png"
-            ))),
-            exit: images.add(load_asset(include_bytes!("../../assets/sprites/exit.png"))),
-            explosion: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/explosion.png"
-            ))),
-            gate: (
-                images.add(load_asset(include_bytes!("../../assets/sprites/gate.png"))),
-                one_by_two_atlas,
-            ),
-            grave: images.add(load_asset(include_bytes!("../../assets/sprites/grave.png"))),
-            ice: images.add(load_asset(include_bytes!("../../assets/sprites/ice.png"))),
-            key: images.add(load_asset(include_bytes!("../../assets/sprites/key.png"))),
-            mine: images.add(load_asset(include_bytes!("../../assets/sprites/mine.png"))),
-            player: images.add(load_asset(PLAYER_ASSET)),
-            purple_block: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/purpleblock.png"
-            ))),
-            purple_paint: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/purplepaint.png"
-            ))),
-            raft: images.add(load_asset(include_bytes!("../../assets/sprites/raft.png"))),
-            red_block: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/redblock.png"
-            ))),
-            red_paint: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/redpaint.png"
-            ))),
-            splash: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/splash.png"
-            ))),
-            teleporter: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/teleporter.png"
-            ))),
-            transporter: (
-                images.add(load_asset(include_bytes!(
-                    "../../assets/sprites/transporter.png"
-                ))),
-                one_by_four_atlas,
-            ),
-            water: (
-                images.add(load_asset(include_bytes!("../../assets/sprites/water.png"))),
-                one_by_three_atlas,
-            ),
-            yellow_block: images.add(load_asset(include_bytes!(
-                "../../assets/sprites/yellowblock.png"
-            ))),
+        assets.set_player_skin(PlayerSkin::default());
+
+        let (image, layout) = atlas.build();
+        assets.atlas = images.add(image);
+        assets.atlas_layout = texture_atlas_layouts.add(layout);
+        assets
+    }
+
+    /// Uses the sprite of the given skin for players.
+    pub fn set_player_skin(&mut self, skin: PlayerSkin) {
+        let index = PlayerSkin::ALL
+            .iter()
+            .position(|candidate| *candidate == skin)
+            .unwrap_or_default();
+        self.player = self.player_skins[index];
+    }
+
+    /// Returns the sprite used for objects of the given type.
+    pub fn for_object_type(&self, object_type: ObjectType) -> AtlasSprite {
+        match object_type {
+            ObjectType::BlueBlock => self.blue_block,
+            ObjectType::BluePaint => self.blue_paint,
+            ObjectType::Bomb => self.bomb,
+            ObjectType::BouncingBall => self.bouncing_ball,
+            ObjectType::Bridge => self.bridge,
+            ObjectType::Button => self.button,
+            ObjectType::Checkpoint => self.checkpoint,
+            ObjectType::Creature1 => self.creature1,
+            ObjectType::Decoration => self.decoration,
+            ObjectType::Door => self.door,
+            ObjectType::Entrance => self.entrance,
+            ObjectType::Exit => self.exit,
+            ObjectType::Explosion => self.explosion,
+            ObjectType::Gate => self.gate,
+            ObjectType::Grave => self.grave,
+            ObjectType::Ice => self.ice,
+            ObjectType::Key => self.key,
+            ObjectType::Mine => self.mine,
+            ObjectType::Npc => self.npc,
+            ObjectType::Player => self.player,
+            ObjectType::PurpleBlock => self.purple_block,
+            ObjectType::PurplePaint => self.purple_paint,
+            ObjectType::Raft => self.raft,
+            ObjectType::RedBlock => self.red_block,
+            ObjectType::RedPaint => self.red_paint,
+            ObjectType::Sign => self.sign,
+            ObjectType::Splash => self.splash,
+            ObjectType::Teleporter => self.teleporter,
+            ObjectType::TimerGate => self.gate,
+            ObjectType::Transporter => self.transporter,
+            ObjectType::Wall => self.wall,
+            ObjectType::Water => self.water,
+            ObjectType::YellowBlock => self.yellow_block,
+        }
+    }
+
+    /// Returns a UI image showing the given frame of the sprite.
+    pub fn image_node(&self, sprite: AtlasSprite, frame: usize) -> ImageNode {
+        ImageNode::from_atlas_image(self.atlas.clone(), self.texture_atlas(sprite, frame))
+    }
+
+    /// Returns a sprite showing its given frame.
+    pub fn sprite(&self, sprite: AtlasSprite, frame: usize) -> Sprite {
+        Sprite::from_atlas_image(self.atlas.clone(), self.texture_atlas(sprite, frame))
+    }
+
+    fn texture_atlas(&self, sprite: AtlasSprite, frame: usize) -> TextureAtlas {
+        TextureAtlas {
+            layout: self.atlas_layout.clone(),
+            index: sprite.index(frame),
+        }
+    }
+}
+
+/// Collects the frames of all sprites, so they can be combined into a single
+/// atlas.
+#[derive(Default)]
+struct AtlasBuilder {
+    frames: Vec<RgbaImage>,
+
+    /// Directory of the theme pack with replacements for the embedded
+    /// sprites, if one is selected.
+    theme_dir: Option<PathBuf>,
+}
+
+impl AtlasBuilder {
+    /// Adds the frames of a sprite, which are expected to be stacked vertically
+    /// in the given PNG image.
+    ///
+    /// The image is taken from the theme pack if it has a file with the same
+    /// name, falling back to the embedded bytes if there is no such file or
+    /// it can't be loaded.
+    fn add(&mut self, (name, bytes): (&str, &[u8])) -> AtlasSprite {
+        let image = self
+            .load_theme_image(name)
+            .unwrap_or_else(|| {
+                image::load_from_memory_with_format(bytes, ImageFormat::Png)
+                    .expect("cannot load game object asset")
+            })
+            .into_rgba8();
+
+        let first_index = self.frames.len();
+        let num_frames = (image.height() / FRAME_SIZE).max(1);
+        for frame in 0..num_frames {
+            let frame = imageops::crop_imm(&image, 0, frame * FRAME_SIZE, FRAME_SIZE, FRAME_SIZE);
+            self.frames.push(frame.to_image());
+        }
+
+        AtlasSprite {
+            first_index,
+            num_frames: num_frames as usize,
+        }
+    }
+
+    /// Adds the frames of a sprite like [Self::add], with the brightness of
+    /// every pixel multiplied by the given tint.
+    fn add_tinted(&mut self, asset: (&str, &[u8]), tint: [f32; 3]) -> AtlasSprite {
+        let sprite = self.add(asset);
+        for frame in &mut self.frames[sprite.first_index..] {
+            for pixel in frame.pixels_mut() {
+                let [r, g, b, a] = pixel.0;
+                let brightness = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+                let channel = |tint: f32| (brightness * tint).clamp(0., 255.) as u8;
+                pixel.0 = [channel(tint[0]), channel(tint[1]), channel(tint[2]), a];
+            }
+        }
+        sprite
+    }
+
+    fn load_theme_image(&self, name: &str) -> Option<image::DynamicImage> {
+        let path = self.theme_dir.as_ref()?.join(name);
+        if !path.exists() {
+            return None;
+        }
+
+        fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| {
+                image::load_from_memory_with_format(&bytes, ImageFormat::Png)
+                    .map_err(anyhow::Error::from)
+            })
+            .map_err(|err| warn!("Can't load themed sprite {path:?}: {err}"))
+            .ok()
+    }
+
+    fn build(self) -> (Image, TextureAtlasLayout) {
+        let cell_size = FRAME_SIZE + 2 * FRAME_PADDING;
+        let rows = (self.frames.len() as u32).div_ceil(ATLAS_COLUMNS);
+
+        let mut atlas = RgbaImage::new(ATLAS_COLUMNS * cell_size, rows * cell_size);
+        let mut layout = TextureAtlasLayout::new_empty(UVec2::new(atlas.width(), atlas.height()));
+        for (index, frame) in self.frames.iter().enumerate() {
+            let x = (index as u32 % ATLAS_COLUMNS) * cell_size;
+            let y = (index as u32 / ATLAS_COLUMNS) * cell_size;
+
+            // Fill the entire cell by stretching the frame one pixel in every
+            // direction, then draw the frame itself on top of it.
+            let padded_frame =
+                imageops::resize(frame, cell_size, cell_size, imageops::FilterType::Nearest);
+            imageops::replace(&mut atlas, &padded_frame, x.into(), y.into());
+            imageops::replace(
+                &mut atlas,
+                frame,
+                (x + FRAME_PADDING).into(),
+                (y + FRAME_PADDING).into(),
+            );
+
+            let min = UVec2::new(x + FRAME_PADDING, y + FRAME_PADDING);
+            layout.add_texture(URect::from_corners(min, min + UVec2::splat(FRAME_SIZE)));
         }
+
+        let image = Image::new(
+            Extent3d {
+                width: atlas.width(),
+                height: atlas.height(),
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            atlas.into_raw(),
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::all(),
+        );
+
+        (image, layout)
     }
 }
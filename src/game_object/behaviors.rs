@@ -1,54 +1,168 @@
-use std::{cmp::Ordering, collections::BTreeSet};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+};
 
-use bevy::prelude::*;
-use rand::{thread_rng, Rng};
+use bevy::{ecs::query::QueryFilter, prelude::*};
+use rand::Rng;
 
 use crate::{
+    achievements::AchievementProgress,
     background::UpdateBackgroundTransform,
+    constants::{LOCKED_ENTRANCE, SKIPPED_ENTRANCE},
+    credits::PlayCredits,
     editor::EditorState,
     game_object::Pushable,
-    game_state::GameState,
+    game_state::{DeathCause, GameState, Killer},
+    leaderboard::SubmitScore,
+    level_complete::ShowLevelComplete,
     levels::{Dimensions, InitialPositionAndMetadata},
-    timers::{AnimationTimer, MovementTimer, TemporaryTimer, TransporterTimer},
-    ExitState, PressedTriggers, SaveLevel, SpawnObject,
+    menu::MenuState,
+    photo_mode::PhotoMode,
+    practice_mode::PracticeMode,
+    rng::GameRng,
+    timers::{AnimationTimer, MovementTimer, SlideTimer, TemporaryTimer, TransporterTimer},
+    toast::ShowToast,
+    ExitState, LevelCheckpoint, PressedTriggers, SaveLevel, SaveTarget, SpawnObject,
 };
 
 use super::{
     collission_object::{CollisionObject, CollisionObjectQuery},
     components::{Animatable, Direction, Trigger, *},
-    ObjectType,
+    tile_index::TileIndex,
+    GameObjectAssets, ObjectType,
 };
 
+/// How long decorations take to grow into view once they're revealed, in
+/// seconds.
+const REVEAL_DURATION: f32 = 0.6;
+
 pub fn animate_objects(
     mut timer: ResMut<AnimationTimer>,
     time: Res<Time>,
-    mut query: Query<(&Animatable, &mut Sprite)>,
+    mut query: Query<(&Animatable, &ObjectType, &mut Sprite)>,
+    mut rng: ResMut<GameRng>,
+    assets: Res<GameObjectAssets>,
 ) {
     timer.tick(time.delta());
     if timer.just_finished() {
-        for (animatable, mut sprite) in &mut query {
-            if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                atlas.index = thread_rng().gen_range(0..animatable.num_frames);
+        for (animatable, object_type, mut sprite) in &mut query {
+            let frame = rng.gen_range(0..animatable.num_frames);
+            assets
+                .for_object_type(*object_type)
+                .set_frame(&mut sprite, frame);
+        }
+    }
+}
+
+/// Picks the sprite variant and tint of newly spawned [Variation] entities.
+pub fn apply_variations(
+    mut query: Query<(&Variation, &ObjectType, &Position, &mut Sprite), Added<Variation>>,
+    assets: Res<GameObjectAssets>,
+) {
+    for (variation, object_type, position, mut sprite) in &mut query {
+        let hash = position.seed();
+        assets
+            .for_object_type(*object_type)
+            .set_frame(&mut sprite, hash as usize % variation.num_variants.max(1));
+
+        // Take a few bits of the hash per channel to darken each by up to 6%,
+        // which shifts the hue ever so slightly.
+        let channel = |shift: u32| 1. - 0.06 * ((hash >> shift) & 0xff) as f32 / 255.;
+        sprite.color = Color::srgb(channel(8), channel(16), channel(24));
+    }
+}
+
+/// Collapses bridges into water when the total weight on them exceeds their
+/// capacity.
+///
+/// The objects on a collapsing bridge are marked as moved, so that they sink
+/// into the water unless something keeps them afloat.
+pub fn check_for_bridges(
+    mut commands: Commands,
+    bridge_query: Query<(Entity, &Bridge, &Position)>,
+    mut objects_query: Query<(&mut Position, &Weight), Without<Bridge>>,
+    editor_state: Res<EditorState>,
+    tile_index: Res<TileIndex>,
+) {
+    if editor_state.is_open {
+        return;
+    }
+
+    for (bridge, Bridge(capacity), position) in &bridge_query {
+        let total_weight: u16 = objects_query
+            .iter_many(tile_index.get(*position))
+            .map(|(_, weight)| weight.0)
+            .sum();
+        if total_weight <= *capacity {
+            continue;
+        }
+
+        commands.entity(bridge).despawn();
+        commands.trigger(SpawnObject {
+            object_type: ObjectType::Water,
+            position: position.into(),
+        });
+
+        let mut objects = objects_query.iter_many_mut(tile_index.get(*position));
+        while let Some((mut position, _)) = objects.fetch_next() {
+            position.set_changed();
+        }
+    }
+}
+
+/// Takes a snapshot of the level when the player steps onto a checkpoint.
+pub fn check_for_checkpoint(
+    mut commands: Commands,
+    player_query: Query<Ref<Position>, With<Player>>,
+    checkpoint_query: Query<(), With<Checkpoint>>,
+    level_checkpoint: Res<LevelCheckpoint>,
+    tile_index: Res<TileIndex>,
+) {
+    for player_position in &player_query {
+        // Players that were just (re)spawned on a checkpoint haven't reached it.
+        if player_position.is_added() || !player_position.is_changed() {
+            continue;
+        }
+
+        if checkpoint_query
+            .iter_many(tile_index.get(*player_position))
+            .next()
+            .is_some()
+        {
+            if level_checkpoint.position != Some(*player_position) {
+                commands.trigger(ShowToast::new("Checkpoint reached"));
             }
+
+            commands.trigger(SaveLevel {
+                target: SaveTarget::Checkpoint(*player_position),
+            });
         }
     }
 }
 
 pub fn check_for_deadly(
     mut commands: Commands,
-    deadly_query: Query<(Entity, &Position), With<Deadly>>,
+    deadly_query: Query<(Entity, &ObjectType), With<Deadly>>,
     player_query: Query<(Entity, &Position), With<Player>>,
+    mut game_state: ResMut<GameState>,
+    tile_index: Res<TileIndex>,
 ) {
     for (player, player_position) in &player_query {
-        for (deadly, deadly_position) in &deadly_query {
-            if player_position == deadly_position {
-                commands.entity(player).despawn();
-                commands.entity(deadly).despawn();
-                commands.trigger(SpawnObject {
-                    object_type: ObjectType::Grave,
-                    position: player_position.into(),
-                });
-            }
+        for (deadly, object_type) in deadly_query.iter_many(tile_index.get(*player_position)) {
+            game_state.record_death(
+                DeathCause::Deadly,
+                Some(Killer {
+                    object_type: *object_type,
+                    position: *player_position,
+                }),
+            );
+            commands.entity(player).despawn();
+            commands.entity(deadly).despawn();
+            commands.trigger(SpawnObject {
+                object_type: ObjectType::Grave,
+                position: player_position.into(),
+            });
         }
     }
 }
@@ -56,48 +170,87 @@ pub fn check_for_deadly(
 pub fn check_for_entrance(
     mut commands: Commands,
     player_query: Query<Ref<Position>, With<Player>>,
-    entrance_query: Query<(&Entrance, &Position)>,
+    entrance_query: Query<&Entrance>,
     mut background_events: EventWriter<UpdateBackgroundTransform>,
     mut exit_state: ResMut<ExitState>,
+    mut game_state: ResMut<GameState>,
+    tile_index: Res<TileIndex>,
 ) {
     for player_position in &player_query {
         if player_position.is_added() || !player_position.is_changed() {
             continue;
         }
 
-        for (entrance, entrance_position) in &entrance_query {
-            if player_position.as_ref() == entrance_position {
-                commands.trigger(SaveLevel {
-                    save_to_disk: false,
-                });
-                exit_state.next_level = Some(entrance.0);
-                background_events.send(UpdateBackgroundTransform::LevelExit);
-                return;
+        if let Some(entrance) = entrance_query
+            .iter_many(tile_index.get(*player_position))
+            .next()
+        {
+            if game_state.is_in_hub() {
+                game_state.record_discovered_entrance(entrance.0);
             }
+            commands.trigger(SaveLevel {
+                target: SaveTarget::Memory,
+            });
+            exit_state.next_level = Some(entrance.0);
+            background_events.send(UpdateBackgroundTransform::LevelExit);
+            return;
         }
     }
 }
 
+#[expect(clippy::too_many_arguments)]
 pub fn check_for_exit(
+    mut commands: Commands,
     player_query: Query<Ref<Position>, With<Player>>,
-    exit_query: Query<&Position, With<Exit>>,
+    exit_query: Query<&Exit>,
     mut background_events: EventWriter<UpdateBackgroundTransform>,
     mut exit_state: ResMut<ExitState>,
     mut game_state: ResMut<GameState>,
+    practice_mode: Res<PracticeMode>,
+    tile_index: Res<TileIndex>,
+    time: Res<Time>,
 ) {
     for player_position in &player_query {
         if player_position.is_added() || !player_position.is_changed() {
             continue;
         }
 
-        for exit_position in &exit_query {
-            if player_position.as_ref() == exit_position {
-                let finished_level = game_state.current_level;
-                game_state.finished_levels.insert(finished_level);
-                exit_state.next_level = Some(0);
-                background_events.send(UpdateBackgroundTransform::LevelExit);
+        if let Some(Exit(secret_level)) = exit_query
+            .iter_many(tile_index.get(*player_position))
+            .next()
+        {
+            if practice_mode.is_active() {
+                commands.trigger(ShowToast::new(
+                    "Exit reached, but levels aren't finished in practice mode",
+                ));
                 return;
             }
+
+            if secret_level.is_some() && game_state.record_secret_exit() {
+                commands.trigger(ShowToast::new("Secret exit found!"));
+            }
+            let result = game_state.finish_current_level(time.elapsed());
+            commands.trigger(SubmitScore(game_state.current_level));
+            if game_state.check_for_completion() {
+                commands.trigger(PlayCredits);
+            }
+            commands.trigger(AchievementProgress::LevelFinished);
+            exit_state.next_level = Some(secret_level.unwrap_or(game_state.current_hub));
+
+            // The completion screen lets the player choose where to go
+            // next, but the demo just goes back to the hub.
+            match result {
+                Some(result) if !game_state.is_playing_demo => {
+                    commands.trigger(ShowLevelComplete {
+                        result,
+                        secret_level: *secret_level,
+                    });
+                }
+                _ => {
+                    background_events.send(UpdateBackgroundTransform::LevelExit);
+                }
+            }
+            return;
         }
     }
 }
@@ -105,22 +258,34 @@ pub fn check_for_exit(
 #[expect(clippy::type_complexity)]
 pub fn check_for_explosive(
     mut commands: Commands,
-    explosive_query: Query<(Entity, &Position), With<Explosive>>,
-    moved_objects_query: Query<(Entity, &Position), (Changed<Position>, Without<Explosive>)>,
+    explosive_query: Query<(Entity, &ObjectType), With<Explosive>>,
+    moved_objects_query: Query<
+        (Entity, &Position, Has<Player>),
+        (Changed<Position>, Without<Explosive>),
+    >,
+    mut game_state: ResMut<GameState>,
     mut temporary_timer: ResMut<TemporaryTimer>,
+    tile_index: Res<TileIndex>,
 ) {
-    for (object, position) in &moved_objects_query {
-        for (explosive, explosive_position, ..) in &explosive_query {
-            if explosive_position == position {
-                commands.entity(explosive).despawn();
-                commands.entity(object).despawn();
-                commands.trigger(SpawnObject {
-                    object_type: ObjectType::Explosion,
-                    position: position.into(),
-                });
-                if temporary_timer.finished() {
-                    temporary_timer.reset();
-                }
+    for (object, position, is_player) in &moved_objects_query {
+        for (explosive, object_type) in explosive_query.iter_many(tile_index.get(*position)) {
+            if is_player {
+                game_state.record_death(
+                    DeathCause::Exploded,
+                    Some(Killer {
+                        object_type: *object_type,
+                        position: *position,
+                    }),
+                );
+            }
+            commands.entity(explosive).despawn();
+            commands.entity(object).despawn();
+            commands.trigger(SpawnObject {
+                object_type: ObjectType::Explosion,
+                position: position.into(),
+            });
+            if temporary_timer.finished() {
+                temporary_timer.reset();
             }
         }
     }
@@ -131,39 +296,182 @@ pub fn check_for_finished_levels(
     mut commands: Commands,
     mut query: Query<(
         Entity,
+        &ObjectType,
         Option<&Entrance>,
         Option<&Openable>,
         Option<&Massive>,
         &mut Sprite,
     )>,
+    assets: Res<GameObjectAssets>,
     game_state: Res<GameState>,
 ) {
     if !game_state.is_changed() {
         return;
     }
 
-    for (entity, entrance, openable, massive, mut sprite) in &mut query {
+    let num_finished_levels = game_state
+        .finished_levels
+        .iter()
+        .filter(|level| !game_state.is_hub_level(**level))
+        .count();
+
+    for (entity, object_type, entrance, openable, massive, mut sprite) in &mut query {
+        let atlas_sprite = assets.for_object_type(*object_type);
         if let Some(entrance) = entrance {
-            if game_state.finished_levels.contains(&entrance.0) {
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 1;
-                }
+            let finished = game_state.finished_levels.contains(&entrance.0);
+            atlas_sprite.set_frame(&mut sprite, if finished { 1 } else { 0 });
+
+            // Skipped levels are tinted, so they stand apart from levels
+            // that haven't been tried yet.
+            sprite.color = if game_state.skipped_levels.contains(&entrance.0) {
+                SKIPPED_ENTRANCE
+            } else {
+                Color::WHITE
+            };
+        }
+
+        let opened = match openable {
+            Some(Openable::LevelFinished(level)) => game_state.finished_levels.contains(level),
+            Some(Openable::FinishedCount(count)) => num_finished_levels >= *count as usize,
+            _ => continue,
+        };
+        if opened && massive.is_some() {
+            commands.entity(entity).remove::<Massive>();
+        } else if !opened && massive.is_none() {
+            commands.entity(entity).insert(Massive);
+        }
+
+        // Entrances use their frames to show whether their level is finished,
+        // so locked entrances are dimmed instead.
+        if entrance.is_some() {
+            if !opened {
+                sprite.color = LOCKED_ENTRANCE;
             }
-        } else if let Some(Openable::LevelFinished(level)) = openable {
-            let opened = game_state.finished_levels.contains(level);
-            if opened && massive.is_some() {
-                commands.entity(entity).remove::<Massive>();
+        } else {
+            atlas_sprite.set_frame(&mut sprite, if opened { 1 } else { 0 });
+        }
+    }
+}
 
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 1;
-                }
-            } else if !opened && massive.is_none() {
-                commands.entity(entity).insert(Massive);
+/// Shows the decorations that are revealed by finishing a level, which are
+/// kept at zero scale until then. Decorations for the level the player just
+/// came back from grow into view, while others appear right away.
+///
+/// The editor shows all decorations, so they can be edited.
+pub fn reveal_decorations(
+    mut query: Query<(Ref<RevealedByLevel>, &mut Transform)>,
+    editor_state: Res<EditorState>,
+    game_state: Res<GameState>,
+    time: Res<Time>,
+) {
+    for (revealed_by_level, mut transform) in &mut query {
+        let RevealedByLevel(level) = *revealed_by_level;
+        let is_revealed = editor_state.is_open || game_state.finished_levels.contains(&level);
+
+        let scale = if revealed_by_level.is_added() {
+            let grows =
+                is_revealed && !editor_state.is_open && game_state.previous_level == Some(level);
+            if is_revealed && !grows {
+                1.
+            } else {
+                0.
+            }
+        } else if is_revealed {
+            (transform.scale.x + time.delta_secs() / REVEAL_DURATION).min(1.)
+        } else {
+            0.
+        };
+
+        if transform.scale.x != scale {
+            transform.scale = Vec3::new(scale, scale, 1.);
+        }
+    }
+}
 
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 0;
+pub fn update_best_moves_labels(
+    mut label_query: Query<(&Parent, &mut Text2d, Ref<BestMovesLabel>)>,
+    entrance_query: Query<&Entrance>,
+    game_state: Res<GameState>,
+) {
+    for (parent, mut text, label) in &mut label_query {
+        if !game_state.is_changed() && !label.is_added() {
+            continue;
+        }
+
+        let Ok(entrance) = entrance_query.get(parent.get()) else {
+            continue;
+        };
+
+        let best_moves = game_state
+            .level_stats
+            .get(&entrance.0)
+            .and_then(|stats| stats.best_moves);
+        text.0 = best_moves
+            .map(|moves| format!("{moves} moves"))
+            .unwrap_or_default();
+    }
+}
+
+pub fn update_checkpoint_sprites(
+    mut checkpoint_query: Query<(&Position, &mut Sprite, Ref<Checkpoint>)>,
+    assets: Res<GameObjectAssets>,
+    level_checkpoint: Res<LevelCheckpoint>,
+) {
+    for (position, mut sprite, checkpoint) in &mut checkpoint_query {
+        if level_checkpoint.is_changed() || checkpoint.is_added() {
+            let is_reached = level_checkpoint.position == Some(*position);
+            assets
+                .checkpoint
+                .set_frame(&mut sprite, if is_reached { 1 } else { 0 });
+        }
+    }
+}
+
+/// Picks the frames of [Autotile] entities based on their neighbours, whenever
+/// one of them is spawned, moved or despawned.
+pub fn update_autotiles(
+    mut autotile_query: Query<
+        (&ObjectType, &Position, &mut Sprite, Option<&Children>),
+        With<Autotile>,
+    >,
+    mut shoreline_query: Query<&mut Sprite, (With<Shoreline>, Without<Autotile>)>,
+    changed_query: Query<(), (With<Autotile>, Changed<Position>)>,
+    mut removed: RemovedComponents<Autotile>,
+    assets: Res<GameObjectAssets>,
+) {
+    let has_removed = removed.read().count() > 0;
+    if changed_query.is_empty() && !has_removed {
+        return;
+    }
+
+    let tiles: BTreeSet<(ObjectType, Position)> = autotile_query
+        .iter()
+        .map(|(object_type, position, ..)| (*object_type, *position))
+        .collect();
+
+    for (object_type, position, mut sprite, children) in &mut autotile_query {
+        let frame = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (dx, dy))| {
+                let neighbour = Position {
+                    x: position.x + dx,
+                    y: position.y + dy,
+                };
+                tiles.contains(&(*object_type, neighbour))
+            })
+            .fold(0, |frame, (bit, _)| frame | 1 << bit);
+
+        match object_type {
+            ObjectType::Water => {
+                let mut shorelines = shoreline_query.iter_many_mut(children.into_iter().flatten());
+                while let Some(mut sprite) = shorelines.fetch_next() {
+                    assets.shoreline.set_frame(&mut sprite, frame);
                 }
             }
+            object_type => assets
+                .for_object_type(*object_type)
+                .set_frame(&mut sprite, frame),
         }
     }
 }
@@ -171,19 +479,24 @@ pub fn check_for_finished_levels(
 #[expect(clippy::type_complexity)]
 pub fn check_for_key(
     mut commands: Commands,
-    mut openable_query: Query<(Entity, &Position, &Openable, &mut Sprite)>,
+    mut openable_query: Query<(Entity, &ObjectType, &Openable, &mut Sprite)>,
     moved_keys_query: Query<(Entity, &Position), (Changed<Position>, With<Key>)>,
+    assets: Res<GameObjectAssets>,
+    tile_index: Res<TileIndex>,
 ) {
     for (key_entity, key_position) in &moved_keys_query {
-        for (openable_entity, openable_position, openable, mut sprite) in &mut openable_query {
-            if matches!(openable, Openable::Key) && key_position == openable_position {
+        let mut openables = openable_query.iter_many_mut(tile_index.get(*key_position));
+        while let Some((openable_entity, object_type, openable, mut sprite)) =
+            openables.fetch_next()
+        {
+            if matches!(openable, Openable::Key) {
                 commands.entity(key_entity).despawn();
                 commands.entity(openable_entity).remove::<Massive>();
                 commands.entity(openable_entity).remove::<Openable>();
 
-                if let Some(atlas) = sprite.texture_atlas.as_mut() {
-                    atlas.index = 1;
-                }
+                assets
+                    .for_object_type(*object_type)
+                    .set_frame(&mut sprite, 1);
             }
         }
     }
@@ -192,40 +505,55 @@ pub fn check_for_key(
 #[expect(clippy::type_complexity)]
 pub fn check_for_liquid(
     mut commands: Commands,
-    liquid_query: Query<&Position, With<Liquid>>,
+    liquid_query: Query<&ObjectType, With<Liquid>>,
     moved_objects_query: Query<
-        (Entity, &Position, Option<&Floatable>),
+        (
+            Entity,
+            &Position,
+            Option<&Floatable>,
+            Has<Player>,
+            Has<Movable>,
+        ),
         (Changed<Position>, Without<Liquid>),
     >,
-    floatable_objects_query: Query<(Entity, &Position), With<Floatable>>,
+    floatable_objects_query: Query<Entity, With<Floatable>>,
+    mut game_state: ResMut<GameState>,
     mut temporary_timer: ResMut<TemporaryTimer>,
+    tile_index: Res<TileIndex>,
 ) {
-    for (object, position, floatable) in &moved_objects_query {
-        for liquid_position in &liquid_query {
-            if liquid_position == position {
-                if floatable.is_some() {
-                    if !floatable_objects_query
-                        .iter()
-                        .any(|(other, other_position)| {
-                            other != object && other_position == position
-                        })
-                    {
-                        let mut object = commands.entity(object);
-                        object.remove::<Pushable>();
-                    }
-                } else if !floatable_objects_query
-                    .iter()
-                    .any(|(_, other_position)| other_position == position)
-                {
-                    commands.entity(object).despawn();
-                    commands.trigger(SpawnObject {
-                        object_type: ObjectType::Splash,
-                        position: position.into(),
-                    });
-                    if temporary_timer.finished() {
-                        temporary_timer.reset();
-                    }
-                }
+    for (object, position, floatable, is_player, is_creature) in &moved_objects_query {
+        let entities = tile_index.get(*position);
+        let Some(liquid_type) = liquid_query.iter_many(entities).next().copied() else {
+            continue;
+        };
+
+        if floatable.is_some() {
+            if !floatable_objects_query
+                .iter_many(entities)
+                .any(|other| other != object)
+            {
+                let mut object = commands.entity(object);
+                object.remove::<Pushable>();
+            }
+        } else if floatable_objects_query.iter_many(entities).next().is_none() {
+            if is_player {
+                game_state.record_death(
+                    DeathCause::Drowned,
+                    Some(Killer {
+                        object_type: liquid_type,
+                        position: *position,
+                    }),
+                );
+            } else if is_creature {
+                commands.trigger(AchievementProgress::CreatureSunk);
+            }
+            commands.entity(object).despawn();
+            commands.trigger(SpawnObject {
+                object_type: ObjectType::Splash,
+                position: position.into(),
+            });
+            if temporary_timer.finished() {
+                temporary_timer.reset();
             }
         }
     }
@@ -234,23 +562,23 @@ pub fn check_for_liquid(
 pub fn check_for_paint(
     mut commands: Commands,
     moved_paint_query: Query<(Entity, &ObjectType, &Position, &Paint), Changed<Position>>,
-    all_paint_query: Query<(Entity, &ObjectType, &Position), With<Paint>>,
-    paintable_query: Query<(Entity, &Position), With<Paintable>>,
+    all_paint_query: Query<(Entity, &ObjectType), With<Paint>>,
+    paintable_query: Query<Entity, With<Paintable>>,
+    tile_index: Res<TileIndex>,
 ) {
     for (paint_entity, paint_type, paint_position, paint) in &moved_paint_query {
-        for (paintable_entity, paintable_position) in &paintable_query {
-            if paint_position == paintable_position {
-                commands.entity(paint_entity).despawn();
-                commands.entity(paintable_entity).despawn();
-                commands.trigger(SpawnObject {
-                    object_type: paint.0,
-                    position: paintable_position.into(),
-                });
-            }
+        let entities = tile_index.get(*paint_position);
+        for paintable_entity in paintable_query.iter_many(entities) {
+            commands.entity(paint_entity).despawn();
+            commands.entity(paintable_entity).despawn();
+            commands.trigger(SpawnObject {
+                object_type: paint.0,
+                position: paint_position.into(),
+            });
         }
 
-        for (other_entity, other_type, other_position) in &all_paint_query {
-            if paint_entity != other_entity && paint_position == other_position {
+        for (other_entity, other_type) in all_paint_query.iter_many(entities) {
+            if paint_entity != other_entity {
                 if let Some(mixed_type) = paint_type.mix_with(*other_type) {
                     commands.entity(paint_entity).despawn();
                     commands.entity(other_entity).despawn();
@@ -284,31 +612,41 @@ pub fn check_for_transform_on_push(
                 object_type: *object_type,
                 position: InitialPositionAndMetadata {
                     position: *position,
+                    decoration: None,
                     direction: direction.copied(),
                     identifier: None,
                     level: None,
                     open: false,
+                    capacity: None,
+                    interval: None,
+                    required_count: None,
+                    seconds: None,
+                    text: None,
                 },
             });
         }
     }
 }
 
-#[expect(clippy::type_complexity)]
-pub fn check_for_slippery_and_transporter(
+/// Slides the objects on slippery entities one tile further in their
+/// direction. Slides run on their own [SlideTimer], which is faster than the
+/// transporters, and every step glides over using [Glide].
+///
+/// Sliders are moved in the order of [sort_transportees].
+#[expect(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn check_for_slippery(
+    mut commands: Commands,
     mut slippery_query: Query<
-        (&Position, &mut BlocksMovement),
+        (Entity, &Position, &mut BlocksMovement),
         (With<Slippery>, Without<Transporter>),
     >,
-    mut transporter_query: Query<
-        (&Position, &Direction, &mut BlocksMovement),
-        (With<Transporter>, Without<Slippery>),
-    >,
     mut potential_transportees_query: Query<
-        (Entity, Option<&Direction>, CollisionObjectQuery),
+        CollisionObjectQuery,
         (Without<Slippery>, Without<Transporter>),
     >,
-    mut timer: ResMut<TransporterTimer>,
+    transportee_directions_query: Query<&Direction, (Without<Slippery>, Without<Transporter>)>,
+    mut tile_index: ResMut<TileIndex>,
+    mut timer: ResMut<SlideTimer>,
     dimensions: Res<Dimensions>,
     time: Res<Time>,
 ) {
@@ -318,179 +656,358 @@ pub fn check_for_slippery_and_transporter(
     }
 
     let mut already_moved = BTreeSet::new();
-    for (slippery_position, mut blocks_movement) in &mut slippery_query {
-        let (mut transportees, collision_objects): (Vec<_>, Vec<_>) = potential_transportees_query
-            .iter_mut()
-            .map(|(entity, direction, collision_object)| {
-                (entity, direction, CollisionObject::from(collision_object))
-            })
-            .partition(|(.., object)| object.has_position(*slippery_position));
-        transportees.retain(|(entity, ..)| !already_moved.contains(entity));
+    let mut sliders = Vec::new();
+    for (slippery, slippery_position, _) in &slippery_query {
+        for (transportee, transportee_position) in find_transportees(
+            *slippery_position,
+            &potential_transportees_query,
+            &tile_index,
+            &already_moved,
+        ) {
+            if let Ok(direction) = transportee_directions_query.get(transportee) {
+                sliders.push(Transportee {
+                    carrier: slippery,
+                    entity: transportee,
+                    position: transportee_position,
+                    delta: direction.as_delta(),
+                });
+            }
+            already_moved.insert(transportee);
+        }
+    }
 
-        if let Some((transportee, transportee_direction, CollisionObject { position, .. })) =
-            transportees.first_mut()
-        {
-            let Some(direction) = transportee_direction else {
-                continue;
-            };
+    sort_transportees(&mut sliders);
 
-            if move_object(
-                position,
-                direction.as_delta(),
-                &dimensions,
-                collision_objects.into_iter().map(|(.., object)| object),
-                Weight::None,
-            )
-            .is_err_and(MoveObjectError::is_collision)
-            {
+    for Transportee {
+        carrier: slippery,
+        entity: transportee,
+        position: transportee_position,
+        delta,
+    } in sliders
+    {
+        match move_object(
+            transportee,
+            transportee_position,
+            delta,
+            &dimensions,
+            &mut potential_transportees_query,
+            &mut tile_index,
+            Weight::NONE,
+        ) {
+            Ok(new_position) => {
+                if let Ok(mut transportee) = potential_transportees_query.get_mut(transportee) {
+                    *transportee.position = new_position;
+                }
+                commands
+                    .entity(transportee)
+                    .insert(Glide::new(timer.duration()));
+            }
+            Err(err) if err.is_collision() => {
                 // If an object on a slippery entity cannot be moved, the
                 // slippery entity's [BlocksMovement] component is disabled
                 // until the object is moved away.
-                *blocks_movement = BlocksMovement::Disabled;
+                if let Ok((_, _, mut blocks_movement)) = slippery_query.get_mut(slippery) {
+                    *blocks_movement = BlocksMovement::Disabled;
+                }
             }
-            already_moved.insert(*transportee);
+            Err(_) => {}
         }
     }
+}
 
-    for (transporter_position, direction, mut blocks_movement) in &mut transporter_query {
-        let (mut transportees, collision_objects): (Vec<_>, Vec<_>) = potential_transportees_query
-            .iter_mut()
-            .map(|(entity, direction, collision_object)| {
-                (entity, direction, CollisionObject::from(collision_object))
-            })
-            .partition(|(.., object)| object.has_position(*transporter_position));
-        transportees.retain(|(entity, ..)| !already_moved.contains(entity));
+/// Moves the objects on transporters whose interval has passed one tile in
+/// the transporter's direction.
+///
+/// All objects on those transporters are moved in a single pass, in the order
+/// of [sort_transportees].
+pub fn check_for_transporter(
+    mut transporter_query: Query<
+        (
+            Entity,
+            &Position,
+            &Direction,
+            &mut Transporter,
+            &mut BlocksMovement,
+        ),
+        Without<Slippery>,
+    >,
+    mut potential_transportees_query: Query<
+        CollisionObjectQuery,
+        (Without<Slippery>, Without<Transporter>),
+    >,
+    mut tile_index: ResMut<TileIndex>,
+    mut timer: ResMut<TransporterTimer>,
+    dimensions: Res<Dimensions>,
+    time: Res<Time>,
+) {
+    timer.tick(time.delta());
+    if !timer.just_finished() {
+        return;
+    }
 
-        if let Some((transportee, .., CollisionObject { position, .. })) = transportees.first_mut()
-        {
-            if move_object(
-                position,
-                direction.as_delta(),
-                &dimensions,
-                collision_objects.into_iter().map(|(.., object)| object),
-                Weight::None,
-            )
-            .is_err_and(MoveObjectError::is_collision)
-            {
+    let mut already_moved = BTreeSet::new();
+    let mut transportees = Vec::new();
+    for (entity, transporter_position, direction, mut transporter, _) in &mut transporter_query {
+        if !transporter.tick() {
+            continue;
+        }
+
+        for (transportee, transportee_position) in find_transportees(
+            *transporter_position,
+            &potential_transportees_query,
+            &tile_index,
+            &already_moved,
+        ) {
+            transportees.push(Transportee {
+                carrier: entity,
+                entity: transportee,
+                position: transportee_position,
+                delta: direction.as_delta(),
+            });
+            already_moved.insert(transportee);
+        }
+    }
+
+    sort_transportees(&mut transportees);
+
+    for Transportee {
+        carrier: transporter,
+        entity: transportee,
+        position: transportee_position,
+        delta,
+    } in transportees
+    {
+        match move_object(
+            transportee,
+            transportee_position,
+            delta,
+            &dimensions,
+            &mut potential_transportees_query,
+            &mut tile_index,
+            Weight::NONE,
+        ) {
+            Ok(new_position) => {
+                if let Ok(mut transportee) = potential_transportees_query.get_mut(transportee) {
+                    *transportee.position = new_position;
+                }
+            }
+            Err(err) if err.is_collision() => {
                 // If an object on a transporter cannot be moved, the
-                // transporter's [BlocksMovement] component is disabled until
-                // the object is moved away.
-                *blocks_movement = BlocksMovement::Disabled;
+                // transporter's [BlocksMovement] component is disabled
+                // until the object is moved away.
+                if let Ok((.., mut blocks_movement)) = transporter_query.get_mut(transporter) {
+                    *blocks_movement = BlocksMovement::Disabled;
+                }
             }
-            already_moved.insert(*transportee);
+            Err(_) => {}
         }
     }
 }
 
+/// An object that is about to be moved by a slippery entity or a transporter.
+struct Transportee {
+    /// The slippery entity or transporter the object is on.
+    carrier: Entity,
+    entity: Entity,
+    position: Position,
+    delta: (i16, i16),
+}
+
+/// Orders transportees so they're moved front to back along their direction
+/// of movement. That way a row of objects moving in the same direction moves
+/// as one, instead of being blocked by objects that haven't moved yet.
+///
+/// Ties, such as between objects converging onto the same tile, are broken
+/// by position and then by entity, so the outcome doesn't depend on the order
+/// in which the entities happen to be iterated.
+fn sort_transportees(transportees: &mut [Transportee]) {
+    transportees.sort_by_key(|transportee| {
+        let (dx, dy) = transportee.delta;
+        let progress = transportee.position.x * dx + transportee.position.y * dy;
+        (-progress, transportee.position, transportee.entity)
+    });
+}
+
+/// Returns the objects at the given position that haven't been moved yet.
+fn find_transportees<F: QueryFilter>(
+    position: Position,
+    potential_transportees_query: &Query<CollisionObjectQuery, F>,
+    tile_index: &TileIndex,
+    already_moved: &BTreeSet<Entity>,
+) -> Vec<(Entity, Position)> {
+    tile_index
+        .get(position)
+        .iter()
+        .filter(|entity| !already_moved.contains(*entity))
+        .filter_map(|entity| potential_transportees_query.get(*entity).ok())
+        .map(|transportee| (transportee.entity, *transportee.position))
+        .collect()
+}
+
+#[expect(clippy::type_complexity)]
 pub fn check_for_teleporter(
-    mut objects_query: Query<(Mut<Position>, Option<&Massive>), Without<Teleporter>>,
+    mut objects_query: Query<(Entity, Mut<Position>, Option<&Massive>), Without<Teleporter>>,
     teleporters_query: Query<(&Position, &Teleporter)>,
+    mut tile_index: ResMut<TileIndex>,
 ) {
-    if !objects_query
-        .iter()
-        .filter(|(position, _)| position.is_changed())
-        .any(|(position, _)| {
-            teleporters_query
-                .iter()
-                .any(|(teleporter_position, _)| position.as_ref() == teleporter_position)
-        })
-    {
-        return;
-    }
+    let mut teleported_objects = Vec::new();
+    for (entity, position, _) in &objects_query {
+        if !position.is_changed() {
+            continue;
+        }
 
-    let (mut moved_objects, possible_collisions): (Vec<_>, Vec<_>) = objects_query
-        .iter_mut()
-        .filter(|(position, _)| {
-            teleporters_query
-                .iter()
-                .any(|(teleporter_position, _)| position.as_ref() == teleporter_position)
-        })
-        .partition(|(position, _)| position.is_changed());
-
-    for (ref mut position, _) in &mut moved_objects {
-        for (teleporter_position, teleporter) in &teleporters_query {
-            if position.as_ref() == teleporter_position {
-                if let Some((target_position, _)) =
-                    teleporters_query
-                        .iter()
-                        .find(|(target_position, target_teleporter)| {
-                            *target_position != teleporter_position
-                                && *target_teleporter == teleporter
-                        })
+        for (teleporter_position, teleporter) in
+            teleporters_query.iter_many(tile_index.get(*position))
+        {
+            if let Some((target_position, _)) =
+                teleporters_query
+                    .iter()
+                    .find(|(target_position, target_teleporter)| {
+                        *target_position != teleporter_position && *target_teleporter == teleporter
+                    })
+            {
+                if !objects_query
+                    .iter_many(tile_index.get(*target_position))
+                    .any(|(_, position, massive)| !position.is_changed() && massive.is_some())
                 {
-                    if !possible_collisions.iter().any(|(position, massive)| {
-                        position.as_ref() == target_position && massive.is_some()
-                    }) {
-                        *position.as_mut() = *target_position;
-                        break;
-                    }
+                    teleported_objects.push((entity, *target_position));
+                    break;
                 }
             }
         }
     }
+
+    for (entity, target_position) in teleported_objects {
+        if let Ok((_, mut position, _)) = objects_query.get_mut(entity) {
+            *position = target_position;
+            tile_index.insert(entity, target_position);
+        }
+    }
 }
 
-#[expect(clippy::type_complexity)]
+#[expect(clippy::too_many_arguments)]
 pub fn check_for_triggers(
     mut commands: Commands,
-    mut trigger_query: Query<(
+    trigger_query: Query<(&Position, &Trigger)>,
+    mut openable_query: Query<(
         Entity,
-        &Position,
-        Option<&Openable>,
+        &ObjectType,
+        &Openable,
         Option<&Massive>,
-        Option<&Trigger>,
         &mut Sprite,
     )>,
+    occupant_query: Query<(Option<&Openable>, Has<Trigger>), With<Sprite>>,
     moved_objects_query: Query<Entity, Changed<Position>>,
     mut pressed_triggers: ResMut<PressedTriggers>,
+    assets: Res<GameObjectAssets>,
+    tile_index: Res<TileIndex>,
 ) {
     if moved_objects_query.iter().next().is_none() {
         return;
     }
 
-    let mut triggers = Vec::new();
-    let mut openables = Vec::new();
-    let mut objects = Vec::new();
-    for (entity, position, openable, massive, trigger, sprite) in &mut trigger_query {
-        if trigger.is_some() {
-            triggers.push(position);
-        } else if matches!(openable, Some(Openable::Trigger)) {
-            openables.push((entity, massive, sprite));
-        } else {
-            objects.push(position);
-        }
+    let mut num_pressed_triggers: BTreeMap<u16, usize> = BTreeMap::new();
+    for (trigger_position, Trigger(identifier)) in &trigger_query {
+        let is_pressed = occupant_query
+            .iter_many(tile_index.get(*trigger_position))
+            .any(|(openable, is_trigger)| {
+                !is_trigger && !matches!(openable, Some(Openable::Trigger(_)))
+            });
+        *num_pressed_triggers.entry(*identifier).or_default() += usize::from(is_pressed);
     }
 
-    let num_pressed_triggers = triggers
+    // Every identifier opens or closes its entities independently.
+    let changes: BTreeMap<u16, bool> = num_pressed_triggers
         .iter()
-        .filter(|trigger_position| objects.iter().any(|position| position == *trigger_position))
-        .count();
+        .filter_map(|(identifier, num_pressed)| {
+            let previous = pressed_triggers
+                .num_pressed_triggers
+                .get(identifier)
+                .copied()
+                .unwrap_or_default();
+            match num_pressed.cmp(&previous) {
+                Ordering::Greater => Some((*identifier, true)),
+                Ordering::Less => Some((*identifier, false)),
+                Ordering::Equal => None, // No change.
+            }
+        })
+        .collect();
+    if changes.is_empty() {
+        return;
+    }
 
-    let opened = match num_pressed_triggers.cmp(&pressed_triggers.num_pressed_triggers) {
-        Ordering::Greater => true,
-        Ordering::Less => false,
-        Ordering::Equal => return, // No change.
-    };
+    for (entity, object_type, openable, massive, mut sprite) in &mut openable_query {
+        let Openable::Trigger(identifier) = openable else {
+            continue;
+        };
+        let Some(opened) = changes.get(identifier).copied() else {
+            continue;
+        };
 
-    for (entity, massive, mut image_node) in openables {
+        let atlas_sprite = assets.for_object_type(*object_type);
         if opened && massive.is_some() {
             commands.entity(entity).remove::<Massive>();
-
-            if let Some(atlas) = image_node.texture_atlas.as_mut() {
-                atlas.index = 1;
-            }
+            atlas_sprite.set_frame(&mut sprite, 1);
         } else if !opened && massive.is_none() {
             commands.entity(entity).insert(Massive);
-
-            if let Some(atlas) = image_node.texture_atlas.as_mut() {
-                atlas.index = 0;
-            }
+            atlas_sprite.set_frame(&mut sprite, 0);
         }
     }
 
     pressed_triggers.num_pressed_triggers = num_pressed_triggers;
 }
 
+/// Counts down the timer gates, which close once their time has run out.
+#[expect(clippy::too_many_arguments)]
+pub fn count_down_timer_gates(
+    mut commands: Commands,
+    mut gate_query: Query<(Entity, &mut TimerGate, &mut Sprite, Has<Massive>, &Children)>,
+    mut label_query: Query<&mut Text2d, With<TimerGateLabel>>,
+    assets: Res<GameObjectAssets>,
+    editor: Res<EditorState>,
+    menu_state: Res<MenuState>,
+    photo_mode: Res<PhotoMode>,
+    pressed_triggers: Res<PressedTriggers>,
+    time: Res<Time>,
+) {
+    // The countdown stops whenever the game is paused.
+    let is_paused = editor.is_open || menu_state.is_open() || photo_mode.is_active();
+
+    for (entity, mut timer_gate, mut sprite, is_closed, children) in &mut gate_query {
+        let label = if is_closed {
+            String::new()
+        } else {
+            if !timer_gate.is_started {
+                timer_gate.is_started = timer_gate.trigger.is_none_or(|identifier| {
+                    pressed_triggers
+                        .num_pressed_triggers
+                        .get(&identifier)
+                        .is_some_and(|num_pressed| *num_pressed > 0)
+                });
+            }
+
+            if timer_gate.is_started && !is_paused {
+                timer_gate.timer.tick(time.delta());
+                if timer_gate.timer.finished() {
+                    commands.entity(entity).insert(Massive);
+                    assets
+                        .for_object_type(ObjectType::TimerGate)
+                        .set_frame(&mut sprite, 0);
+                }
+            }
+
+            timer_gate.seconds_left().to_string()
+        };
+
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut text) = labels.fetch_next() {
+            if text.0 != label {
+                text.0.clone_from(&label);
+            }
+        }
+    }
+}
+
 pub fn despawn_volatile_objects(
     mut commands: Commands,
     query: Query<Entity, With<Volatile>>,
@@ -506,8 +1023,15 @@ pub fn despawn_volatile_objects(
 }
 
 pub fn move_objects(
-    mut movable_query: Query<(&mut Direction, &Movable, &mut Position, Option<&Weight>)>,
+    mut movable_query: Query<(
+        Entity,
+        &mut Direction,
+        &Movable,
+        &mut Position,
+        Option<&Weight>,
+    )>,
     mut collision_objects_query: Query<CollisionObjectQuery, Without<Movable>>,
+    mut tile_index: ResMut<TileIndex>,
     mut timer: ResMut<MovementTimer>,
     dimensions: Res<Dimensions>,
     time: Res<Time>,
@@ -517,44 +1041,50 @@ pub fn move_objects(
         return;
     }
 
-    for (mut direction, movable, mut position, weight) in &mut movable_query {
+    for (entity, mut direction, movable, mut position, weight) in &mut movable_query {
+        let weight = weight.copied().unwrap_or_default();
         match movable {
-            Movable::Bounce => {
-                if move_object(
-                    &mut position,
-                    direction.as_delta(),
-                    &dimensions,
-                    collision_objects_query.iter_mut().map(Into::into),
-                    weight.copied().unwrap_or_default(),
-                )
-                .is_err_and(MoveObjectError::is_collision)
-                {
-                    *direction = direction.inverse();
-                }
-            }
+            Movable::Bounce => match move_object(
+                entity,
+                *position,
+                direction.as_delta(),
+                &dimensions,
+                &mut collision_objects_query,
+                &mut tile_index,
+                weight,
+            ) {
+                Ok(new_position) => *position = new_position,
+                Err(err) if err.is_collision() => *direction = direction.inverse(),
+                Err(_) => {}
+            },
             Movable::FollowRightHand => {
                 let move_result = move_object(
-                    &mut position,
+                    entity,
+                    *position,
                     direction.right_hand().as_delta(),
                     &dimensions,
-                    collision_objects_query.iter_mut().map(Into::into),
-                    weight.copied().unwrap_or_default(),
+                    &mut collision_objects_query,
+                    &mut tile_index,
+                    weight,
                 );
                 match move_result {
-                    Ok(()) => {
+                    Ok(new_position) => {
+                        *position = new_position;
                         *direction = direction.right_hand();
                     }
                     Err(err) if err.is_collision() => {
-                        if move_object(
-                            &mut position,
+                        match move_object(
+                            entity,
+                            *position,
                             direction.as_delta(),
                             &dimensions,
-                            collision_objects_query.iter_mut().map(Into::into),
-                            weight.copied().unwrap_or_default(),
-                        )
-                        .is_err_and(MoveObjectError::is_collision)
-                        {
-                            *direction = direction.left_hand();
+                            &mut collision_objects_query,
+                            &mut tile_index,
+                            weight,
+                        ) {
+                            Ok(new_position) => *position = new_position,
+                            Err(err) if err.is_collision() => *direction = direction.left_hand(),
+                            Err(_) => {}
                         }
                     }
                     Err(_) => {}
@@ -580,84 +1110,63 @@ impl MoveObjectError {
     }
 }
 
-pub fn move_object<'a>(
-    object_position: &mut Mut<Position>,
+/// Moves the object by the given delta, pushing any objects that are in the
+/// way.
+///
+/// Only the objects on the affected tiles are considered, which are looked up
+/// through the [TileIndex]. The index is updated for the moved objects, but
+/// it is up to the caller to assign the returned position to the object
+/// itself.
+pub fn move_object<F: QueryFilter>(
+    object: Entity,
+    object_position: Position,
     (dx, dy): (i16, i16),
     dimensions: &Dimensions,
-    collision_objects: impl Iterator<Item = CollisionObject<'a>>,
+    collision_objects_query: &mut Query<CollisionObjectQuery, F>,
+    tile_index: &mut TileIndex,
     max_weight: Weight,
-) -> Result<(), MoveObjectError> {
-    let new_x = object_position.x + dx;
-    let new_y = object_position.y + dy;
-    if !dimensions.contains((new_x, new_y).into()) {
+) -> Result<Position, MoveObjectError> {
+    let new_position = Position {
+        x: object_position.x + dx,
+        y: object_position.y + dy,
+    };
+    if !dimensions.contains(new_position) {
         return Err(MoveObjectError::EdgeCollision);
     }
 
-    let mut collision_objects: Vec<_> = collision_objects
-        .filter(|CollisionObject { position, .. }| {
-            position.as_ref() == object_position.as_ref()
-                || if dx > 0 {
-                    position.x >= new_x && position.y == new_y
-                } else if dx < 0 {
-                    position.x <= new_x && position.y == new_y
-                } else if dy > 0 {
-                    position.x == new_x && position.y >= new_y
-                } else if dy < 0 {
-                    position.x == new_x && position.y <= new_y
-                } else {
-                    false
-                }
-        })
-        .collect();
-
-    collision_objects.sort_unstable_by_key(|CollisionObject { position, .. }| {
-        (position.x - new_x).abs() + (position.y - new_y).abs()
-    });
-
-    let can_mix_with = |x: i16, y: i16, other: ObjectType| -> bool {
-        collision_objects
-            .iter()
-            .any(|object| object.has_position((x, y).into()) && object.can_mix_with(other))
-    };
-
-    let can_open_with_key = |x: i16, y: i16| -> bool {
-        collision_objects
-            .iter()
-            .any(|object| object.has_position((x, y).into()) && object.can_open_with_key())
+    let push_position = Position {
+        x: new_position.x + dx,
+        y: new_position.y + dy,
     };
 
-    let can_paint = |x: i16, y: i16| -> bool {
-        collision_objects
-            .iter()
-            .any(|object| object.has_position((x, y).into()) && object.is_paintable())
-    };
-
-    let can_push_to = |x: i16, y: i16| -> bool {
-        dimensions.contains((x, y).into())
-            && collision_objects
+    let mut pushed_objects = Vec::new();
+    let mut left_objects = Vec::new();
+    {
+        let query = &*collision_objects_query;
+        let index = &*tile_index;
+        let objects_at = |position: Position| {
+            index
+                .get(position)
                 .iter()
-                .all(|object| !object.has_position((x, y).into()) || object.can_push_on())
-    };
+                .filter(move |entity| **entity != object)
+                .filter_map(move |entity| query.get(*entity).ok())
+        };
 
-    let mut pushed_object_indices = Vec::new();
-    for (index, collision_object) in collision_objects.iter().enumerate() {
-        if collision_object.has_position(**object_position) && collision_object.blocks_movement() {
-            return Err(MoveObjectError::MovementBlocked);
-        }
-
-        if collision_object.has_position((new_x, new_y).into()) {
-            let can_push_to_or_mix_or_open_or_paint = |x: i16, y: i16| -> bool {
-                can_push_to(x, y)
-                    || can_mix_with(x, y, collision_object.object_type())
-                    || collision_object.is_key() && can_open_with_key(x, y)
-                    || collision_object.is_paint() && can_paint(x, y)
-            };
+        let can_push_to = dimensions.contains(push_position)
+            && objects_at(push_position).all(|other| other.can_push_on());
 
+        for collision_object in objects_at(new_position) {
             if collision_object.weight() <= max_weight
                 && collision_object.is_pushable()
-                && can_push_to_or_mix_or_open_or_paint(new_x + dx, new_y + dy)
+                && (can_push_to
+                    || objects_at(push_position)
+                        .any(|other| other.can_mix_with(collision_object.object_type()))
+                    || collision_object.is_key()
+                        && objects_at(push_position).any(|other| other.can_open_with_key())
+                    || collision_object.is_paint()
+                        && objects_at(push_position).any(|other| other.is_paintable()))
             {
-                pushed_object_indices.push(index);
+                pushed_objects.push(collision_object.entity);
                 continue;
             }
 
@@ -665,23 +1174,219 @@ pub fn move_object<'a>(
                 return Err(MoveObjectError::ObjectCollision);
             }
         }
+
+        for collision_object in objects_at(object_position) {
+            if collision_object.blocks_movement() {
+                return Err(MoveObjectError::MovementBlocked);
+            }
+
+            left_objects.push(collision_object.entity);
+        }
     }
 
-    for index in pushed_object_indices {
-        let position = &mut collision_objects[index].position;
-        position.x += dx;
-        position.y += dy;
+    for entity in pushed_objects {
+        if let Ok(mut pushed_object) = collision_objects_query.get_mut(entity) {
+            pushed_object.position.x += dx;
+            pushed_object.position.y += dy;
+            tile_index.insert(entity, *pushed_object.position);
+        }
     }
 
-    for collission_object in &mut collision_objects {
-        if collission_object.has_position(**object_position) {
-            if let Some(blocks_movement) = collission_object.blocks_movement.as_mut() {
+    for entity in left_objects {
+        if let Ok(mut left_object) = collision_objects_query.get_mut(entity) {
+            if let Some(blocks_movement) = left_object.blocks_movement.as_mut() {
                 **blocks_movement = BlocksMovement::Enabled;
             }
         }
     }
 
-    object_position.x = new_x;
-    object_position.y = new_y;
-    Ok(())
+    tile_index.insert(object, new_position);
+    Ok(new_position)
+}
+
+/// Pushes a row of up to `strength` pushable objects in front of the object,
+/// so that [move_object] can push the first one of them after.
+///
+/// All objects in the row, except for the first one, are moved ahead by the
+/// given delta. This only happens if the row is longer than a single object,
+/// and the tile beyond it is free.
+pub fn push_chain<F: QueryFilter>(
+    object_position: Position,
+    (dx, dy): (i16, i16),
+    dimensions: &Dimensions,
+    collision_objects_query: &mut Query<CollisionObjectQuery, F>,
+    tile_index: &mut TileIndex,
+    max_weight: Weight,
+    strength: u16,
+) {
+    let tile_at = |distance: i16| Position {
+        x: object_position.x + distance * dx,
+        y: object_position.y + distance * dy,
+    };
+
+    let mut chained_objects = Vec::new();
+    {
+        let query = &*collision_objects_query;
+        let index = &*tile_index;
+        let objects_at = |position: Position| {
+            index
+                .get(position)
+                .iter()
+                .filter_map(move |entity| query.get(*entity).ok())
+        };
+        let can_push =
+            |other: &CollisionObject| other.is_pushable() && other.weight() <= max_weight;
+
+        if objects_at(object_position).any(|other| other.blocks_movement()) {
+            return;
+        }
+
+        let mut num_chained: i16 = 0;
+        while num_chained < strength as i16 {
+            let position = tile_at(num_chained + 1);
+            if !objects_at(position).any(|other| can_push(&other))
+                || !objects_at(position).all(|other| can_push(&other) || other.can_push_on())
+            {
+                break;
+            }
+
+            num_chained += 1;
+        }
+
+        let beyond_chain = tile_at(num_chained + 1);
+        if num_chained < 2
+            || !dimensions.contains(beyond_chain)
+            || !objects_at(beyond_chain).all(|other| other.can_push_on())
+        {
+            return;
+        }
+
+        // The first object is left for `move_object()` to push.
+        for distance in 2..=num_chained {
+            chained_objects.extend(
+                objects_at(tile_at(distance))
+                    .filter(|other| can_push(other))
+                    .map(|other| other.entity),
+            );
+        }
+    }
+
+    for entity in chained_objects {
+        if let Ok(mut chained_object) = collision_objects_query.get_mut(entity) {
+            chained_object.position.x += dx;
+            chained_object.position.y += dy;
+            tile_index.insert(entity, *chained_object.position);
+        }
+    }
+}
+
+/// Moves the object by the given delta like [move_object], while pulling
+/// along the objects on the tile behind it.
+///
+/// Only pushable objects up to the given weight are pulled. Nothing is pulled
+/// if the objects cannot leave their tile, or if they couldn't be pushed onto
+/// the tile the object is leaving. The object still moves in that case.
+pub fn pull_object<F: QueryFilter>(
+    object: Entity,
+    object_position: Position,
+    (dx, dy): (i16, i16),
+    dimensions: &Dimensions,
+    collision_objects_query: &mut Query<CollisionObjectQuery, F>,
+    tile_index: &mut TileIndex,
+    max_weight: Weight,
+) -> Result<Position, MoveObjectError> {
+    let pull_position = Position {
+        x: object_position.x - dx,
+        y: object_position.y - dy,
+    };
+
+    let pulled_objects: Vec<Entity> = {
+        let query = &*collision_objects_query;
+        let index = &*tile_index;
+        let objects_at = |position: Position| {
+            index
+                .get(position)
+                .iter()
+                .filter(move |entity| **entity != object)
+                .filter_map(move |entity| query.get(*entity).ok())
+        };
+
+        let can_pull_from = objects_at(pull_position).all(|other| !other.blocks_movement());
+        let can_pull_to = objects_at(object_position).all(|other| other.can_push_on());
+        if can_pull_from && can_pull_to {
+            objects_at(pull_position)
+                .filter(|other| other.is_pushable() && other.weight() <= max_weight)
+                .map(|other| other.entity)
+                .collect()
+        } else {
+            Vec::new()
+        }
+    };
+
+    let new_position = move_object(
+        object,
+        object_position,
+        (dx, dy),
+        dimensions,
+        collision_objects_query,
+        tile_index,
+        max_weight,
+    )?;
+
+    for entity in pulled_objects {
+        if let Ok(mut pulled_object) = collision_objects_query.get_mut(entity) {
+            *pulled_object.position = object_position;
+            tile_index.insert(entity, object_position);
+        }
+    }
+
+    Ok(new_position)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game_object::{ObjectType, Position},
+        levels::Level,
+        simulation::Simulation,
+    };
+
+    /// Two blocks on belts that both lead onto the empty tile between them.
+    const CONVERGING_BELTS: &str = "[General]\nWidth=3\nHeight=1\n\n\
+        [BlueBlock]\nPosition=1,1;3,1\n\n\
+        [Transporter]\nDirection=Right\nPosition=1,1\nDirection=Left\nPosition=3,1\n";
+
+    fn run_converging_belts() -> Vec<(Position, Vec<ObjectType>)> {
+        let level = Level::load(CONVERGING_BELTS).expect("test level should load");
+        let mut simulation = Simulation::new(level);
+        for _ in 0..3 {
+            simulation.wait();
+        }
+        simulation.grid().into_iter().collect()
+    }
+
+    #[test]
+    fn converging_belts_move_one_object_and_hold_the_other() {
+        let grid = run_converging_belts();
+
+        assert_eq!(
+            grid,
+            vec![
+                (Position { x: 1, y: 1 }, vec![ObjectType::Transporter]),
+                (Position { x: 2, y: 1 }, vec![ObjectType::BlueBlock]),
+                (
+                    Position { x: 3, y: 1 },
+                    vec![ObjectType::BlueBlock, ObjectType::Transporter]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn converging_belts_are_deterministic() {
+        let first = run_converging_belts();
+        for _ in 0..5 {
+            assert_eq!(run_converging_belts(), first);
+        }
+    }
 }
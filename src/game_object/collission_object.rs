@@ -1,72 +1,31 @@
-use bevy::prelude::*;
+use bevy::{ecs::query::QueryData, prelude::*};
 
 use super::{components::*, ObjectType};
 
-pub type CollisionObjectQuery<'a> = (
-    Option<Mut<'a, BlocksMovement>>,
-    Option<&'a BlocksPushes>,
-    Option<&'a Key>,
-    Option<&'a Massive>,
-    &'a ObjectType,
-    Option<&'a Openable>,
-    Option<&'a Paint>,
-    Option<&'a Paintable>,
-    Mut<'a, Position>,
-    Option<&'a Pushable>,
-    Option<&'a Weight>,
-);
-
-pub struct CollisionObject<'a> {
-    pub(super) blocks_movement: Option<Mut<'a, BlocksMovement>>,
-    blocks_pushes: Option<&'a BlocksPushes>,
-    key: Option<&'a Key>,
-    massive: Option<&'a Massive>,
-    object_type: &'a ObjectType,
-    openable: Option<&'a Openable>,
-    paint: Option<&'a Paint>,
-    paintable: Option<&'a Paintable>,
-    pub(super) position: Mut<'a, Position>,
-    pushable: Option<&'a Pushable>,
-    weight: Option<&'a Weight>,
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub struct CollisionObjectQuery {
+    pub entity: Entity,
+    pub blocks_movement: Option<&'static mut BlocksMovement>,
+    blocks_pushes: Option<&'static BlocksPushes>,
+    key: Option<&'static Key>,
+    massive: Option<&'static Massive>,
+    object_type: &'static ObjectType,
+    openable: Option<&'static Openable>,
+    paint: Option<&'static Paint>,
+    paintable: Option<&'static Paintable>,
+    pub position: &'static mut Position,
+    pushable: Option<&'static Pushable>,
+    weight: Option<&'static Weight>,
 }
 
-impl<'a> From<CollisionObjectQuery<'a>> for CollisionObject<'a> {
-    fn from(query: CollisionObjectQuery<'a>) -> Self {
-        let (
-            blocks_movement,
-            blocks_pushes,
-            key,
-            massive,
-            object_type,
-            openable,
-            paint,
-            paintable,
-            position,
-            pushable,
-            weight,
-        ) = query;
-
-        Self {
-            blocks_movement,
-            blocks_pushes,
-            key,
-            massive,
-            object_type,
-            openable,
-            paint,
-            paintable,
-            position,
-            pushable,
-            weight,
-        }
-    }
-}
+/// Read-only view of an object that may be collided with.
+pub type CollisionObject<'a> = CollisionObjectQueryReadOnlyItem<'a>;
 
 impl CollisionObject<'_> {
     pub fn blocks_movement(&self) -> bool {
         self.blocks_movement
-            .as_ref()
-            .is_some_and(|blocks| **blocks == BlocksMovement::Enabled)
+            .is_some_and(|blocks| *blocks == BlocksMovement::Enabled)
     }
 
     pub fn can_mix_with(&self, other: ObjectType) -> bool {
@@ -81,10 +40,6 @@ impl CollisionObject<'_> {
         self.massive.is_none() && self.pushable.is_none() && self.blocks_pushes.is_none()
     }
 
-    pub fn has_position(&self, position: Position) -> bool {
-        self.position.as_ref() == &position
-    }
-
     pub fn is_key(&self) -> bool {
         self.key.is_some()
     }
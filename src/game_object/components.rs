@@ -1,15 +1,15 @@
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, time::Duration};
 
 use bevy::prelude::*;
 
-use crate::errors::UnknownDirection;
+use crate::errors::{UnknownDecoration, UnknownDirection};
 
 use super::ObjectType;
 
 /// Game object position.
 ///
 /// The top-left square of a level is position (1, 1).
-#[derive(Clone, Component, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Component, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Position {
     /// 1-based X coordinate of the object's position.
     pub x: i16,
@@ -24,12 +24,70 @@ impl Display for Position {
     }
 }
 
+impl Position {
+    /// Returns a pseudo-random number that is derived only from the position,
+    /// so it stays the same every time a level is loaded.
+    pub fn seed(self) -> u32 {
+        let mut hash = (self.x as u16 as u32) << 16 | self.y as u16 as u32;
+        hash = (hash ^ (hash >> 16)).wrapping_mul(0x7feb_352d);
+        hash = (hash ^ (hash >> 15)).wrapping_mul(0x846c_a68b);
+        hash ^ (hash >> 16)
+    }
+}
+
 impl From<(i16, i16)> for Position {
     fn from((x, y): (i16, i16)) -> Self {
         Self { x, y }
     }
 }
 
+/// A purely visual object, which doesn't interact with anything.
+#[derive(Clone, Component, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Decoration {
+    Flower,
+    Rock,
+    Rug,
+}
+
+impl Display for Decoration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Flower => "Flower",
+            Self::Rock => "Rock",
+            Self::Rug => "Rug",
+        })
+    }
+}
+
+impl Decoration {
+    /// Returns the frame of the decoration sprite that shows this decoration.
+    pub fn frame(self) -> usize {
+        match self {
+            Self::Flower => 0,
+            Self::Rock => 1,
+            Self::Rug => 2,
+        }
+    }
+}
+
+impl FromStr for Decoration {
+    type Err = UnknownDecoration;
+
+    fn from_str(decoration: &str) -> Result<Self, Self::Err> {
+        match decoration {
+            "Flower" => Ok(Self::Flower),
+            "Rock" => Ok(Self::Rock),
+            "Rug" => Ok(Self::Rug),
+            _ => Err(UnknownDecoration),
+        }
+    }
+}
+
+/// The direction an object is facing or moving in.
+///
+/// The diagonal directions come after the cardinal ones, so the latter keep
+/// matching the frames of directional sprites. Diagonals are only used in
+/// levels that enable [DiagonalMovement](crate::levels::DiagonalMovement).
 #[derive(Clone, Component, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
 pub enum Direction {
     #[default]
@@ -37,6 +95,10 @@ pub enum Direction {
     Right,
     Down,
     Left,
+    UpRight,
+    DownRight,
+    DownLeft,
+    UpLeft,
 }
 
 impl Display for Direction {
@@ -46,6 +108,10 @@ impl Display for Direction {
             Self::Right => "Right",
             Self::Down => "Down",
             Self::Left => "Left",
+            Self::UpRight => "UpRight",
+            Self::DownRight => "DownRight",
+            Self::DownLeft => "DownLeft",
+            Self::UpLeft => "UpLeft",
         })
     }
 }
@@ -57,6 +123,10 @@ impl Direction {
             Self::Right => Self::Left,
             Self::Down => Self::Up,
             Self::Left => Self::Right,
+            Self::UpRight => Self::DownLeft,
+            Self::DownRight => Self::UpLeft,
+            Self::DownLeft => Self::UpRight,
+            Self::UpLeft => Self::DownRight,
         }
     }
 
@@ -66,6 +136,10 @@ impl Direction {
             Self::Right => Self::Up,
             Self::Down => Self::Right,
             Self::Left => Self::Down,
+            Self::UpRight => Self::UpLeft,
+            Self::DownRight => Self::UpRight,
+            Self::DownLeft => Self::DownRight,
+            Self::UpLeft => Self::DownLeft,
         }
     }
 
@@ -75,6 +149,28 @@ impl Direction {
             Self::Right => Self::Down,
             Self::Down => Self::Left,
             Self::Left => Self::Up,
+            Self::UpRight => Self::DownRight,
+            Self::DownRight => Self::DownLeft,
+            Self::DownLeft => Self::UpLeft,
+            Self::UpLeft => Self::UpRight,
+        }
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        matches!(
+            self,
+            Self::UpRight | Self::DownRight | Self::DownLeft | Self::UpLeft
+        )
+    }
+
+    /// Returns the cardinal direction closest to this one, which picks the
+    /// frame of directional sprites. Diagonals face sideways, as the sprites
+    /// are most recognizable that way.
+    pub fn nearest_cardinal(self) -> Self {
+        match self {
+            Self::UpRight | Self::DownRight => Self::Right,
+            Self::DownLeft | Self::UpLeft => Self::Left,
+            cardinal => cardinal,
         }
     }
 
@@ -85,6 +181,10 @@ impl Direction {
             Self::Right => (1, 0),
             Self::Down => (0, 1),
             Self::Left => (-1, 0),
+            Self::UpRight => (1, -1),
+            Self::DownRight => (1, 1),
+            Self::DownLeft => (-1, 1),
+            Self::UpLeft => (-1, -1),
         }
     }
 }
@@ -98,6 +198,10 @@ impl FromStr for Direction {
             "Right" => Ok(Self::Right),
             "Down" => Ok(Self::Down),
             "Left" => Ok(Self::Left),
+            "UpRight" => Ok(Self::UpRight),
+            "DownRight" => Ok(Self::DownRight),
+            "DownLeft" => Ok(Self::DownLeft),
+            "UpLeft" => Ok(Self::UpLeft),
             _ => Err(UnknownDirection),
         }
     }
@@ -112,6 +216,10 @@ impl TryFrom<(i16, i16)> for Direction {
             (1, 0) => Ok(Self::Right),
             (0, 1) => Ok(Self::Down),
             (-1, 0) => Ok(Self::Left),
+            (1, -1) => Ok(Self::UpRight),
+            (1, 1) => Ok(Self::DownRight),
+            (-1, 1) => Ok(Self::DownLeft),
+            (-1, -1) => Ok(Self::UpLeft),
             _ => Err(()),
         }
     }
@@ -122,6 +230,15 @@ pub struct Animatable {
     pub num_frames: usize,
 }
 
+/// An entity whose sprite depends on which of its neighbours are of the same
+/// [ObjectType], such as the shorelines of water.
+///
+/// The frame to use is picked from the neighbours above, to the right, below
+/// and to the left, which add 1, 2, 4 and 8 respectively if they're of the
+/// same type.
+#[derive(Component, Debug)]
+pub struct Autotile;
+
 /// An entity that prevents the [Player] as well as other [Movable] entities
 /// from moving when on the same [Position].
 ///
@@ -139,17 +256,46 @@ pub enum BlocksMovement {
 #[derive(Component, Debug)]
 pub struct BlocksPushes;
 
+/// Label showing the best number of moves for the level behind an entrance.
+#[derive(Component, Debug)]
+pub struct BestMovesLabel;
+
+/// Bomb lying in the level, which the player picks up by walking onto it.
+///
+/// Carried bombs can be placed on the player's tile, where they act as a
+/// mine.
+#[derive(Component, Debug)]
+pub struct Bomb;
+
+/// A bridge collapses into water when the total [Weight] of the entities on it
+/// exceeds its capacity.
+#[derive(Component, Debug)]
+pub struct Bridge(pub u16);
+
+/// A checkpoint saves the state of the level when the player steps on it, so
+/// that dying respawns the player there instead of restarting the level.
+#[derive(Component, Debug)]
+pub struct Checkpoint;
+
 /// A deadly entity will kill the player if it comes into contact with it.
 #[derive(Component, Debug)]
 pub struct Deadly;
 
+/// Marks a decoration that only appears once the given level is finished, so
+/// the hub changes as the player makes progress.
+#[derive(Component, Debug)]
+pub struct RevealedByLevel(pub u16);
+
 /// An entrance to another level.
 #[derive(Component, Debug)]
 pub struct Entrance(pub u16);
 
 /// An exit completes the level when stepped on.
+///
+/// Exits lead back to the hub, unless they are secret exits with a level of
+/// their own.
 #[derive(Component, Debug)]
-pub struct Exit;
+pub struct Exit(pub Option<u16>);
 
 /// Explodes on contact.
 ///
@@ -166,6 +312,13 @@ pub struct Floatable;
 #[derive(Component, Debug)]
 pub struct Key;
 
+/// Entity was spawned as part of the level, rather than while playing it.
+///
+/// Holds the index of the object in the level's list of spawned objects, so it
+/// can be reset to its initial state when the level is restarted.
+#[derive(Component, Debug)]
+pub struct LevelObject(pub usize);
+
 /// Liquid entities will cause other entities to sink when it comes into
 /// contact with them. An exception are [Floatable] entities.
 ///
@@ -205,8 +358,12 @@ pub enum Openable {
     /// Entity opens when the given level is finished.
     LevelFinished(u16),
 
-    /// Entity opens when a [Trigger] is pressed.
-    Trigger,
+    /// Entity opens once the given number of levels is finished, not counting
+    /// the hub.
+    FinishedCount(u16),
+
+    /// Entity opens when a [Trigger] with the given identifier is pressed.
+    Trigger(u16),
 }
 
 /// Entity is controlled by the player.
@@ -231,12 +388,51 @@ pub struct Paintable;
 #[derive(Component, Debug)]
 pub struct Pushable;
 
+/// A friendly creature that talks to the player when bumped into.
+///
+/// The dialogue is split into pages by empty lines.
+#[derive(Component, Debug)]
+pub struct Npc(pub String);
+
+/// Overlay of a [Water] entity that draws the edges towards neighbouring
+/// tiles without water.
+///
+/// [Water]: super::Water
+#[derive(Component, Debug)]
+pub struct Shoreline;
+
+/// A sign with a message that is shown when the player comes near.
+#[derive(Component, Debug)]
+pub struct Sign(pub String);
+
 /// When an entity with a [Direction] gets onto a slippery entity, it keeps
 /// sliding in that direction until it's no longer on a slippery entity or
 /// cannot move further.
 #[derive(Component, Debug)]
 pub struct Slippery;
 
+/// Entity that is sliding, whose sprite glides from one tile to the next
+/// instead of jumping there.
+///
+/// Every step of the slide restarts the glide, and the component is removed
+/// once the entity has come to rest.
+#[derive(Component, Debug)]
+pub struct Glide {
+    /// Translation at which the current step started, which is taken once
+    /// the step begins.
+    pub from: Option<Vec2>,
+    pub timer: Timer,
+}
+
+impl Glide {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            from: None,
+            timer: Timer::new(duration, TimerMode::Once),
+        }
+    }
+}
+
 /// After pushing, entity transforms into another of the given type.
 #[derive(Component, Debug)]
 pub struct TransformOnPush(pub ObjectType);
@@ -248,17 +444,96 @@ pub struct TransformOnPush(pub ObjectType);
 #[derive(Component, Debug, Eq, PartialEq)]
 pub struct Teleporter(pub u16);
 
+/// A gate that stays open until its countdown runs out, after which it closes
+/// for good.
+///
+/// The countdown starts when the level begins, unless the gate is linked to
+/// the [Trigger]s with a given identifier, in which case it starts once one of
+/// them is pressed.
+#[derive(Component, Debug)]
+pub struct TimerGate {
+    pub timer: Timer,
+    pub trigger: Option<u16>,
+    pub is_started: bool,
+}
+
+impl TimerGate {
+    pub fn new(seconds: u16, trigger: Option<u16>) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds as f32, TimerMode::Once),
+            trigger,
+            is_started: false,
+        }
+    }
+
+    /// Returns the number of whole seconds left, rounded up.
+    pub fn seconds_left(&self) -> u16 {
+        self.timer.remaining_secs().ceil() as u16
+    }
+}
+
+/// Label showing the seconds left on the countdown of a [TimerGate].
+#[derive(Component, Debug)]
+pub struct TimerGateLabel;
+
 /// Entity pushes all other entities that are placed on it towards a given
 /// [Direction].
 ///
 /// This is not limited to [Pushable] entities, although the behavior for
 /// pushing uses the same constraints as for pushing [Pushable] entities.
+///
+/// Every transporter keeps its own count of the ticks of the
+/// [TransporterTimer](crate::timers::TransporterTimer), so that slower
+/// transporters only push every few ticks.
 #[derive(Component, Debug)]
-pub struct Transporter;
+pub struct Transporter {
+    /// Number of ticks between pushes.
+    pub interval: u16,
+    ticks_left: u16,
+}
+
+impl Transporter {
+    pub fn new(interval: u16) -> Self {
+        Self {
+            interval: interval.max(1),
+            ticks_left: 0,
+        }
+    }
+
+    /// Advances the transporter by a tick, and returns whether it pushes on
+    /// this tick.
+    pub fn tick(&mut self) -> bool {
+        if self.ticks_left == 0 {
+            self.ticks_left = self.interval - 1;
+            true
+        } else {
+            self.ticks_left -= 1;
+            false
+        }
+    }
+
+    /// Starts the transporter over, so it pushes on the next tick.
+    pub fn reset(&mut self) {
+        self.ticks_left = 0;
+    }
+}
 
 /// Entity acts as trigger for opening [Openable::Trigger] entities.
+///
+/// Triggers only open the entities with the same identifier, so that
+/// independent puzzles in the same level don't interfere with each other.
+#[derive(Component, Debug)]
+pub struct Trigger(pub u16);
+
+/// Entity whose look varies slightly depending on the position it's spawned
+/// at, so that large areas of the same object don't look flat.
+///
+/// The position picks one of the sprite's variants, as well as a subtle tint.
+/// Animated entities should have a single variant, so only the tint is used.
 #[derive(Component, Debug)]
-pub struct Trigger;
+pub struct Variation {
+    pub num_variants: usize,
+}
 
 /// Automatically disappears after spawning.
 #[derive(Component, Debug)]
@@ -267,11 +542,12 @@ pub struct Volatile;
 /// Weight of an entity.
 ///
 /// Pushable entities can only be pushed by other entities of equal or more
-/// weight.
+/// weight. The weights of all entities on a [Bridge] add up.
 #[derive(Clone, Component, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
-pub enum Weight {
-    #[default]
-    None,
-    Light,
-    Heavy,
+pub struct Weight(pub u16);
+
+impl Weight {
+    pub const NONE: Self = Self(0);
+    pub const LIGHT: Self = Self(1);
+    pub const HEAVY: Self = Self(2);
 }
@@ -5,11 +5,15 @@ use crate::levels::InitialPositionAndMetadata;
 use super::{
     assets::GameObjectAssets,
     components::{Exit, Liquid, Massive, Player, Position, Pushable},
-    Animatable, BlocksMovement, BlocksPushes, Deadly, Direction, Entrance, Explosive, Floatable,
-    Key, Movable, ObjectType, Openable, Paint, Paintable, Slippery, Teleporter, TransformOnPush,
-    Transporter, Trigger, Volatile, Weight,
+    Animatable, Autotile, BlocksMovement, BlocksPushes, Bomb, Bridge, Checkpoint, Deadly,
+    Decoration, Direction, Entrance, Explosive, Floatable, Key, Movable, Npc, ObjectType, Openable,
+    Paint, Paintable, Shoreline, Sign, Slippery, Teleporter, TimerGate, TransformOnPush,
+    Transporter, Trigger, Variation, Volatile, Weight,
 };
 
+/// Countdown of timer gates that don't specify their number of seconds.
+const DEFAULT_TIMER_GATE_SECONDS: u16 = 30;
+
 pub struct BlueBlock;
 
 impl BlueBlock {
@@ -20,9 +24,10 @@ impl BlueBlock {
             Paintable,
             position,
             Pushable,
-            Sprite::from_image(assets.blue_block.clone()),
+            assets.sprite(assets.blue_block, 0),
+            Variation { num_variants: 3 },
             Transform::from_translation(Vec3::new(0., 0., 3.)),
-            Weight::Heavy,
+            Weight::HEAVY,
         )
     }
 }
@@ -36,9 +41,21 @@ impl BluePaint {
             Paint(ObjectType::BlueBlock),
             position,
             Pushable,
-            Sprite::from_image(assets.blue_paint.clone()),
+            assets.sprite(assets.blue_paint, 0),
             Transform::from_translation(Vec3::new(0., 0., 3.)),
-            Weight::Light,
+            Weight::LIGHT,
+        )
+    }
+}
+
+impl Bomb {
+    pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
+        (
+            ObjectType::Bomb,
+            Bomb,
+            position,
+            assets.sprite(assets.bomb, 0),
+            Transform::from_translation(Vec3::new(0., 0., 2.)),
         )
     }
 }
@@ -58,9 +75,21 @@ impl BouncingBall {
             direction,
             Movable::Bounce,
             position,
-            Sprite::from_image(assets.bouncing_ball.clone()),
+            assets.sprite(assets.bouncing_ball, 0),
             Transform::from_translation(Vec3::new(0., 0., 4.)),
-            Weight::Light,
+            Weight::LIGHT,
+        )
+    }
+}
+
+impl Bridge {
+    pub fn spawn(assets: &GameObjectAssets, position: Position, capacity: u16) -> impl Bundle {
+        (
+            ObjectType::Bridge,
+            Bridge(capacity),
+            position,
+            assets.sprite(assets.bridge, 0),
+            Transform::from_translation(Vec3::new(0., 0., 1.)),
         )
     }
 }
@@ -68,13 +97,26 @@ impl BouncingBall {
 pub struct Button;
 
 impl Button {
-    pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
+    pub fn spawn(assets: &GameObjectAssets, position: Position, identifier: u16) -> impl Bundle {
         (
             ObjectType::Button,
             position,
-            Sprite::from_image(assets.button.clone()),
+            assets.sprite(assets.button, 0),
+            Transform::from_translation(Vec3::new(0., 0., 1.)),
+            Trigger(identifier),
+        )
+    }
+}
+
+impl Checkpoint {
+    pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
+        (
+            ObjectType::Checkpoint,
+            BlocksPushes,
+            Checkpoint,
+            position,
+            assets.sprite(assets.checkpoint, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
-            Trigger,
         )
     }
 }
@@ -94,15 +136,26 @@ impl Creature1 {
             direction,
             Movable::FollowRightHand,
             position,
-            Sprite::from_atlas_image(
-                assets.creature1.0.clone(),
-                TextureAtlas {
-                    layout: assets.creature1.1.clone(),
-                    index: direction as usize,
-                },
-            ),
+            assets.sprite(assets.creature1, direction.nearest_cardinal() as usize),
             Transform::from_translation(Vec3::new(0., 0., 4.)),
-            Weight::Light,
+            Weight::LIGHT,
+        )
+    }
+}
+
+impl Decoration {
+    pub fn spawn(
+        assets: &GameObjectAssets,
+        position: Position,
+        decoration: Decoration,
+    ) -> impl Bundle {
+        (
+            ObjectType::Decoration,
+            decoration,
+            position,
+            assets.sprite(assets.decoration, decoration.frame()),
+            // Decorations go underneath all other objects.
+            Transform::from_translation(Vec3::new(0., 0., 0.5)),
         )
     }
 }
@@ -119,13 +172,7 @@ impl Door {
         let InitialPositionAndMetadata { open, .. } = initial_position;
 
         let openable = Openable::Key;
-        let sprite = Sprite::from_atlas_image(
-            assets.door.0.clone(),
-            TextureAtlas {
-                layout: assets.door.1.clone(),
-                index: if open { 1 } else { 0 },
-            },
-        );
+        let sprite = assets.sprite(assets.door, if open { 1 } else { 0 });
         let transform = Transform::from_translation(Vec3::new(0., 0., 5.));
 
         if open {
@@ -150,26 +197,20 @@ impl Entrance {
             BlocksPushes,
             Entrance(level),
             position,
-            Sprite::from_atlas_image(
-                assets.entrance.0.clone(),
-                TextureAtlas {
-                    layout: assets.entrance.1.clone(),
-                    index: 0,
-                },
-            ),
+            assets.sprite(assets.entrance, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
         )
     }
 }
 
 impl Exit {
-    pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
+    pub fn spawn(assets: &GameObjectAssets, position: Position, level: Option<u16>) -> impl Bundle {
         (
             ObjectType::Exit,
             BlocksPushes,
-            Exit,
+            Exit(level),
             position,
-            Sprite::from_image(assets.exit.clone()),
+            assets.sprite(assets.exit, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
         )
     }
@@ -181,7 +222,7 @@ impl Explosion {
     pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
         (
             position,
-            Sprite::from_image(assets.explosion.clone()),
+            assets.sprite(assets.explosion, 0),
             Transform::from_translation(Vec3::new(0., 0., 4.)),
             Volatile,
         )
@@ -197,20 +238,22 @@ impl Gate {
         position: Position,
         initial_position: InitialPositionAndMetadata,
     ) -> EntityCommands<'a> {
-        let InitialPositionAndMetadata { level, open, .. } = initial_position;
-
-        let openable = if let Some(level) = level {
+        let InitialPositionAndMetadata {
+            identifier,
+            level,
+            open,
+            required_count,
+            ..
+        } = initial_position;
+
+        let openable = if let Some(count) = required_count {
+            Openable::FinishedCount(count)
+        } else if let Some(level) = level {
             Openable::LevelFinished(level)
         } else {
-            Openable::Trigger
+            Openable::Trigger(identifier.unwrap_or_default())
         };
-        let sprite = Sprite::from_atlas_image(
-            assets.gate.0.clone(),
-            TextureAtlas {
-                layout: assets.gate.1.clone(),
-                index: if open { 1 } else { 0 },
-            },
-        );
+        let sprite = assets.sprite(assets.gate, if open { 1 } else { 0 });
         let transform = Transform::from_translation(Vec3::new(0., 0., 5.));
 
         if open {
@@ -228,14 +271,51 @@ impl Gate {
     }
 }
 
+impl TimerGate {
+    pub fn spawn<'a>(
+        cb: &'a mut ChildBuilder,
+        assets: &GameObjectAssets,
+        position: Position,
+        initial_position: InitialPositionAndMetadata,
+    ) -> EntityCommands<'a> {
+        let seconds = initial_position
+            .seconds
+            .unwrap_or(DEFAULT_TIMER_GATE_SECONDS);
+        let timer_gate = TimerGate::new(seconds, initial_position.identifier);
+        let transform = Transform::from_translation(Vec3::new(0., 0., 5.));
+
+        // Gates of which the time has already run out are saved with zero
+        // seconds left.
+        if seconds == 0 {
+            cb.spawn((
+                ObjectType::TimerGate,
+                Massive,
+                timer_gate,
+                position,
+                assets.sprite(assets.gate, 0),
+                transform,
+            ))
+        } else {
+            cb.spawn((
+                ObjectType::TimerGate,
+                timer_gate,
+                position,
+                assets.sprite(assets.gate, 1),
+                transform,
+            ))
+        }
+    }
+}
+
 pub struct Grave;
 
 impl Grave {
     pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
         (
+            ObjectType::Grave,
             Massive,
             position,
-            Sprite::from_image(assets.grave.clone()),
+            assets.sprite(assets.grave, 0),
             Transform::from_translation(Vec3::new(0., 0., 4.)),
         )
     }
@@ -250,7 +330,7 @@ impl Ice {
             BlocksMovement::Enabled,
             position,
             Slippery,
-            Sprite::from_image(assets.ice.clone()),
+            assets.sprite(assets.ice, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
         )
     }
@@ -263,9 +343,9 @@ impl Key {
             Key,
             position,
             Pushable,
-            Sprite::from_image(assets.key.clone()),
+            assets.sprite(assets.key, 0),
             Transform::from_translation(Vec3::new(0., 0., 2.)),
-            Weight::Light,
+            Weight::LIGHT,
         )
     }
 }
@@ -278,7 +358,7 @@ impl Mine {
             ObjectType::Mine,
             Explosive,
             position,
-            Sprite::from_image(assets.mine.clone()),
+            assets.sprite(assets.mine, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
         )
     }
@@ -291,9 +371,9 @@ impl Player {
             BlocksPushes,
             Player,
             position,
-            Sprite::from_image(assets.player.clone()),
+            assets.sprite(assets.player, 0),
             Transform::from_translation(Vec3::new(0., 0., 3.)),
-            Weight::Heavy,
+            Weight::HEAVY,
         )
     }
 }
@@ -308,10 +388,11 @@ impl PurpleBlock {
             Paintable,
             position,
             Pushable,
-            Sprite::from_image(assets.purple_block.clone()),
+            assets.sprite(assets.purple_block, 0),
+            Variation { num_variants: 3 },
             Transform::from_translation(Vec3::new(0., 0., 3.)),
             TransformOnPush(ObjectType::RedBlock),
-            Weight::Heavy,
+            Weight::HEAVY,
         )
     }
 }
@@ -325,9 +406,9 @@ impl PurplePaint {
             Paint(ObjectType::PurpleBlock),
             position,
             Pushable,
-            Sprite::from_image(assets.purple_paint.clone()),
+            assets.sprite(assets.purple_paint, 0),
             Transform::from_translation(Vec3::new(0., 0., 3.)),
-            Weight::Light,
+            Weight::LIGHT,
         )
     }
 }
@@ -341,9 +422,9 @@ impl Raft {
             Floatable,
             position,
             Pushable,
-            Sprite::from_image(assets.raft.clone()),
+            assets.sprite(assets.raft, 0),
             Transform::from_translation(Vec3::new(0., 0., 2.)),
-            Weight::Heavy,
+            Weight::HEAVY,
         )
     }
 }
@@ -357,7 +438,8 @@ impl RedBlock {
             Massive,
             Paintable,
             position,
-            Sprite::from_image(assets.red_block.clone()),
+            assets.sprite(assets.red_block, 0),
+            Variation { num_variants: 3 },
             Transform::from_translation(Vec3::new(0., 0., 2.)),
         )
     }
@@ -372,9 +454,36 @@ impl RedPaint {
             Paint(ObjectType::RedBlock),
             position,
             Pushable,
-            Sprite::from_image(assets.red_paint.clone()),
+            assets.sprite(assets.red_paint, 0),
             Transform::from_translation(Vec3::new(0., 0., 3.)),
-            Weight::Light,
+            Weight::LIGHT,
+        )
+    }
+}
+
+impl Npc {
+    pub fn spawn(assets: &GameObjectAssets, position: Position, text: String) -> impl Bundle {
+        (
+            ObjectType::Npc,
+            BlocksPushes,
+            Massive,
+            Npc(text),
+            position,
+            assets.sprite(assets.npc, 0),
+            Transform::from_translation(Vec3::new(0., 0., 4.)),
+        )
+    }
+}
+
+impl Sign {
+    pub fn spawn(assets: &GameObjectAssets, position: Position, text: String) -> impl Bundle {
+        (
+            ObjectType::Sign,
+            BlocksPushes,
+            Sign(text),
+            position,
+            assets.sprite(assets.sign, 0),
+            Transform::from_translation(Vec3::new(0., 0., 1.)),
         )
     }
 }
@@ -386,7 +495,7 @@ impl Splash {
         (
             Floatable,
             position,
-            Sprite::from_image(assets.splash.clone()),
+            assets.sprite(assets.splash, 0),
             Transform::from_translation(Vec3::new(0., 0., 4.)),
             Volatile,
         )
@@ -398,7 +507,7 @@ impl Teleporter {
         (
             ObjectType::Teleporter,
             position,
-            Sprite::from_image(assets.teleporter.clone()),
+            assets.sprite(assets.teleporter, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
             Teleporter(identifier),
         )
@@ -410,43 +519,63 @@ impl Transporter {
         assets: &GameObjectAssets,
         position: Position,
         direction: Direction,
+        interval: u16,
     ) -> impl Bundle {
         (
             ObjectType::Transporter,
             BlocksMovement::Enabled,
             direction,
             position,
-            Sprite::from_atlas_image(
-                assets.transporter.0.clone(),
-                TextureAtlas {
-                    layout: assets.transporter.1.clone(),
-                    index: 0,
-                },
-            ),
+            assets.sprite(assets.transporter, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
-            Transporter,
+            Transporter::new(interval),
         )
     }
 }
 
-pub struct Water;
+pub struct Wall;
 
-impl Water {
+impl Wall {
     pub fn spawn(assets: &GameObjectAssets, position: Position) -> impl Bundle {
         (
+            ObjectType::Wall,
+            Autotile,
+            Massive,
+            position,
+            assets.sprite(assets.wall, 0),
+            Transform::from_translation(Vec3::new(0., 0., 3.)),
+        )
+    }
+}
+
+pub struct Water;
+
+impl Water {
+    /// Spawns water with a [Shoreline] overlay, so the water itself can keep
+    /// animating while the edges are autotiled.
+    pub fn spawn<'a>(
+        cb: &'a mut ChildBuilder,
+        assets: &GameObjectAssets,
+        position: Position,
+    ) -> EntityCommands<'a> {
+        let mut cb = cb.spawn((
             ObjectType::Water,
             Animatable { num_frames: 3 },
+            Autotile,
             Liquid,
             position,
-            Sprite::from_atlas_image(
-                assets.water.0.clone(),
-                TextureAtlas {
-                    layout: assets.water.1.clone(),
-                    index: 0,
-                },
-            ),
+            assets.sprite(assets.water, 0),
             Transform::from_translation(Vec3::new(0., 0., 1.)),
-        )
+            Variation { num_variants: 1 },
+        ));
+        cb.with_children(|cb| {
+            cb.spawn((
+                Shoreline,
+                assets.sprite(assets.shoreline, 0),
+                Transform::from_translation(Vec3::new(0., 0., 0.1)),
+            ));
+        });
+        cb
     }
 }
 
@@ -460,9 +589,10 @@ impl YellowBlock {
             Paintable,
             position,
             Pushable,
-            Sprite::from_image(assets.yellow_block.clone()),
+            assets.sprite(assets.yellow_block, 0),
+            Variation { num_variants: 3 },
             Transform::from_translation(Vec3::new(0., 0., 3.)),
-            Weight::Light,
+            Weight::LIGHT,
         )
     }
 }
@@ -9,18 +9,24 @@ use crate::{
 
 use super::{
     assets::GameObjectAssets,
-    object_bundles::{BlueBlock, BouncingBall, Creature1, Raft, RedBlock, Water},
-    BluePaint, Button, Door, Entrance, Exit, Explosion, Gate, Grave, Ice, Key, Mine, Player,
-    PurpleBlock, PurplePaint, RedPaint, Splash, Teleporter, Transporter, YellowBlock,
+    object_bundles::{BlueBlock, BouncingBall, Creature1, Raft, RedBlock, Wall, Water},
+    BestMovesLabel, BluePaint, Bomb, Bridge, Button, Checkpoint, Decoration, Door, Entrance, Exit,
+    Explosion, Gate, Grave, Ice, Key, Massive, Mine, Npc, Openable, Player, PurpleBlock,
+    PurplePaint, RedPaint, RevealedByLevel, Sign, Splash, Teleporter, TimerGate, TimerGateLabel,
+    Transporter, Weight, YellowBlock,
 };
 
 #[derive(Clone, Component, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub enum ObjectType {
     BlueBlock,
     BluePaint,
+    Bomb,
     BouncingBall,
+    Bridge,
     Button,
+    Checkpoint,
     Creature1,
+    Decoration,
     Door,
     Entrance,
     Exit,
@@ -30,15 +36,19 @@ pub enum ObjectType {
     Ice,
     Key,
     Mine,
+    Npc,
     Player,
     PurpleBlock,
     PurplePaint,
     Raft,
     RedBlock,
     RedPaint,
+    Sign,
     Splash,
     Teleporter,
+    TimerGate,
     Transporter,
+    Wall,
     Water,
     YellowBlock,
 }
@@ -48,9 +58,13 @@ impl Display for ObjectType {
         f.write_str(match self {
             Self::BlueBlock => "BlueBlock",
             Self::BluePaint => "BluePaint",
+            Self::Bomb => "Bomb",
             Self::BouncingBall => "BouncingBall",
+            Self::Bridge => "Bridge",
             Self::Button => "Button",
+            Self::Checkpoint => "Checkpoint",
             Self::Creature1 => "Creature1",
+            Self::Decoration => "Decoration",
             Self::Door => "Door",
             Self::Entrance => "Entrance",
             Self::Exit => "Exit",
@@ -60,15 +74,19 @@ impl Display for ObjectType {
             Self::Ice => "Ice",
             Self::Key => "Key",
             Self::Mine => "Mine",
+            Self::Npc => "Npc",
             Self::Player => "Player",
             Self::PurpleBlock => "PurpleBlock",
             Self::PurplePaint => "PurplePaint",
             Self::Raft => "Raft",
             Self::RedBlock => "RedBlock",
             Self::RedPaint => "RedPaint",
+            Self::Sign => "Sign",
             Self::Splash => "Splash",
             Self::Teleporter => "Teleporter",
+            Self::TimerGate => "TimerGate",
             Self::Transporter => "Transporter",
+            Self::Wall => "Wall",
             Self::Water => "Water",
             Self::YellowBlock => "YellowBlock",
         })
@@ -82,9 +100,13 @@ impl FromStr for ObjectType {
         match object_type {
             "BlueBlock" => Ok(Self::BlueBlock),
             "BluePaint" => Ok(Self::BluePaint),
+            "Bomb" => Ok(Self::Bomb),
             "BouncingBall" => Ok(Self::BouncingBall),
+            "Bridge" => Ok(Self::Bridge),
             "Button" => Ok(Self::Button),
+            "Checkpoint" => Ok(Self::Checkpoint),
             "Creature1" => Ok(Self::Creature1),
+            "Decoration" => Ok(Self::Decoration),
             "Door" => Ok(Self::Door),
             "Entrance" => Ok(Self::Entrance),
             "Exit" => Ok(Self::Exit),
@@ -92,14 +114,18 @@ impl FromStr for ObjectType {
             "Ice" => Ok(Self::Ice),
             "Key" => Ok(Self::Key),
             "Mine" => Ok(Self::Mine),
+            "Npc" => Ok(Self::Npc),
             "Player" => Ok(Self::Player),
             "PurpleBlock" => Ok(Self::PurpleBlock),
             "PurplePaint" => Ok(Self::PurplePaint),
             "Raft" => Ok(Self::Raft),
             "RedBlock" => Ok(Self::RedBlock),
             "RedPaint" => Ok(Self::RedPaint),
+            "Sign" => Ok(Self::Sign),
             "Teleporter" => Ok(Self::Teleporter),
+            "TimerGate" => Ok(Self::TimerGate),
             "Transporter" => Ok(Self::Transporter),
+            "Wall" => Ok(Self::Wall),
             "Water" => Ok(Self::Water),
             "YellowBlock" => Ok(Self::YellowBlock),
             _ => Err(UnknownObjectType),
@@ -108,6 +134,18 @@ impl FromStr for ObjectType {
 }
 
 impl ObjectType {
+    /// Returns whether objects of this type can be restarted by resetting
+    /// their position and direction, instead of being respawned.
+    ///
+    /// This is not the case for objects that may gain or lose components while
+    /// the level is being played.
+    pub fn can_restart_in_place(self) -> bool {
+        !matches!(
+            self,
+            Self::Door | Self::Gate | Self::Player | Self::Raft | Self::TimerGate
+        )
+    }
+
     /// Returns the object type this turns into when mixed with another.
     ///
     /// Only used for mixing of paint.
@@ -127,29 +165,51 @@ impl ObjectType {
     }
 }
 
-pub fn spawn_object_of_type(
-    cb: &mut ChildBuilder,
+pub fn spawn_object_of_type<'a>(
+    cb: &'a mut ChildBuilder,
     assets: &GameObjectAssets,
     fonts: &Fonts,
     object_type: ObjectType,
     initial_position: InitialPositionAndMetadata,
-) {
+) -> EntityCommands<'a> {
     let position = initial_position.position;
 
     match object_type {
         ObjectType::BlueBlock => cb.spawn(BlueBlock::spawn(assets, position)),
         ObjectType::BluePaint => cb.spawn(BluePaint::spawn(assets, position)),
+        ObjectType::Bomb => cb.spawn(Bomb::spawn(assets, position)),
         ObjectType::BouncingBall => cb.spawn(BouncingBall::spawn(
             assets,
             position,
             initial_position.direction.unwrap_or_default(),
         )),
-        ObjectType::Button => cb.spawn(Button::spawn(assets, position)),
+        ObjectType::Bridge => cb.spawn(Bridge::spawn(
+            assets,
+            position,
+            initial_position.capacity.unwrap_or(Weight::HEAVY.0),
+        )),
+        ObjectType::Button => cb.spawn(Button::spawn(
+            assets,
+            position,
+            initial_position.identifier.unwrap_or_default(),
+        )),
+        ObjectType::Checkpoint => cb.spawn(Checkpoint::spawn(assets, position)),
         ObjectType::Creature1 => cb.spawn(Creature1::spawn(
             assets,
             position,
             initial_position.direction.unwrap_or_default(),
         )),
+        ObjectType::Decoration => {
+            let mut cb = cb.spawn(Decoration::spawn(
+                assets,
+                position,
+                initial_position.decoration.unwrap_or(Decoration::Flower),
+            ));
+            if let Some(level) = initial_position.level {
+                cb.insert(RevealedByLevel(level));
+            }
+            cb
+        }
         ObjectType::Door => Door::spawn(cb, assets, position, initial_position),
         ObjectType::Entrance => {
             let mut cb = cb.spawn(Entrance::spawn(
@@ -157,6 +217,9 @@ pub fn spawn_object_of_type(
                 position,
                 initial_position.level.unwrap_or_default(),
             ));
+            if let Some(count) = initial_position.required_count {
+                cb.insert((Openable::FinishedCount(count), Massive));
+            }
             cb.with_children(|cb| {
                 cb.spawn((
                     Text2d::new(initial_position.level.unwrap_or_default().to_string()),
@@ -164,34 +227,66 @@ pub fn spawn_object_of_type(
                     TextFont::from_font(fonts.poppins_light.clone()).with_font_size(24.),
                     Transform::from_translation(Vec3::new(0., 0., 1.)),
                 ));
+                cb.spawn((
+                    BestMovesLabel,
+                    Text2d::default(),
+                    TextColor(ENTRANCE_TEXT),
+                    TextFont::from_font(fonts.poppins_light.clone()).with_font_size(10.),
+                    Transform::from_translation(Vec3::new(0., -16., 1.)),
+                ));
             });
             cb
         }
-        ObjectType::Exit => cb.spawn(Exit::spawn(assets, position)),
+        ObjectType::Exit => cb.spawn(Exit::spawn(assets, position, initial_position.level)),
         ObjectType::Explosion => cb.spawn(Explosion::spawn(assets, position)),
         ObjectType::Gate => Gate::spawn(cb, assets, position, initial_position),
         ObjectType::Grave => cb.spawn(Grave::spawn(assets, position)),
         ObjectType::Ice => cb.spawn(Ice::spawn(assets, position)),
         ObjectType::Key => cb.spawn(Key::spawn(assets, position)),
         ObjectType::Mine => cb.spawn(Mine::spawn(assets, position)),
+        ObjectType::Npc => cb.spawn(Npc::spawn(
+            assets,
+            position,
+            initial_position.text.unwrap_or_default(),
+        )),
         ObjectType::Player => cb.spawn(Player::spawn(assets, position)),
         ObjectType::PurpleBlock => cb.spawn(PurpleBlock::spawn(assets, position)),
         ObjectType::PurplePaint => cb.spawn(PurplePaint::spawn(assets, position)),
         ObjectType::Raft => cb.spawn(Raft::spawn(assets, position)),
         ObjectType::RedBlock => cb.spawn(RedBlock::spawn(assets, position)),
         ObjectType::RedPaint => cb.spawn(RedPaint::spawn(assets, position)),
+        ObjectType::Sign => cb.spawn(Sign::spawn(
+            assets,
+            position,
+            initial_position.text.unwrap_or_default(),
+        )),
         ObjectType::Splash => cb.spawn(Splash::spawn(assets, position)),
         ObjectType::Teleporter => cb.spawn(Teleporter::spawn(
             assets,
             position,
             initial_position.identifier.unwrap_or_default(),
         )),
+        ObjectType::TimerGate => {
+            let mut cb = TimerGate::spawn(cb, assets, position, initial_position);
+            cb.with_children(|cb| {
+                cb.spawn((
+                    TimerGateLabel,
+                    Text2d::default(),
+                    TextColor(ENTRANCE_TEXT),
+                    TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+                    Transform::from_translation(Vec3::new(0., 24., 1.)),
+                ));
+            });
+            cb
+        }
         ObjectType::Transporter => cb.spawn(Transporter::spawn(
             assets,
             position,
             initial_position.direction.unwrap_or_default(),
+            initial_position.interval.unwrap_or(1),
         )),
-        ObjectType::Water => cb.spawn(Water::spawn(assets, position)),
+        ObjectType::Wall => cb.spawn(Wall::spawn(assets, position)),
+        ObjectType::Water => Water::spawn(cb, assets, position),
         ObjectType::YellowBlock => cb.spawn(YellowBlock::spawn(assets, position)),
-    };
+    }
 }
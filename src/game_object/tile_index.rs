@@ -0,0 +1,84 @@
+use bevy::{prelude::*, utils::HashMap};
+use smallvec::SmallVec;
+
+use super::Position;
+
+/// Spatial index of all entities with a [Position].
+///
+/// Allows looking up the entities on a given tile without scanning all
+/// entities in the level. The index is synced with spawned, moved and
+/// despawned entities by [update_tile_index()], but systems that move entities
+/// should also update it directly, so that other systems see the new positions
+/// within the same frame.
+#[derive(Default, Resource)]
+pub struct TileIndex {
+    entities_by_position: HashMap<Position, SmallVec<[Entity; 4]>>,
+    positions_by_entity: HashMap<Entity, Position>,
+}
+
+impl TileIndex {
+    /// Returns all the entities at the given position.
+    ///
+    /// Entities that were despawned during the current frame may still be
+    /// returned, so callers should be prepared for lookups of the returned
+    /// entities to fail.
+    pub fn get(&self, position: Position) -> &[Entity] {
+        self.entities_by_position
+            .get(&position)
+            .map(SmallVec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Inserts the entity at the given position, or moves it there if it was
+    /// already indexed.
+    pub fn insert(&mut self, entity: Entity, position: Position) {
+        if let Some(previous_position) = self.positions_by_entity.insert(entity, position) {
+            if previous_position == position {
+                return;
+            }
+
+            self.remove_from_position(entity, previous_position);
+        }
+
+        self.entities_by_position
+            .entry(position)
+            .or_default()
+            .push(entity);
+    }
+
+    /// Removes all entities from the index.
+    pub fn clear(&mut self) {
+        self.entities_by_position.clear();
+        self.positions_by_entity.clear();
+    }
+
+    /// Removes the entity from the index.
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(position) = self.positions_by_entity.remove(&entity) {
+            self.remove_from_position(entity, position);
+        }
+    }
+
+    fn remove_from_position(&mut self, entity: Entity, position: Position) {
+        if let Some(entities) = self.entities_by_position.get_mut(&position) {
+            entities.retain(|other| *other != entity);
+            if entities.is_empty() {
+                self.entities_by_position.remove(&position);
+            }
+        }
+    }
+}
+
+pub fn update_tile_index(
+    mut tile_index: ResMut<TileIndex>,
+    mut removed: RemovedComponents<Position>,
+    changed_query: Query<(Entity, &Position), Changed<Position>>,
+) {
+    for entity in removed.read() {
+        tile_index.remove(entity);
+    }
+
+    for (entity, position) in &changed_query {
+        tile_index.insert(entity, *position);
+    }
+}
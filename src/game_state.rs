@@ -1,10 +1,27 @@
-use std::{collections::BTreeSet, fs};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 
 use anyhow::Context;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::utils::ensure_chunky_dir;
+use crate::{
+    achievements::{Achievement, AchievementCounters},
+    game_object::{ObjectType, Position},
+    levels::LEVELS,
+    modifiers::Modifier,
+    randomizer::Randomizer,
+    skins::PlayerSkin,
+    utils::{ensure_chunky_dir, home_dir},
+};
+
+/// Number of failed attempts at a level after which the player is offered to
+/// skip it.
+const ASSIST_ATTEMPTS: u32 = 5;
 
 #[derive(Default, Deserialize, Resource, Serialize)]
 pub struct GameState {
@@ -14,41 +31,600 @@ pub struct GameState {
     #[serde(skip)]
     pub previous_level: Option<u16>,
 
+    /// What killed the player most recently, to show on the game over screen.
+    #[serde(skip)]
+    pub last_death_cause: Option<DeathCause>,
+
+    /// The object that killed the player most recently, if any.
+    #[serde(skip)]
+    pub last_killer: Option<Killer>,
+
+    /// Whether the attract mode is playing its demo, during which the
+    /// player's progress is left alone.
+    #[serde(skip)]
+    pub is_playing_demo: bool,
+
+    /// Levels that are hubs, as declared by the levels themselves. Level 0
+    /// is a hub regardless.
+    #[serde(skip)]
+    pub hub_levels: BTreeSet<u16>,
+
+    /// The hub the player was in most recently, which is where going back to
+    /// the hub leads.
+    #[serde(skip)]
+    pub current_hub: u16,
+
     pub finished_levels: BTreeSet<u16>,
+
+    /// Levels of which the player has found the secret exit.
+    #[serde(default)]
+    pub secret_exits: BTreeSet<u16>,
+
+    /// Levels the player has skipped using the assist mode, without having
+    /// finished them.
+    #[serde(default)]
+    pub skipped_levels: BTreeSet<u16>,
+
+    /// Levels of which the player has reached the entrance in the hub, so
+    /// they can fast-travel there.
+    #[serde(default)]
+    pub discovered_entrances: BTreeSet<u16>,
+
+    #[serde(default)]
+    pub level_stats: BTreeMap<u16, LevelStats>,
+
+    /// Whether the player has finished every level.
+    #[serde(default)]
+    pub completed: bool,
+
+    /// Identifiers of the tutorial prompts the player has already followed.
+    #[serde(default)]
+    pub seen_tutorials: BTreeSet<String>,
+
+    /// Names of the cutscenes that have already been played.
+    #[serde(default)]
+    pub seen_cutscenes: BTreeSet<String>,
+
+    /// The level the player left before finishing it, so they can resume
+    /// where they left off.
+    #[serde(default)]
+    pub level_in_progress: Option<LevelInProgress>,
+
+    /// Achievements the player has unlocked.
+    #[serde(default)]
+    pub achievements: BTreeSet<Achievement>,
+
+    #[serde(default)]
+    pub achievement_counters: AchievementCounters,
+
+    /// Modifiers with which levels have been finished.
+    #[serde(default)]
+    pub modifier_completions: BTreeMap<u16, BTreeSet<Modifier>>,
+
+    /// Shuffles the entrances in the hub, if the randomizer is enabled.
+    #[serde(default)]
+    pub randomizer: Option<Randomizer>,
+
+    /// The look the player has chosen in the settings.
+    #[serde(default)]
+    pub player_skin: PlayerSkin,
+
+    #[serde(skip)]
+    current_attempt: Attempt,
+}
+
+/// Statistics for a single level.
+/// The ways in which the player can die.
+#[derive(Clone, Copy, Debug)]
+pub enum DeathCause {
+    Deadly,
+    Drowned,
+    Exploded,
+    OutOfMoves,
+}
+
+/// The object that killed the player, and the position where it happened.
+#[derive(Clone, Copy, Debug)]
+pub struct Killer {
+    pub object_type: ObjectType,
+    pub position: Position,
+}
+
+impl DeathCause {
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Deadly => "You ran into something deadly",
+            Self::Drowned => "You drowned",
+            Self::Exploded => "You were blown up",
+            Self::OutOfMoves => "You ran out of moves",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LevelStats {
+    pub attempts: u32,
+    pub deaths: u32,
+    pub best_moves: Option<u32>,
+
+    /// Fastest completion time, in seconds.
+    pub best_time: Option<f32>,
+
+    /// When the level was first completed, in seconds since the Unix epoch.
+    pub completed_at: Option<u64>,
+}
+
+impl LevelResult {
+    /// Returns whether the attempt improved upon a previous best time.
+    pub fn is_new_best_time(&self) -> bool {
+        self.previous_stats
+            .best_time
+            .is_some_and(|best| self.time < best)
+    }
+}
+
+impl LevelStats {
+    /// Describes the current attempt at the level, such as "Attempt #7, 3
+    /// deaths".
+    pub fn attempt_summary(&self) -> String {
+        let attempt = self.attempts.max(1);
+        match self.deaths {
+            1 => format!("Attempt #{attempt}, 1 death"),
+            deaths => format!("Attempt #{attempt}, {deaths} deaths"),
+        }
+    }
+}
+
+/// Statistics of the attempt with which the player finished a level.
+#[derive(Clone, Debug)]
+pub struct LevelResult {
+    pub moves: u32,
+
+    /// Time of the attempt, in seconds.
+    pub time: f32,
+
+    /// Stats of the level from before the attempt.
+    pub previous_stats: LevelStats,
+}
+
+/// Snapshot of a level the player left before finishing it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LevelInProgress {
+    pub level: u16,
+    pub contents: String,
+}
+
+/// Progress of the attempt at the current level.
+#[derive(Default)]
+struct Attempt {
+    moves: u32,
+    started: Duration,
+
+    /// Time the attempt has spent paused, such as while a menu was open.
+    paused: Duration,
+
+    /// When the attempt got paused, if it is paused right now.
+    paused_since: Option<Duration>,
+}
+
+impl Attempt {
+    /// Returns how long the attempt has been going on, leaving out the time
+    /// it spent paused.
+    fn duration(&self, now: Duration) -> Duration {
+        let paused = self.paused
+            + self
+                .paused_since
+                .map_or(Duration::ZERO, |since| now.saturating_sub(since));
+        now.saturating_sub(self.started).saturating_sub(paused)
+    }
 }
 
 impl GameState {
     /// Loads game state from disk, or returns `Self::default()` if no
     /// game state could be loaded.
+    ///
+    /// Falls back to the backup of the previous save if the game state itself
+    /// cannot be loaded.
     pub fn load() -> Self {
-        fs::read(ensure_chunky_dir().join("game_state.json"))
+        Self::load_from(&game_state_path())
+            .or_else(|err| {
+                error!("Can't load game state: {err}");
+                Self::load_from(&backup_path())
+            })
+            .map_err(|err| error!("Can't load game state backup: {err}"))
+            .unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Result<Self, anyhow::Error> {
+        fs::read(path)
             .context("Can't read file")
             .and_then(|json| Self::from_json(&json))
-            .map_err(|err| println!("Can't load game state: {err}"))
-            .unwrap_or_default()
+    }
+
+    /// Exports the player's progress to the given file.
+    pub fn export_progress(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let json = self.to_json()?;
+        fs::write(path, json).context("Can't write file")
+    }
+
+    /// Imports the player's progress from the given file, replacing the
+    /// current progress.
+    pub fn import_progress(&mut self, path: &Path) -> Result<(), anyhow::Error> {
+        let Self {
+            finished_levels,
+            skipped_levels,
+            discovered_entrances,
+            level_stats,
+            completed,
+            seen_tutorials,
+            seen_cutscenes,
+            achievements,
+            achievement_counters,
+            modifier_completions,
+            randomizer,
+            player_skin,
+            ..
+        } = Self::load_from(path)?;
+
+        self.finished_levels = finished_levels;
+        self.skipped_levels = skipped_levels;
+        self.discovered_entrances = discovered_entrances;
+        self.level_stats = level_stats;
+        self.completed = completed;
+        self.seen_tutorials = seen_tutorials;
+        self.seen_cutscenes = seen_cutscenes;
+        self.achievements = achievements;
+        self.achievement_counters = achievement_counters;
+        self.modifier_completions = modifier_completions;
+        self.randomizer = randomizer;
+        self.player_skin = player_skin;
+
+        self.save();
+        Ok(())
+    }
+
+    /// Marks the game as completed if all levels have been finished.
+    ///
+    /// Returns whether the game has just been completed.
+    pub fn check_for_completion(&mut self) -> bool {
+        if self.completed
+            || !LEVELS
+                .iter()
+                .all(|(level, _)| self.is_hub_level(*level) || self.finished_levels.contains(level))
+        {
+            return false;
+        }
+
+        self.completed = true;
+        self.save();
+        true
+    }
+
+    /// Returns the number of moves made in the current attempt.
+    pub fn current_moves(&self) -> u32 {
+        self.current_attempt.moves
+    }
+
+    /// Returns how long the current attempt has been going on.
+    ///
+    /// `now` is the elapsed time since the start of the game.
+    pub fn current_attempt_duration(&self, now: Duration) -> Duration {
+        self.current_attempt.duration(now)
+    }
+
+    /// Stops the clock of the current attempt until it is resumed.
+    ///
+    /// `now` is the elapsed time since the start of the game.
+    pub fn pause_attempt(&mut self, now: Duration) {
+        self.current_attempt.paused_since.get_or_insert(now);
+    }
+
+    /// Resumes the clock of the current attempt after it was paused.
+    ///
+    /// `now` is the elapsed time since the start of the game.
+    pub fn resume_attempt(&mut self, now: Duration) {
+        if let Some(since) = self.current_attempt.paused_since.take() {
+            self.current_attempt.paused += now.saturating_sub(since);
+        }
+    }
+
+    pub fn has_seen_cutscene(&self, name: &str) -> bool {
+        self.seen_cutscenes.contains(name)
+    }
+
+    pub fn has_seen_tutorial(&self, id: &str) -> bool {
+        self.seen_tutorials.contains(id)
     }
 
     pub fn is_in_hub(&self) -> bool {
-        self.current_level == 0
+        self.is_hub_level(self.current_level)
+    }
+
+    pub fn is_hub_level(&self, level: u16) -> bool {
+        level == 0 || self.hub_levels.contains(&level)
+    }
+
+    /// Finishes the current level, recording the statistics of the current
+    /// attempt.
+    ///
+    /// `now` is the elapsed time since the start of the game. Returns the
+    /// result of the attempt, unless the player is in the hub.
+    pub fn finish_current_level(&mut self, now: Duration) -> Option<LevelResult> {
+        self.finished_levels.insert(self.current_level);
+        self.skipped_levels.remove(&self.current_level);
+        if self
+            .level_in_progress
+            .as_ref()
+            .is_some_and(|in_progress| in_progress.level == self.current_level)
+        {
+            self.level_in_progress = None;
+        }
+
+        let mut result = None;
+        if !self.is_in_hub() {
+            let moves = self.current_attempt.moves;
+            let time = self.current_attempt.duration(now).as_secs_f32();
+
+            let stats = self.level_stats.entry(self.current_level).or_default();
+            result = Some(LevelResult {
+                moves,
+                time,
+                previous_stats: stats.clone(),
+            });
+            stats.best_moves = Some(stats.best_moves.map_or(moves, |best| best.min(moves)));
+            stats.best_time = Some(stats.best_time.map_or(time, |best| best.min(time)));
+            if stats.completed_at.is_none() {
+                stats.completed_at = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .ok()
+                    .map(|since_epoch| since_epoch.as_secs());
+            }
+        }
+
+        self.save();
+        result
+    }
+
+    /// Records the death of the player in the current level.
+    pub fn record_death(&mut self, cause: DeathCause, killer: Option<Killer>) {
+        self.last_death_cause = Some(cause);
+        self.last_killer = killer;
+
+        if !self.is_in_hub() {
+            self.level_stats
+                .entry(self.current_level)
+                .or_default()
+                .deaths += 1;
+            self.save()
+        }
+    }
+
+    /// Remembers that the cutscene with the given name has been played, so it
+    /// won't be played again.
+    pub fn mark_cutscene_seen(&mut self, name: &str) {
+        if self.seen_cutscenes.insert(name.to_owned()) {
+            self.save()
+        }
+    }
+
+    /// Remembers that the player has followed the tutorial prompt with the
+    /// given identifier, so it won't be shown again.
+    pub fn mark_tutorial_seen(&mut self, id: &str) {
+        if self.seen_tutorials.insert(id.to_owned()) {
+            self.save()
+        }
+    }
+
+    /// Records that the player left the current level through its secret
+    /// exit.
+    ///
+    /// Returns whether the secret exit was found for the first time.
+    pub fn record_secret_exit(&mut self) -> bool {
+        let is_new = self.secret_exits.insert(self.current_level);
+        if is_new {
+            self.save();
+        }
+        is_new
+    }
+
+    /// Remembers that the player has reached the entrance to the given level
+    /// in the hub.
+    pub fn record_discovered_entrance(&mut self, level: u16) {
+        if !self.is_playing_demo && self.discovered_entrances.insert(level) {
+            self.save();
+        }
+    }
+
+    /// Marks the given level as finished, without recording any statistics.
+    pub fn mark_level_finished(&mut self, level: u16) {
+        if self.finished_levels.insert(level) {
+            self.save();
+        }
+    }
+
+    /// Enables the randomizer with the given seed, or disables it if `None`.
+    pub fn set_randomizer_seed(&mut self, seed: Option<u64>) {
+        self.randomizer = seed.map(|seed| Randomizer::new(seed, |level| self.is_hub_level(level)));
+        self.save()
+    }
+
+    /// Moves the given number of steps through the skins the player has
+    /// unlocked, wrapping around at either end.
+    pub fn cycle_player_skin(&mut self, steps: isize) {
+        let unlocked: Vec<PlayerSkin> = PlayerSkin::ALL
+            .into_iter()
+            .filter(|skin| skin.is_unlocked(self))
+            .collect();
+        let index = unlocked
+            .iter()
+            .position(|skin| *skin == self.player_skin)
+            .unwrap_or_default() as isize;
+        let len = unlocked.len() as isize;
+        self.player_skin = unlocked[(index + steps).rem_euclid(len) as usize];
+        self.save()
+    }
+
+    /// Returns whether the player should be offered to skip the current
+    /// level, which is after failing it a number of times without ever
+    /// finishing it.
+    pub fn can_skip_current_level(&self) -> bool {
+        !self.is_in_hub()
+            && !self.finished_levels.contains(&self.current_level)
+            && !self.skipped_levels.contains(&self.current_level)
+            && self
+                .level_stats
+                .get(&self.current_level)
+                .is_some_and(|stats| stats.attempts >= ASSIST_ATTEMPTS)
+    }
+
+    /// Marks the current level as skipped, so it stands out in the hub. The
+    /// level still counts as unfinished.
+    pub fn skip_current_level(&mut self) {
+        if self.skipped_levels.insert(self.current_level) {
+            self.save();
+        }
+    }
+
+    /// Records that the current level was finished with the given modifiers.
+    pub fn record_modifier_completion(&mut self, modifiers: BTreeSet<Modifier>) {
+        self.modifier_completions
+            .entry(self.current_level)
+            .or_default()
+            .extend(modifiers);
+        self.save()
+    }
+
+    /// Records a move by the player in the current level.
+    pub fn record_move(&mut self) {
+        self.current_attempt.moves += 1;
+    }
+
+    /// Records blocks pushed by the player.
+    ///
+    /// The counter is saved along with the next change that gets saved, to
+    /// avoid writing the game state for every move.
+    pub fn record_blocks_pushed(&mut self, count: u32) {
+        if self.is_playing_demo {
+            return;
+        }
+
+        self.achievement_counters.blocks_pushed += count;
+    }
+
+    /// Records a creature that the player has sunk.
+    pub fn record_creature_sunk(&mut self) {
+        self.achievement_counters.creatures_sunk += 1;
+        self.save()
+    }
+
+    /// Unlocks the given achievement.
+    ///
+    /// Returns whether the achievement wasn't unlocked before.
+    pub fn unlock_achievement(&mut self, achievement: Achievement) -> bool {
+        let is_new = self.achievements.insert(achievement);
+        if is_new {
+            self.save();
+        }
+        is_new
+    }
+
+    /// Forgets about all finished levels and their statistics.
+    pub fn reset_progress(&mut self) {
+        self.finished_levels.clear();
+        self.secret_exits.clear();
+        self.skipped_levels.clear();
+        self.discovered_entrances.clear();
+        self.level_stats.clear();
+        self.completed = false;
+        self.seen_tutorials.clear();
+        self.seen_cutscenes.clear();
+        self.level_in_progress = None;
+        self.achievements.clear();
+        self.achievement_counters = AchievementCounters::default();
+        self.modifier_completions.clear();
+        self.randomizer = None;
+        self.player_skin = PlayerSkin::default();
+
+        self.save()
+    }
+
+    /// Remembers the contents of a level the player is leaving unfinished.
+    pub fn set_level_in_progress(&mut self, level: u16, contents: String) {
+        self.level_in_progress = Some(LevelInProgress { level, contents });
+
+        self.save()
+    }
+
+    /// Returns the contents of the given level if the player has left it in
+    /// progress, which will be forgotten about.
+    pub fn take_level_in_progress(&mut self, level: u16) -> Option<String> {
+        if self.is_playing_demo
+            || self
+                .level_in_progress
+                .as_ref()
+                .is_none_or(|in_progress| in_progress.level != level)
+        {
+            return None;
+        }
+
+        let in_progress = self.level_in_progress.take();
+        self.save();
+        in_progress.map(|in_progress| in_progress.contents)
     }
 
     pub fn set_current_level(&mut self, level: u16) {
         self.previous_level = Some(self.current_level);
         self.current_level = level;
+        if self.is_hub_level(level) {
+            self.current_hub = level;
+        }
 
         self.save()
     }
 
+    /// Starts a new attempt at the current level.
+    ///
+    /// `now` is the elapsed time since the start of the game.
+    pub fn start_attempt(&mut self, now: Duration) {
+        // Attempts that start while the game is paused, such as when
+        // restarting from the menu, only start counting once it resumes.
+        self.current_attempt = Attempt {
+            moves: 0,
+            started: now,
+            paused: Duration::ZERO,
+            paused_since: self.current_attempt.paused_since.map(|_| now),
+        };
+
+        if !self.is_in_hub() && !self.is_playing_demo {
+            self.level_stats
+                .entry(self.current_level)
+                .or_default()
+                .attempts += 1;
+            self.save()
+        }
+    }
+
     /// Saves game state to disk.
     ///
-    /// This is called automatically on drop.
+    /// The game state is written to a temporary file first, which then
+    /// replaces the previous save, so that a crash mid-write cannot corrupt
+    /// it. The previous save, if valid, is kept as a backup.
     fn save(&self) {
         self.to_json()
             .and_then(|json| {
-                fs::write(ensure_chunky_dir().join("game_state.json"), json)
-                    .map_err(anyhow::Error::from)
+                let path = game_state_path();
+                let temp_path = path.with_extension("json.tmp");
+                fs::write(&temp_path, json).context("Can't write temporary file")?;
+
+                // Don't let a corrupted save replace a good backup.
+                if Self::load_from(&path).is_ok() {
+                    fs::rename(&path, backup_path()).context("Can't create backup")?;
+                }
+
+                fs::rename(&temp_path, &path).context("Can't replace game state")
             })
-            .unwrap_or_else(|err| println!("Can't save game state: {err}"));
+            .unwrap_or_else(|err| error!("Can't save game state: {err}"));
     }
 
     /// Serializes the game state to JSON.
@@ -61,3 +637,61 @@ impl GameState {
         serde_json::from_slice(bytes).map_err(anyhow::Error::from)
     }
 }
+
+fn game_state_path() -> PathBuf {
+    ensure_chunky_dir().join("game_state.json")
+}
+
+fn backup_path() -> PathBuf {
+    ensure_chunky_dir().join("game_state.json.bak")
+}
+
+/// Default path of the file that progress gets exported to and imported from.
+///
+/// This lives directly in the home directory, so that players can easily find
+/// it to move their progress between machines. On iOS, it goes in the
+/// documents directory instead, since that is the only place the player can
+/// reach through the Files app.
+pub fn progress_export_path() -> PathBuf {
+    let parent_dir = if cfg!(target_os = "ios") {
+        home_dir().join("Documents")
+    } else {
+        home_dir()
+    };
+
+    parent_dir.join("chunky_progress.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attempt_duration_leaves_out_paused_time() {
+        let mut game_state = GameState::default();
+        game_state.start_attempt(Duration::from_secs(1));
+        game_state.pause_attempt(Duration::from_secs(3));
+        assert_eq!(
+            game_state.current_attempt_duration(Duration::from_secs(8)),
+            Duration::from_secs(2)
+        );
+
+        game_state.resume_attempt(Duration::from_secs(10));
+        assert_eq!(
+            game_state.current_attempt_duration(Duration::from_secs(12)),
+            Duration::from_secs(4)
+        );
+    }
+
+    #[test]
+    fn attempt_started_while_paused_counts_from_resume() {
+        let mut game_state = GameState::default();
+        game_state.pause_attempt(Duration::from_secs(1));
+        game_state.start_attempt(Duration::from_secs(2));
+        game_state.resume_attempt(Duration::from_secs(5));
+        assert_eq!(
+            game_state.current_attempt_duration(Duration::from_secs(6)),
+            Duration::from_secs(1)
+        );
+    }
+}
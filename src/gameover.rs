@@ -1,10 +1,75 @@
 use bevy::prelude::*;
 
-use crate::{constants::*, editor::EditorState, fonts::Fonts, Player};
+use crate::{
+    background::Background, constants::*, editor::EditorState, fonts::Fonts,
+    game_object::ObjectType, game_state::GameState, input_prompt::InputPrompt, levels::Dimensions,
+    menu::MenuState, settings::Settings, timers::RespawnTimer, LevelCheckpoint, LoadLevel,
+    LoadRelativeLevel, Player, RestoreSnapshot,
+};
+
+/// Color of the highlight on the tile where the player got killed.
+const KILLER_HIGHLIGHT_COLOR: Color = Color::srgba(1., 0.1, 0.1, 0.5);
+
+/// The highlight is shown above the level objects.
+const KILLER_HIGHLIGHT_Z: f32 = 8.;
+
+/// Number of times per second the highlight flashes.
+const KILLER_HIGHLIGHT_FREQUENCY: f32 = 2.;
 
 #[derive(Component)]
 pub struct GameOver;
 
+#[derive(Component)]
+pub struct DeathCauseText;
+
+#[derive(Component)]
+pub struct DeathCountText;
+
+#[derive(Component)]
+pub struct KillerText;
+
+/// Marks the tile where the player got killed, while the game over screen is
+/// shown.
+#[derive(Component)]
+pub struct KillerHighlight;
+
+#[derive(Clone, Copy, Component, Default, Eq, PartialEq)]
+pub enum GameOverButton {
+    #[default]
+    Retry,
+    Undo,
+    BackToHub,
+    SkipLevel,
+}
+
+impl GameOverButton {
+    const ALL: [Self; 4] = [Self::Retry, Self::Undo, Self::BackToHub, Self::SkipLevel];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Retry => "Retry",
+            Self::Undo => "Undo move",
+            Self::BackToHub => "Back to Hub",
+            Self::SkipLevel => "Skip Level",
+        }
+    }
+
+    /// Returns whether the button is shown. There's no hub to go back to when
+    /// already in it, and skipping is only offered after a number of failed
+    /// attempts.
+    fn is_available(self, game_state: &GameState) -> bool {
+        match self {
+            Self::Retry | Self::Undo => true,
+            Self::BackToHub => !game_state.is_in_hub(),
+            Self::SkipLevel => game_state.can_skip_current_level(),
+        }
+    }
+}
+
+/// The button that is selected on the game over screen.
+#[derive(Default, Resource)]
+pub struct GameOverSelection(GameOverButton);
+
 pub fn setup_gameover(commands: &mut Commands, fonts: &Fonts) {
     commands
         .spawn((
@@ -14,40 +79,330 @@ pub fn setup_gameover(commands: &mut Commands, fonts: &Fonts) {
             GlobalZIndex(100),
             Node {
                 display: Display::None,
-                width: Val::Px(300.),
-                height: Val::Px(80.),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Px(600.),
                 border: UiRect::all(Val::Px(2.)),
                 margin: UiRect::all(Val::Auto),
+                padding: UiRect::all(Val::Px(20.)),
+                row_gap: Val::Px(10.),
                 position_type: PositionType::Absolute,
                 ..default()
             },
         ))
         .with_children(|cb| {
             cb.spawn((
-                Text::new("Game Over\n\nPress Enter to try again"),
+                Text::new("Game Over"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(32.),
+            ));
+            cb.spawn((
+                DeathCauseText,
+                Text::default(),
                 TextColor(WHITE),
                 TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
-                TextLayout::new_with_justify(JustifyText::Center),
-                Node {
-                    margin: UiRect::all(Val::Auto),
-                    ..default()
-                },
+            ));
+            cb.spawn((
+                KillerText,
+                Text::default(),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+            ));
+            cb.spawn((
+                DeathCountText,
+                Text::default(),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+            ));
+            cb.spawn(Node {
+                column_gap: Val::Px(10.),
+                margin: UiRect::top(Val::Px(10.)),
+                ..default()
+            })
+            .with_children(|cb| {
+                for button in GameOverButton::ALL {
+                    cb.spawn((
+                        button,
+                        Button,
+                        BackgroundColor(BLUE),
+                        Node {
+                            height: Val::Px(44.),
+                            width: Val::Px(130.),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|cb| {
+                        cb.spawn((
+                            Text::new(button.label()),
+                            TextColor(WHITE),
+                            TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                            Node {
+                                margin: UiRect::all(Val::Auto),
+                                ..default()
+                            },
+                        ));
+                    });
+                }
+            });
+            cb.spawn((
+                InputPrompt::new("{confirm} to choose"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
             ));
         });
 }
 
+/// Shows the game over screen when the player has died, unless a checkpoint
+/// was reached, in which case the player respawns there after a short delay.
+#[expect(clippy::too_many_arguments)]
 pub fn check_for_game_over(
+    mut commands: Commands,
     mut game_over_query: Query<&mut Node, With<GameOver>>,
+    mut button_query: Query<(&GameOverButton, &mut Node), Without<GameOver>>,
+    mut cause_text_query: Query<&mut Text, (With<DeathCauseText>, Without<DeathCountText>)>,
+    mut count_text_query: Query<&mut Text, (With<DeathCountText>, Without<DeathCauseText>)>,
     player_query: Query<Entity, With<Player>>,
+    mut respawn_timer: ResMut<RespawnTimer>,
+    mut selection: ResMut<GameOverSelection>,
+    checkpoint: Res<LevelCheckpoint>,
     editor: Res<EditorState>,
+    game_state: Res<GameState>,
+    time: Res<Time>,
 ) {
     let mut game_over_style = game_over_query.get_single_mut().unwrap();
 
-    if player_query.get_single().is_ok() || editor.is_open {
+    let is_alive = player_query.get_single().is_ok();
+    if !is_alive && !editor.is_open && checkpoint.position.is_some() {
+        respawn_timer.tick(time.delta());
+        if respawn_timer.finished() {
+            respawn_timer.reset();
+            commands.trigger(RestoreSnapshot::Checkpoint);
+        }
+        return;
+    }
+
+    if is_alive || editor.is_open {
         if game_over_style.display != Display::None {
             game_over_style.display = Display::None;
         }
-    } else if game_over_style.display != Display::Flex {
-        game_over_style.display = Display::Flex;
+        return;
+    } else if game_over_style.display == Display::Flex {
+        return;
+    }
+
+    game_over_style.display = Display::Flex;
+    selection.0 = GameOverButton::Retry;
+
+    for mut text in &mut cause_text_query {
+        text.0 = game_state
+            .last_death_cause
+            .map(|cause| cause.description())
+            .unwrap_or_default()
+            .to_owned();
+    }
+
+    for mut text in &mut count_text_query {
+        text.0 = if game_state.is_in_hub() {
+            String::new()
+        } else {
+            game_state
+                .level_stats
+                .get(&game_state.current_level)
+                .cloned()
+                .unwrap_or_default()
+                .attempt_summary()
+        };
+    }
+
+    for (button, mut node) in &mut button_query {
+        node.display = if button.is_available(&game_state) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+/// Tells the player what killed them when the game over screen is shown, and
+/// highlights the tile where it happened.
+pub fn show_death_recap(
+    mut commands: Commands,
+    game_over_query: Query<Ref<Node>, With<GameOver>>,
+    mut text_query: Query<&mut Text, With<KillerText>>,
+    background_query: Query<Entity, With<Background>>,
+    highlight_query: Query<Entity, With<KillerHighlight>>,
+    dimensions: Res<Dimensions>,
+    game_state: Res<GameState>,
+) {
+    let Ok(node) = game_over_query.get_single() else {
+        return;
+    };
+    if !node.is_changed() {
+        return;
+    }
+
+    for entity in &highlight_query {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let killer = game_state
+        .last_killer
+        .filter(|_| node.display == Display::Flex);
+
+    for mut text in &mut text_query {
+        text.0 = killer
+            .map(|killer| {
+                format!(
+                    "{} at {}, {}",
+                    killer_name(killer.object_type),
+                    killer.position.x,
+                    killer.position.y
+                )
+            })
+            .unwrap_or_default();
+    }
+
+    let (Some(killer), Ok(background)) = (killer, background_query.get_single()) else {
+        return;
+    };
+
+    let translation = dimensions.translation_of(killer.position);
+    commands.entity(background).with_children(|cb| {
+        cb.spawn((
+            KillerHighlight,
+            Sprite::from_color(KILLER_HIGHLIGHT_COLOR, Vec2::splat(GRID_SIZE as f32)),
+            Transform::from_translation(translation.extend(KILLER_HIGHLIGHT_Z)),
+        ));
+    });
+}
+
+/// Flashes the highlight of the tile where the player got killed, unless the
+/// player wants to avoid cosmetic motion.
+pub fn flash_killer_highlight(
+    mut highlight_query: Query<&mut Sprite, With<KillerHighlight>>,
+    settings: Res<Settings>,
+    time: Res<Time<Real>>,
+) {
+    let alpha = if settings.reduce_motion {
+        KILLER_HIGHLIGHT_COLOR.alpha()
+    } else {
+        let phase = time.elapsed_secs() * KILLER_HIGHLIGHT_FREQUENCY * std::f32::consts::TAU;
+        KILLER_HIGHLIGHT_COLOR.alpha() * (0.5 + 0.5 * phase.sin())
+    };
+
+    for mut sprite in &mut highlight_query {
+        sprite.color.set_alpha(alpha);
+    }
+}
+
+/// Returns a readable name for the object that killed the player, such as
+/// "Bouncing ball".
+fn killer_name(object_type: ObjectType) -> String {
+    let mut name = String::new();
+    for c in object_type.to_string().chars() {
+        if name.is_empty() {
+            name.push(c);
+        } else if c.is_ascii_uppercase() || c.is_ascii_digit() {
+            name.push(' ');
+            name.push(c.to_ascii_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Lets the player choose from the buttons on the game over screen, using the
+/// keyboard, a gamepad, the mouse or touch.
+#[expect(clippy::too_many_arguments)]
+pub fn on_game_over_input(
+    mut commands: Commands,
+    game_over_query: Query<&Node, With<GameOver>>,
+    button_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    gamepads: Query<&Gamepad>,
+    mut game_state: ResMut<GameState>,
+    mut selection: ResMut<GameOverSelection>,
+    editor: Res<EditorState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<MenuState>,
+) {
+    let is_shown = game_over_query
+        .get_single()
+        .is_ok_and(|node| node.display == Display::Flex);
+    if !is_shown || editor.is_open || menu_state.is_open() {
+        return;
+    }
+
+    let available: Vec<GameOverButton> = GameOverButton::ALL
+        .into_iter()
+        .filter(|button| button.is_available(&game_state))
+        .collect();
+    let move_selection = |selection: &mut GameOverSelection, delta: isize| {
+        let index = available
+            .iter()
+            .position(|button| *button == selection.0)
+            .unwrap_or_default() as isize;
+        let len = available.len() as isize;
+        selection.0 = available[(index + delta).rem_euclid(len) as usize];
+    };
+
+    let mut is_pressed = false;
+
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::ArrowLeft => move_selection(&mut selection, -1),
+            KeyCode::ArrowRight => move_selection(&mut selection, 1),
+            KeyCode::Enter | KeyCode::Space => is_pressed = true,
+            _ => {}
+        }
+    }
+
+    for gamepad in &gamepads {
+        if gamepad.just_pressed(GamepadButton::DPadLeft) {
+            move_selection(&mut selection, -1);
+        } else if gamepad.just_pressed(GamepadButton::DPadRight) {
+            move_selection(&mut selection, 1);
+        } else if gamepad.just_pressed(GamepadButton::South) {
+            is_pressed = true;
+        }
+    }
+
+    for (interaction, button) in &button_query {
+        match *interaction {
+            Interaction::Pressed => {
+                selection.0 = *button;
+                is_pressed = true;
+            }
+            Interaction::Hovered => selection.0 = *button,
+            Interaction::None => {}
+        }
+    }
+
+    if !is_pressed {
+        return;
+    }
+
+    match selection.0 {
+        GameOverButton::Retry => commands.trigger(LoadRelativeLevel(0)),
+        // The latest snapshot is from before the move the player died in.
+        GameOverButton::Undo => commands.trigger(RestoreSnapshot::History),
+        GameOverButton::BackToHub => commands.trigger(LoadLevel(game_state.current_hub)),
+        GameOverButton::SkipLevel => {
+            game_state.skip_current_level();
+            commands.trigger(LoadLevel(game_state.current_hub));
+        }
+    }
+}
+
+pub fn render_game_over_buttons(
+    mut button_query: Query<(&GameOverButton, &mut BackgroundColor)>,
+    selection: Res<GameOverSelection>,
+) {
+    if !selection.is_changed() {
+        return;
+    }
+
+    for (button, mut background_color) in &mut button_query {
+        *background_color = if *button == selection.0 { RED } else { BLUE }.into();
     }
 }
@@ -0,0 +1,77 @@
+use bevy::prelude::*;
+
+use crate::{
+    game_object::{LevelObject, Massive, ObjectType, Position, Pushable},
+    game_state::GameState,
+    levels::{InitialPositionAndMetadata, Level, Levels},
+    settings::Settings,
+    SpawnedLevel,
+};
+
+pub struct GravesPlugin;
+
+impl Plugin for GravesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, persist_graves);
+    }
+}
+
+/// Keeps the graves the player leaves behind for the rest of the session,
+/// when they have enabled it, and lets them be pushed around like blocks.
+///
+/// New graves are merged into the in-memory snapshot of the level, so they
+/// are still there when the player retries the level or comes back to it.
+fn persist_graves(
+    mut commands: Commands,
+    grave_query: Query<(Entity, &ObjectType, &Position, Has<LevelObject>), Added<ObjectType>>,
+    mut levels: ResMut<Levels>,
+    mut spawned_level: ResMut<SpawnedLevel>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+) {
+    if !settings.persistent_graves {
+        return;
+    }
+
+    for (entity, object_type, position, is_level_object) in &grave_query {
+        if *object_type != ObjectType::Grave {
+            continue;
+        }
+
+        commands.entity(entity).remove::<Massive>().insert(Pushable);
+
+        // Graves that are restored from a snapshot may already be part of
+        // the level.
+        let is_known = spawned_level
+            .objects
+            .iter()
+            .any(|(object_type, initial_position)| {
+                *object_type == ObjectType::Grave && initial_position.position == *position
+            });
+        if is_level_object
+            || is_known
+            || game_state.is_playing_demo
+            || spawned_level.level != game_state.current_level
+        {
+            continue;
+        }
+
+        let Ok(mut level) = Level::load(&spawned_level.contents) else {
+            continue;
+        };
+        level
+            .objects
+            .entry(ObjectType::Grave)
+            .or_default()
+            .push(position.into());
+        spawned_level.contents = level.save();
+        levels.insert_current(spawned_level.level, spawned_level.contents.clone());
+
+        let index = spawned_level.objects.len();
+        spawned_level.objects.push((
+            ObjectType::Grave,
+            InitialPositionAndMetadata::from(position),
+        ));
+        commands.entity(entity).insert(LevelObject(index));
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*, fonts::Fonts, game_state::GameState, levels::Levels, setup,
+    timers::LevelWatchTimer, utils::find_level_path, LoadLevel,
+};
+
+#[derive(Component)]
+pub struct ReloadPrompt;
+
+/// Keeps track of the file of the current level, so we can detect when it
+/// gets changed on disk.
+///
+/// Rather than using a file watcher, the modification time of the file is
+/// polled every [LevelWatchTimer] tick. The watched file is the user's own
+/// version of the level if there is one, and the bundled level otherwise.
+#[derive(Default, Resource)]
+pub struct LevelWatcher {
+    level: Option<u16>,
+
+    /// Path of the watched file, which is resolved when the level is loaded,
+    /// and again when the levels change.
+    path: PathBuf,
+
+    modified: Option<SystemTime>,
+
+    /// Contents read from disk that differ from the stored level, waiting for
+    /// the player to confirm the reload.
+    pending_contents: Option<String>,
+}
+
+impl LevelWatcher {
+    pub fn has_pending_reload(&self) -> bool {
+        self.pending_contents.is_some()
+    }
+}
+
+pub struct HotReloadPlugin;
+
+impl Plugin for HotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_reload_prompt.after(setup))
+            .init_resource::<LevelWatcher>()
+            .init_resource::<LevelWatchTimer>()
+            .add_systems(Update, (on_reload_prompt_input, watch_level_file))
+            .add_systems(Update, render_reload_prompt.after(watch_level_file));
+    }
+}
+
+fn setup_reload_prompt(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            ReloadPrompt,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                width: Val::Px(400.),
+                height: Val::Px(60.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::horizontal(Val::Auto).with_top(Val::Px(20.)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new("Level changed on disk\nPress F6 to reload"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(18.),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    margin: UiRect::all(Val::Auto),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn watch_level_file(
+    mut watcher: ResMut<LevelWatcher>,
+    mut timer: ResMut<LevelWatchTimer>,
+    game_state: Res<GameState>,
+    levels: Res<Levels>,
+    time: Res<Time>,
+) {
+    let level = game_state.current_level;
+    if watcher.level != Some(level) {
+        // Only changes that happen while the level is loaded are of interest.
+        let path = find_level_path(level);
+        *watcher = LevelWatcher {
+            level: Some(level),
+            modified: get_modified_time(&path),
+            path,
+            pending_contents: None,
+        };
+        return;
+    }
+
+    // Saving from the editor may create the user's own version of the level,
+    // which is watched from then on.
+    if levels.is_changed() {
+        let path = find_level_path(level);
+        if path != watcher.path {
+            watcher.modified = get_modified_time(&path);
+            watcher.path = path;
+        }
+    }
+
+    timer.tick(time.delta());
+    if !timer.just_finished() {
+        return;
+    }
+
+    let modified = get_modified_time(&watcher.path);
+    if modified.is_none() || modified == watcher.modified {
+        return;
+    }
+
+    watcher.modified = modified;
+
+    match fs::read_to_string(&watcher.path) {
+        // Saving from the editor also modifies the file, but the stored level
+        // will already be up-to-date in that case.
+        Ok(contents) if levels.get_stored(level) != Some(contents.as_str()) => {
+            watcher.pending_contents = Some(contents);
+        }
+        Ok(_) => {}
+        Err(error) => error!("Could not read changed level: {error}"),
+    }
+}
+
+fn on_reload_prompt_input(
+    mut commands: Commands,
+    mut watcher: ResMut<LevelWatcher>,
+    mut levels: ResMut<Levels>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if !watcher.has_pending_reload() || !keys.just_pressed(KeyCode::F6) {
+        return;
+    }
+
+    let (Some(level), Some(contents)) = (watcher.level, watcher.pending_contents.take()) else {
+        return;
+    };
+
+    levels.insert_stored(level, contents);
+    commands.trigger(LoadLevel(level));
+}
+
+fn render_reload_prompt(
+    mut prompt_query: Query<&mut Node, With<ReloadPrompt>>,
+    watcher: Res<LevelWatcher>,
+) {
+    if !watcher.is_changed() {
+        return;
+    }
+
+    for mut node in &mut prompt_query {
+        node.display = if watcher.has_pending_reload() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+fn get_modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,164 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*, direction_for_key, editor::EditorState, fonts::Fonts, on_game_event,
+    settings::Settings, setup, GameEvent,
+};
+
+/// How long a move that wasn't made with the keyboard stays highlighted,
+/// since there is no key being held for it.
+const FLASH_DURATION: Duration = Duration::from_millis(150);
+
+const KEY_SIZE: f32 = 36.;
+
+/// Direction keys of the input display, by row.
+const KEY_ROWS: [&[((i16, i16), &str)]; 2] = [
+    &[((0, -1), "U")],
+    &[((-1, 0), "L"), ((0, 1), "D"), ((1, 0), "R")],
+];
+
+#[derive(Component)]
+struct InputDisplay;
+
+#[derive(Component)]
+struct InputDisplayKey((i16, i16));
+
+#[derive(Component)]
+struct InputDisplayPull;
+
+pub struct InputDisplayPlugin;
+
+impl Plugin for InputDisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_input_display.after(setup))
+            .add_systems(Update, render_input_display.after(on_game_event));
+    }
+}
+
+fn setup_input_display(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            InputDisplay,
+            GlobalZIndex(90),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.),
+                bottom: Val::Px(20.),
+                left: Val::Px(20.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            for row in KEY_ROWS {
+                cb.spawn(Node {
+                    column_gap: Val::Px(4.),
+                    ..default()
+                })
+                .with_children(|cb| {
+                    for (direction, label) in row {
+                        cb.spawn((
+                            InputDisplayKey(*direction),
+                            BackgroundColor(NORMAL_GRAY),
+                            BorderRadius::all(Val::Px(4.)),
+                            Node {
+                                width: Val::Px(KEY_SIZE),
+                                height: Val::Px(KEY_SIZE),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|cb| {
+                            cb.spawn((
+                                Text::new(*label),
+                                TextColor(WHITE),
+                                TextFont::from_font(fonts.poppins_light.clone())
+                                    .with_font_size(18.),
+                                Node {
+                                    margin: UiRect::all(Val::Auto),
+                                    ..default()
+                                },
+                            ));
+                        });
+                    }
+                });
+            }
+
+            cb.spawn((
+                InputDisplayPull,
+                Text::new("Pull"),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+/// Highlights the direction keys that are held, using the same key mapping
+/// as the player's movement. Moves from other sources, such as touch
+/// gestures, are highlighted briefly.
+#[expect(clippy::too_many_arguments)]
+fn render_input_display(
+    mut display_query: Query<&mut Node, With<InputDisplay>>,
+    mut key_query: Query<(&InputDisplayKey, &mut BackgroundColor)>,
+    mut pull_query: Query<&mut TextColor, With<InputDisplayPull>>,
+    mut game_events: EventReader<GameEvent>,
+    mut flashes: Local<Vec<((i16, i16), Timer)>>,
+    editor_state: Res<EditorState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+) {
+    let Ok(mut display_node) = display_query.get_single_mut() else {
+        return;
+    };
+
+    let is_shown = settings.input_display && !editor_state.is_open;
+    let display = if is_shown {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if display_node.display != display {
+        display_node.display = display;
+    }
+
+    if !is_shown {
+        game_events.clear();
+        flashes.clear();
+        return;
+    }
+
+    for event in game_events.read() {
+        let (GameEvent::MovePlayer(dx, dy) | GameEvent::PullPlayer(dx, dy)) = event;
+        flashes.push(((*dx, *dy), Timer::new(FLASH_DURATION, TimerMode::Once)));
+    }
+    for (_, timer) in flashes.iter_mut() {
+        timer.tick(time.delta());
+    }
+    flashes.retain(|(_, timer)| !timer.finished());
+
+    for (key, mut background_color) in &mut key_query {
+        let InputDisplayKey(direction) = key;
+        let is_pressed = keys
+            .get_pressed()
+            .any(|key| direction_for_key(*key) == Some(*direction))
+            || flashes
+                .iter()
+                .any(|(flashed_direction, _)| flashed_direction == direction);
+        let color = if is_pressed { RED } else { NORMAL_GRAY };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+
+    let is_pulling = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    for mut text_color in &mut pull_query {
+        let color = if is_pulling { WHITE } else { LIGHT_GRAY };
+        if text_color.0 != color {
+            text_color.0 = color;
+        }
+    }
+}
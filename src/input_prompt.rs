@@ -0,0 +1,104 @@
+use bevy::prelude::*;
+
+/// USB vendor ID of Sony, used to recognize PlayStation controllers.
+const SONY_VENDOR_ID: u16 = 0x054c;
+
+/// Placeholder in the template of an [InputPrompt] for the action that
+/// confirms.
+const CONFIRM_PLACEHOLDER: &str = "{confirm}";
+
+/// The input device the player has used most recently.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub enum InputDevice {
+    #[default]
+    Keyboard,
+    Xbox,
+    PlayStation,
+    Touch,
+}
+
+impl InputDevice {
+    /// Returns how to confirm with this device, phrased to start a sentence.
+    fn confirm_prompt(self) -> &'static str {
+        match self {
+            Self::Keyboard => "Press Enter",
+            Self::Xbox => "Press A",
+            Self::PlayStation => "Press Cross",
+            Self::Touch => "Tap",
+        }
+    }
+}
+
+/// Text that refers to an input, and updates along with the input device
+/// the player is using.
+///
+/// The template gets rendered into the [Text] of the same entity, with
+/// `{confirm}` replaced by how to confirm using the current device.
+#[derive(Component)]
+#[require(Text)]
+pub struct InputPrompt {
+    pub template: String,
+}
+
+impl InputPrompt {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    fn render(&self, device: InputDevice) -> String {
+        self.template
+            .replace(CONFIRM_PLACEHOLDER, device.confirm_prompt())
+    }
+}
+
+pub struct InputPromptPlugin;
+
+impl Plugin for InputPromptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InputDevice>()
+            .add_systems(Update, (detect_input_device, update_input_prompts).chain());
+    }
+}
+
+fn detect_input_device(
+    mut device: ResMut<InputDevice>,
+    gamepads: Query<&Gamepad>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+) {
+    let used_gamepad = gamepads
+        .iter()
+        .find(|gamepad| gamepad.get_just_pressed().next().is_some());
+
+    let used_device = if let Some(gamepad) = used_gamepad {
+        if gamepad.vendor_id() == Some(SONY_VENDOR_ID) {
+            InputDevice::PlayStation
+        } else {
+            InputDevice::Xbox
+        }
+    } else if touches.any_just_pressed() {
+        InputDevice::Touch
+    } else if keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+    {
+        InputDevice::Keyboard
+    } else {
+        return;
+    };
+
+    device.set_if_neq(used_device);
+}
+
+fn update_input_prompts(
+    mut prompt_query: Query<(Ref<InputPrompt>, &mut Text)>,
+    device: Res<InputDevice>,
+) {
+    for (prompt, mut text) in &mut prompt_query {
+        if prompt.is_changed() || device.is_changed() {
+            text.0 = prompt.render(*device);
+        }
+    }
+}
@@ -0,0 +1,358 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    credits::CreditsState,
+    cutscene::CutsceneState,
+    dialogue::DialogueState,
+    editor::EditorState,
+    fonts::Fonts,
+    game_object::{
+        Bomb, Explosive, GameObjectAssets, Key, Massive, ObjectType, Openable, Player, Position,
+        Pushable, TileIndex, Weight,
+    },
+    levels::KeyMode,
+    menu::MenuState,
+    on_game_event, setup, ExitState, GameEvent, RestartLevel, SpawnObject,
+};
+
+/// Items the player is carrying in the current level.
+#[derive(Clone, Copy, Default, Resource)]
+pub struct Inventory {
+    pub keys: u16,
+    pub bombs: u16,
+}
+
+impl Inventory {
+    fn is_empty(&self) -> bool {
+        self.keys == 0 && self.bombs == 0
+    }
+}
+
+/// Items from the inventory that the player can use by themselves, rather
+/// than by bumping into something.
+#[derive(Clone, Copy, Debug)]
+pub enum Item {
+    Bomb,
+}
+
+/// Uses one of the given item on the tile of the player.
+#[derive(Event)]
+pub struct UseItem(pub Item);
+
+#[derive(Component)]
+struct InventoryBar;
+
+#[derive(Component)]
+struct KeySlot;
+
+#[derive(Component)]
+struct KeyCount;
+
+/// On-screen button for placing a bomb, so it can be used on touch screens.
+#[derive(Component)]
+struct BombSlot;
+
+#[derive(Component)]
+struct BombCount;
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inventory>()
+            .add_systems(Startup, setup_inventory_bar.after(setup))
+            .add_observer(clear_inventory)
+            .add_observer(use_item)
+            .add_systems(
+                Update,
+                (
+                    apply_key_mode,
+                    pick_up_bombs,
+                    pick_up_keys,
+                    use_carried_keys.before(on_game_event),
+                    on_item_input,
+                ),
+            )
+            .add_systems(Update, render_inventory_bar);
+    }
+}
+
+fn setup_inventory_bar(mut commands: Commands, assets: Res<GameObjectAssets>, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            InventoryBar,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.)),
+                padding: UiRect::axes(Val::Px(10.), Val::Px(4.)),
+                column_gap: Val::Px(16.),
+                top: Val::Px(20.),
+                left: Val::Px(20.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((KeySlot, slot_node())).with_children(|cb| {
+                cb.spawn((assets.image_node(assets.key, 0), icon_node()));
+                cb.spawn((
+                    KeyCount,
+                    Text::default(),
+                    TextColor(WHITE),
+                    TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                ));
+            });
+            cb.spawn((BombSlot, Button, slot_node()))
+                .with_children(|cb| {
+                    cb.spawn((assets.image_node(assets.bomb, 0), icon_node()));
+                    cb.spawn((
+                        BombCount,
+                        Text::default(),
+                        TextColor(WHITE),
+                        TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                    ));
+                });
+        });
+}
+
+fn slot_node() -> Node {
+    Node {
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(6.),
+        ..default()
+    }
+}
+
+fn icon_node() -> Node {
+    Node {
+        width: Val::Px(32.),
+        height: Val::Px(32.),
+        ..default()
+    }
+}
+
+/// Empties the inventory when the level is restarted.
+///
+/// Loading a level sets the inventory from the level itself, since levels
+/// that were saved in progress remember the items being carried.
+fn clear_inventory(_trigger: Trigger<RestartLevel>, mut inventory: ResMut<Inventory>) {
+    *inventory = Inventory::default();
+}
+
+/// Keys that are carried cannot be pushed, so the player can walk onto them.
+fn apply_key_mode(
+    mut commands: Commands,
+    key_query: Query<Entity, Added<Key>>,
+    key_mode: Res<KeyMode>,
+) {
+    if *key_mode != KeyMode::Carry {
+        return;
+    }
+
+    for key in &key_query {
+        commands.entity(key).remove::<(Pushable, Weight)>();
+    }
+}
+
+fn pick_up_bombs(
+    mut commands: Commands,
+    player_query: Query<Ref<Position>, With<Player>>,
+    bomb_query: Query<Entity, With<Bomb>>,
+    mut inventory: ResMut<Inventory>,
+    tile_index: Res<TileIndex>,
+) {
+    for player_position in &player_query {
+        if player_position.is_added() || !player_position.is_changed() {
+            continue;
+        }
+
+        for bomb in bomb_query.iter_many(tile_index.get(*player_position)) {
+            commands.entity(bomb).despawn();
+            inventory.bombs += 1;
+        }
+    }
+}
+
+fn pick_up_keys(
+    mut commands: Commands,
+    player_query: Query<Ref<Position>, With<Player>>,
+    key_query: Query<Entity, With<Key>>,
+    mut inventory: ResMut<Inventory>,
+    key_mode: Res<KeyMode>,
+    tile_index: Res<TileIndex>,
+) {
+    if *key_mode != KeyMode::Carry {
+        return;
+    }
+
+    for player_position in &player_query {
+        if player_position.is_added() || !player_position.is_changed() {
+            continue;
+        }
+
+        for key in key_query.iter_many(tile_index.get(*player_position)) {
+            commands.entity(key).despawn();
+            inventory.keys += 1;
+        }
+    }
+}
+
+/// Opens a locked door with a carried key when the player bumps into it.
+fn use_carried_keys(
+    mut commands: Commands,
+    mut game_events: EventReader<GameEvent>,
+    mut door_query: Query<(Entity, &ObjectType, &Openable, &mut Sprite), With<Massive>>,
+    player_query: Query<&Position, With<Player>>,
+    mut inventory: ResMut<Inventory>,
+    assets: Res<GameObjectAssets>,
+    tile_index: Res<TileIndex>,
+) {
+    for event in game_events.read() {
+        let (GameEvent::MovePlayer(dx, dy) | GameEvent::PullPlayer(dx, dy)) = event;
+        let Ok(position) = player_query.get_single() else {
+            continue;
+        };
+
+        if inventory.keys == 0 {
+            continue;
+        }
+
+        let target = Position {
+            x: position.x + dx,
+            y: position.y + dy,
+        };
+        let mut doors = door_query.iter_many_mut(tile_index.get(target));
+        while let Some((door, object_type, openable, mut sprite)) = doors.fetch_next() {
+            if matches!(openable, Openable::Key) {
+                commands.entity(door).remove::<(Massive, Openable)>();
+                assets
+                    .for_object_type(*object_type)
+                    .set_frame(&mut sprite, 1);
+                inventory.keys -= 1;
+                break;
+            }
+        }
+    }
+}
+
+/// Places a bomb when B or the North button of a gamepad is pressed, or when
+/// the bomb in the inventory bar is clicked.
+#[expect(clippy::too_many_arguments)]
+fn on_item_input(
+    mut commands: Commands,
+    bomb_slot_query: Query<&Interaction, (With<BombSlot>, Changed<Interaction>)>,
+    gamepads: Query<&Gamepad>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    dialogue_state: Res<DialogueState>,
+    editor_state: Res<EditorState>,
+    exit_state: Res<ExitState>,
+    menu_state: Res<MenuState>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if credits_state.is_playing()
+        || cutscene_state.is_playing()
+        || dialogue_state.is_open()
+        || editor_state.is_open
+        || menu_state.is_open()
+        || exit_state.next_level.is_some()
+    {
+        return;
+    }
+
+    let is_bomb_used = keys.just_pressed(KeyCode::KeyB)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::North))
+        || bomb_slot_query
+            .iter()
+            .any(|interaction| *interaction == Interaction::Pressed);
+    if is_bomb_used {
+        commands.trigger(UseItem(Item::Bomb));
+    }
+}
+
+/// Places the item on the tile of the player, if the player is carrying one.
+///
+/// A bomb cannot be placed where there is already something explosive.
+fn use_item(
+    trigger: Trigger<UseItem>,
+    mut commands: Commands,
+    player_query: Query<&Position, With<Player>>,
+    explosive_query: Query<(), With<Explosive>>,
+    mut inventory: ResMut<Inventory>,
+    tile_index: Res<TileIndex>,
+) {
+    let Ok(position) = player_query.get_single() else {
+        return;
+    };
+
+    match trigger.event().0 {
+        Item::Bomb => {
+            if inventory.bombs == 0
+                || explosive_query
+                    .iter_many(tile_index.get(*position))
+                    .next()
+                    .is_some()
+            {
+                return;
+            }
+
+            commands.trigger(SpawnObject {
+                object_type: ObjectType::Mine,
+                position: position.into(),
+            });
+            inventory.bombs -= 1;
+        }
+    }
+}
+
+#[expect(clippy::type_complexity)]
+fn render_inventory_bar(
+    mut bar_query: Query<&mut Node, With<InventoryBar>>,
+    mut key_slot_query: Query<&mut Node, (With<KeySlot>, Without<InventoryBar>)>,
+    mut bomb_slot_query: Query<
+        &mut Node,
+        (With<BombSlot>, Without<InventoryBar>, Without<KeySlot>),
+    >,
+    mut key_count_query: Query<&mut Text, (With<KeyCount>, Without<BombCount>)>,
+    mut bomb_count_query: Query<&mut Text, (With<BombCount>, Without<KeyCount>)>,
+    inventory: Res<Inventory>,
+) {
+    if !inventory.is_changed() {
+        return;
+    }
+
+    for mut node in &mut bar_query {
+        node.display = display_if(!inventory.is_empty());
+    }
+
+    for mut node in &mut key_slot_query {
+        node.display = display_if(inventory.keys > 0);
+    }
+
+    for mut node in &mut bomb_slot_query {
+        node.display = display_if(inventory.bombs > 0);
+    }
+
+    for mut text in &mut key_count_query {
+        text.0 = format!("x {}", inventory.keys);
+    }
+
+    for mut text in &mut bomb_count_query {
+        text.0 = format!("x {}", inventory.bombs);
+    }
+}
+
+fn display_if(is_visible: bool) -> Display {
+    if is_visible {
+        Display::Flex
+    } else {
+        Display::None
+    }
+}
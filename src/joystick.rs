@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*, credits::CreditsState, cutscene::CutsceneState, dialogue::DialogueState,
+    editor::EditorState, menu::MenuState, on_game_event, timers::MovementRepeatTimer, GameEvent,
+};
+
+const BASE_SIZE: f32 = 120.;
+const KNOB_SIZE: f32 = 50.;
+
+/// Distance the touch needs to be dragged from where it started before the
+/// player starts moving.
+const DEAD_ZONE: f32 = 20.;
+
+/// Floating joystick for moving the player on touch screens.
+///
+/// The joystick gets anchored wherever a touch starts, and the direction in
+/// which the touch is dragged is the direction the player moves in, for as
+/// long as the touch is held.
+#[derive(Default, Resource)]
+struct Joystick {
+    touch: Option<JoystickTouch>,
+}
+
+struct JoystickTouch {
+    id: u64,
+    anchor: Vec2,
+    delta: Vec2,
+}
+
+impl Joystick {
+    fn direction(&self) -> Option<(i16, i16)> {
+        self.touch
+            .as_ref()
+            .and_then(|touch| directions_from_deltas(touch.delta))
+    }
+}
+
+#[derive(Component)]
+struct JoystickBase;
+
+#[derive(Component)]
+struct JoystickKnob;
+
+pub struct JoystickPlugin;
+
+impl Plugin for JoystickPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_joystick)
+            .init_resource::<Joystick>()
+            .add_systems(
+                Update,
+                (on_joystick_input, move_with_joystick)
+                    .chain()
+                    .before(on_game_event),
+            )
+            .add_systems(Update, render_joystick.after(on_joystick_input));
+    }
+}
+
+fn setup_joystick(mut commands: Commands) {
+    commands
+        .spawn((
+            JoystickBase,
+            BackgroundColor(WHITE.with_alpha(0.2)),
+            BorderColor(WHITE.with_alpha(0.5)),
+            BorderRadius::MAX,
+            GlobalZIndex(90),
+            Node {
+                display: Display::None,
+                width: Val::Px(BASE_SIZE),
+                height: Val::Px(BASE_SIZE),
+                border: UiRect::all(Val::Px(2.)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                JoystickKnob,
+                BackgroundColor(WHITE.with_alpha(0.6)),
+                BorderRadius::MAX,
+                Node {
+                    width: Val::Px(KNOB_SIZE),
+                    height: Val::Px(KNOB_SIZE),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Returns the direction to move in for a touch that has been dragged by the
+/// given delta, or `None` if it's still within the dead zone.
+///
+/// Only the dominant axis is used, since the player cannot move diagonally.
+pub fn directions_from_deltas(delta: Vec2) -> Option<(i16, i16)> {
+    if delta.length() < DEAD_ZONE {
+        None
+    } else if delta.x.abs() > delta.y.abs() {
+        Some((delta.x.signum() as i16, 0))
+    } else {
+        Some((0, delta.y.signum() as i16))
+    }
+}
+
+fn on_joystick_input(
+    mut joystick: ResMut<Joystick>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    dialogue_state: Res<DialogueState>,
+    editor_state: Res<EditorState>,
+    menu_state: Res<MenuState>,
+    touches: Res<Touches>,
+) {
+    if credits_state.is_playing()
+        || cutscene_state.is_playing()
+        || dialogue_state.is_open()
+        || editor_state.is_open
+        || menu_state.is_open()
+    {
+        if joystick.touch.is_some() {
+            joystick.touch = None;
+        }
+        return;
+    }
+
+    let Some(touch) = joystick.touch.as_mut() else {
+        if let Some(touch) = touches.iter_just_pressed().next() {
+            joystick.touch = Some(JoystickTouch {
+                id: touch.id(),
+                anchor: touch.position(),
+                delta: Vec2::ZERO,
+            });
+        }
+        return;
+    };
+
+    match touches.get_pressed(touch.id) {
+        Some(pressed) => {
+            let delta = pressed.position() - touch.anchor;
+            if delta != touch.delta {
+                touch.delta = delta;
+            }
+        }
+        None => joystick.touch = None,
+    }
+}
+
+/// Moves the player in the direction of the joystick, and keeps repeating
+/// the move while the joystick is held.
+fn move_with_joystick(
+    mut game_events: EventWriter<GameEvent>,
+    mut timer: ResMut<MovementRepeatTimer>,
+    mut previous_direction: Local<Option<(i16, i16)>>,
+    joystick: Res<Joystick>,
+    time: Res<Time>,
+) {
+    let direction = joystick.direction();
+    if direction != *previous_direction {
+        *previous_direction = direction;
+        timer.restart();
+        if let Some((dx, dy)) = direction {
+            game_events.send(GameEvent::MovePlayer(dx, dy));
+        }
+        return;
+    }
+
+    let Some((dx, dy)) = direction else {
+        return;
+    };
+
+    if timer.tick(time.delta()) {
+        game_events.send(GameEvent::MovePlayer(dx, dy));
+    }
+}
+
+fn render_joystick(
+    mut base_query: Query<&mut Node, (With<JoystickBase>, Without<JoystickKnob>)>,
+    mut knob_query: Query<&mut Node, (With<JoystickKnob>, Without<JoystickBase>)>,
+    joystick: Res<Joystick>,
+    ui_scale: Res<UiScale>,
+) {
+    if !joystick.is_changed() {
+        return;
+    }
+
+    let Ok(mut base_node) = base_query.get_single_mut() else {
+        return;
+    };
+
+    let Some(touch) = joystick.touch.as_ref() else {
+        base_node.display = Display::None;
+        return;
+    };
+
+    // Touches are in window coordinates, while the joystick is positioned in
+    // UI pixels.
+    let anchor = touch.anchor / ui_scale.0;
+    base_node.display = Display::Flex;
+    base_node.left = Val::Px(anchor.x - 0.5 * BASE_SIZE);
+    base_node.top = Val::Px(anchor.y - 0.5 * BASE_SIZE);
+
+    // Keep the knob within the base, even if the touch is dragged further.
+    let offset = (touch.delta / ui_scale.0).clamp_length_max(0.5 * (BASE_SIZE - KNOB_SIZE));
+    for mut knob_node in &mut knob_query {
+        knob_node.left = Val::Px(0.5 * (BASE_SIZE - KNOB_SIZE) + offset.x);
+        knob_node.top = Val::Px(0.5 * (BASE_SIZE - KNOB_SIZE) + offset.y);
+    }
+}
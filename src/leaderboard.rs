@@ -0,0 +1,284 @@
+use std::{
+    fs,
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use anyhow::{bail, Context};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_state::GameState,
+    level_complete::ShowStandings,
+    levels::LevelChecksum,
+    settings::Settings,
+    utils::{ensure_chunky_dir, format_duration},
+};
+
+/// How often submissions that could not be sent are retried.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How long to wait for the server before giving up on a submission.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of top entries that are shown on the completion screen.
+const NUM_TOP_ENTRIES: usize = 3;
+
+/// Submits the best time and moves of the given level to the leaderboard.
+///
+/// Does nothing unless a leaderboard URL is configured in the settings.
+#[derive(Event)]
+pub struct SubmitScore(pub u16);
+
+/// Best time and moves for a level, as submitted to the server.
+///
+/// Submissions are sent as JSON using `POST <url>/levels/<level>/scores`.
+#[derive(Clone, Deserialize, Serialize)]
+struct Submission {
+    level: u16,
+    name: String,
+    best_time: f32,
+    best_moves: u32,
+
+    /// Checksum of the level the score was set on, so the server can keep
+    /// scores on modified levels apart.
+    #[serde(default)]
+    checksum: String,
+    #[serde(default)]
+    modified: bool,
+}
+
+/// Response of the server for a submission.
+#[derive(Deserialize)]
+struct Standing {
+    rank: u32,
+    num_entries: u32,
+    top: Vec<LeaderboardEntry>,
+}
+
+#[derive(Deserialize)]
+struct LeaderboardEntry {
+    name: String,
+    best_time: f32,
+    best_moves: u32,
+}
+
+/// Connection to the worker thread that talks to the leaderboard server.
+///
+/// Submissions that cannot be sent, for instance because there is no network,
+/// are queued on disk and retried periodically.
+#[derive(Resource)]
+struct LeaderboardClient {
+    submissions: Sender<Submission>,
+    standings: Mutex<Receiver<(u16, Standing)>>,
+}
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SubmitScore>()
+            .add_observer(on_submit_score)
+            .add_systems(Startup, start_leaderboard_client)
+            .add_systems(Update, show_standings);
+    }
+}
+
+fn queue_path() -> PathBuf {
+    ensure_chunky_dir().join("leaderboard_queue.json")
+}
+
+fn start_leaderboard_client(mut commands: Commands, settings: Res<Settings>) {
+    let Some(url) = settings.leaderboard_url.clone() else {
+        return;
+    };
+
+    let (submission_sender, submission_receiver) = mpsc::channel();
+    let (standing_sender, standing_receiver) = mpsc::channel();
+    thread::spawn(move || run_worker(&url, submission_receiver, standing_sender));
+
+    commands.insert_resource(LeaderboardClient {
+        submissions: submission_sender,
+        standings: Mutex::new(standing_receiver),
+    });
+}
+
+fn on_submit_score(
+    trigger: Trigger<SubmitScore>,
+    client: Option<Res<LeaderboardClient>>,
+    checksum: Res<LevelChecksum>,
+    game_state: Res<GameState>,
+    settings: Res<Settings>,
+) {
+    let SubmitScore(level) = trigger.event();
+    let Some(client) = client else {
+        return;
+    };
+
+    let Some(stats) = game_state.level_stats.get(level) else {
+        return;
+    };
+    let (Some(best_time), Some(best_moves)) = (stats.best_time, stats.best_moves) else {
+        return;
+    };
+
+    let submission = Submission {
+        level: *level,
+        name: settings.player_name.clone(),
+        best_time,
+        best_moves,
+        checksum: checksum.to_string(),
+        modified: checksum.is_modified(*level),
+    };
+    if client.submissions.send(submission).is_err() {
+        error!("Can't submit to leaderboard: The connection was closed");
+    }
+}
+
+fn show_standings(mut commands: Commands, client: Option<Res<LeaderboardClient>>) {
+    let Some(client) = client else {
+        return;
+    };
+
+    let Ok(standings) = client.standings.lock() else {
+        return;
+    };
+
+    for (level, standing) in standings.try_iter() {
+        let mut summary = format!("Rank {} of {}", standing.rank, standing.num_entries);
+        for (index, entry) in standing.top.iter().take(NUM_TOP_ENTRIES).enumerate() {
+            summary.push_str(&format!(
+                "\n{}. {} - {}, {} moves",
+                index + 1,
+                entry.name,
+                format_duration(entry.best_time),
+                entry.best_moves
+            ));
+        }
+        commands.trigger(ShowStandings { level, summary });
+    }
+}
+
+/// Sends submissions to the server, and reports back the standings.
+///
+/// Submissions that were queued in an earlier session are sent right away.
+/// Only the latest submission for every level is kept in the queue, since it
+/// contains the best time and moves so far.
+fn run_worker(url: &str, submissions: Receiver<Submission>, standings: Sender<(u16, Standing)>) {
+    let mut queue = load_queue();
+
+    loop {
+        if !queue.is_empty() {
+            flush_queue(url, &mut queue, &standings);
+        }
+
+        match submissions.recv_timeout(RETRY_INTERVAL) {
+            Ok(submission) => {
+                queue.retain(|queued| queued.level != submission.level);
+                queue.push(submission);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+    }
+}
+
+/// Sends the queued submissions in order, until one of them fails, and saves
+/// the ones that are left.
+fn flush_queue(url: &str, queue: &mut Vec<Submission>, standings: &Sender<(u16, Standing)>) {
+    while let Some(submission) = queue.first() {
+        match submit(url, submission) {
+            Ok(standing) => {
+                let _ = standings.send((submission.level, standing));
+                queue.remove(0);
+            }
+            Err(err) => {
+                error!("Can't submit to leaderboard: {err}");
+                break;
+            }
+        }
+    }
+
+    save_queue(queue);
+}
+
+fn load_queue() -> Vec<Submission> {
+    let path = queue_path();
+    if !path.exists() {
+        return Vec::new();
+    }
+
+    fs::read(&path)
+        .context("Can't read file")
+        .and_then(|json| serde_json::from_slice(&json).map_err(anyhow::Error::from))
+        .map_err(|err| error!("Can't load leaderboard queue: {err}"))
+        .unwrap_or_default()
+}
+
+fn save_queue(queue: &[Submission]) {
+    serde_json::to_vec(queue)
+        .map_err(anyhow::Error::from)
+        .and_then(|json| fs::write(queue_path(), json).context("Can't write file"))
+        .unwrap_or_else(|err| error!("Can't save leaderboard queue: {err}"));
+}
+
+/// Posts the submission to the server using a minimal HTTP/1.0 request.
+///
+/// Only plain `http://` URLs are supported.
+fn submit(url: &str, submission: &Submission) -> Result<Standing, anyhow::Error> {
+    let address = url
+        .strip_prefix("http://")
+        .context("Only http:// URLs are supported")?;
+    let (host, base_path) = address.split_once('/').unwrap_or((address, ""));
+    let base_path = base_path.trim_end_matches('/');
+    let path = if base_path.is_empty() {
+        format!("/levels/{}/scores", submission.level)
+    } else {
+        format!("/{base_path}/levels/{}/scores", submission.level)
+    };
+
+    let socket_address = if host.contains(':') {
+        host.to_socket_addrs()
+    } else {
+        (host, 80).to_socket_addrs()
+    }
+    .context("Can't resolve host")?
+    .next()
+    .context("Can't resolve host")?;
+
+    let mut stream =
+        TcpStream::connect_timeout(&socket_address, TIMEOUT).context("Can't connect")?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let body = serde_json::to_string(submission)?;
+    write!(
+        stream,
+        "POST {path} HTTP/1.0\r\nHost: {host}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+        body.len()
+    )
+    .context("Can't send request")?;
+
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .context("Can't read response")?;
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .context("Invalid response")?;
+    let status = head.split_whitespace().nth(1).context("Invalid response")?;
+    if !status.starts_with('2') {
+        bail!("Server responded with status {status}");
+    }
+
+    serde_json::from_str(body).context("Invalid response")
+}
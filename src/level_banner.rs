@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*, editor::EditorState, fonts::Fonts, game_state::GameState, setup,
+    timers::LevelBannerTimer, utils::format_duration, LoadLevel,
+};
+
+/// How long the banner takes to fade out at the end of its display time.
+const FADE_DURATION: f32 = 0.5;
+
+#[derive(Component)]
+pub struct LevelBanner;
+
+#[derive(Component)]
+struct LevelBannerTitle;
+
+#[derive(Component)]
+struct LevelBannerStats;
+
+pub struct LevelBannerPlugin;
+
+impl Plugin for LevelBannerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_level_banner.after(setup))
+            .init_resource::<LevelBannerTimer>()
+            .add_observer(show_level_banner)
+            .add_systems(Update, fade_level_banner);
+    }
+}
+
+fn setup_level_banner(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            LevelBanner,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Px(300.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::horizontal(Val::Auto).with_top(Val::Px(40.)),
+                padding: UiRect::all(Val::Px(10.)),
+                row_gap: Val::Px(4.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                LevelBannerTitle,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(36.),
+            ));
+            cb.spawn((
+                LevelBannerStats,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+            ));
+        });
+}
+
+fn show_level_banner(
+    trigger: Trigger<LoadLevel>,
+    mut banner_query: Query<&mut Node, With<LevelBanner>>,
+    mut title_query: Query<&mut Text, (With<LevelBannerTitle>, Without<LevelBannerStats>)>,
+    mut stats_query: Query<&mut Text, (With<LevelBannerStats>, Without<LevelBannerTitle>)>,
+    mut timer: ResMut<LevelBannerTimer>,
+    editor_state: Res<EditorState>,
+    game_state: Res<GameState>,
+) {
+    let LoadLevel(level) = trigger.event();
+    let Ok(mut banner_node) = banner_query.get_single_mut() else {
+        return;
+    };
+
+    if game_state.is_hub_level(*level) || editor_state.is_open {
+        banner_node.display = Display::None;
+        return;
+    }
+
+    for mut title in &mut title_query {
+        title.0 = format!("Level {level}");
+    }
+
+    let stats = game_state.level_stats.get(level);
+    let best_moves = stats.and_then(|stats| stats.best_moves);
+    let best_time = stats.and_then(|stats| stats.best_time);
+    for mut text in &mut stats_query {
+        text.0 = match (best_moves, best_time) {
+            (Some(moves), Some(time)) => {
+                format!("Best: {moves} moves, {}", format_duration(time))
+            }
+            _ => "Not finished yet".to_owned(),
+        };
+    }
+
+    banner_node.display = Display::Flex;
+    timer.reset();
+}
+
+fn fade_level_banner(
+    mut banner_query: Query<
+        (&mut Node, &mut BackgroundColor, &mut BorderColor, &Children),
+        With<LevelBanner>,
+    >,
+    mut text_query: Query<&mut TextColor>,
+    mut timer: ResMut<LevelBannerTimer>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    time: Res<Time>,
+) {
+    let Ok((mut node, mut background_color, mut border_color, children)) =
+        banner_query.get_single_mut()
+    else {
+        return;
+    };
+
+    if node.display == Display::None {
+        return;
+    }
+
+    // The first input after showing the banner skips straight to fading out.
+    let fade_start = timer.duration().as_secs_f32() - FADE_DURATION;
+    if timer.elapsed_secs() < fade_start
+        && (keys.get_just_pressed().next().is_some()
+            || mouse_buttons.get_just_pressed().next().is_some())
+    {
+        timer.set_elapsed(Duration::from_secs_f32(fade_start));
+    }
+
+    timer.tick(time.delta());
+    if timer.finished() {
+        node.display = Display::None;
+        return;
+    }
+
+    let alpha = (timer.remaining_secs() / FADE_DURATION).min(1.);
+    background_color.0 = GRAY_BACKGROUND.with_alpha(alpha);
+    border_color.0 = RED.with_alpha(alpha);
+    let mut texts = text_query.iter_many_mut(children);
+    while let Some(mut text_color) = texts.fetch_next() {
+        text_color.0 = WHITE.with_alpha(alpha);
+    }
+}
@@ -0,0 +1,461 @@
+use bevy::prelude::*;
+
+use crate::{
+    background::UpdateBackgroundTransform,
+    constants::*,
+    editor::EditorState,
+    fonts::Fonts,
+    game_object::behaviors::check_for_exit,
+    game_state::{GameState, LevelResult},
+    input_prompt::InputPrompt,
+    levels::Levels,
+    menu::MenuState,
+    setup,
+    toast::ShowToast,
+    utils::format_duration,
+    ExitState,
+};
+
+/// Triggered when the player has finished a level, to show the completion
+/// screen instead of going straight back to the hub.
+#[derive(Event)]
+pub struct ShowLevelComplete {
+    pub result: LevelResult,
+
+    /// The level the exit leads to, if it was a secret exit.
+    pub secret_level: Option<u16>,
+}
+
+/// Triggered when the leaderboard standings of a level come in, to show them
+/// on the completion screen.
+///
+/// Standings for a level whose completion screen is no longer shown, such as
+/// for scores that were queued in an earlier session, are shown as a toast.
+#[derive(Event)]
+pub struct ShowStandings {
+    pub level: u16,
+    pub summary: String,
+}
+
+/// The completion screen, if it is shown.
+#[derive(Default, Resource)]
+struct LevelCompleteState {
+    shown: Option<ShownLevelComplete>,
+    selection: LevelCompleteButton,
+}
+
+struct ShownLevelComplete {
+    level: u16,
+    next_level: Option<u16>,
+
+    /// The hub the player entered the level from.
+    hub: u16,
+
+    /// The lowest-numbered level the player hasn't finished yet, so they can
+    /// go there without walking across the hub.
+    next_unfinished_level: Option<u16>,
+
+    /// The leaderboard standings, once they've come in.
+    standings: Option<String>,
+}
+
+#[derive(Component)]
+pub struct LevelComplete;
+
+#[derive(Component)]
+struct LevelCompleteTitle;
+
+#[derive(Component)]
+struct LevelCompleteStats;
+
+#[derive(Component)]
+struct LevelCompleteAttempts;
+
+#[derive(Component)]
+struct LevelCompleteStandings;
+
+#[derive(Clone, Copy, Component, Default, Eq, PartialEq)]
+enum LevelCompleteButton {
+    #[default]
+    NextLevel,
+    NextUnfinished,
+    Replay,
+    BackToHub,
+}
+
+impl LevelCompleteButton {
+    const ALL: [Self; 4] = [
+        Self::NextLevel,
+        Self::NextUnfinished,
+        Self::Replay,
+        Self::BackToHub,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::NextLevel => "Next Level",
+            Self::NextUnfinished => "Next Unfinished",
+            Self::Replay => "Replay",
+            Self::BackToHub => "Back to Hub",
+        }
+    }
+
+    /// Returns whether the button is shown. There's no next level after the
+    /// last one, and no need to offer the next unfinished level if it's the
+    /// next level anyway.
+    fn is_available(self, shown: &ShownLevelComplete) -> bool {
+        match self {
+            Self::NextLevel => shown.next_level.is_some(),
+            Self::NextUnfinished => {
+                shown.next_unfinished_level.is_some()
+                    && shown.next_unfinished_level != shown.next_level
+            }
+            Self::Replay | Self::BackToHub => true,
+        }
+    }
+}
+
+pub struct LevelCompletePlugin;
+
+impl Plugin for LevelCompletePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelCompleteState>()
+            .add_observer(on_show_level_complete)
+            .add_observer(on_show_standings)
+            .add_systems(Startup, setup_level_complete.after(setup))
+            .add_systems(
+                Update,
+                // The keys that led the player into the exit shouldn't also
+                // act on the screen it opens.
+                (on_level_complete_input, render_level_complete)
+                    .chain()
+                    .before(check_for_exit),
+            );
+    }
+}
+
+fn setup_level_complete(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            LevelComplete,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Px(720.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::all(Val::Auto),
+                padding: UiRect::all(Val::Px(20.)),
+                row_gap: Val::Px(10.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                LevelCompleteTitle,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(32.),
+            ));
+            cb.spawn((
+                LevelCompleteStats,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            cb.spawn((
+                LevelCompleteAttempts,
+                Text::default(),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+            ));
+            cb.spawn((
+                LevelCompleteStandings,
+                Text::default(),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            cb.spawn(Node {
+                column_gap: Val::Px(10.),
+                margin: UiRect::top(Val::Px(10.)),
+                ..default()
+            })
+            .with_children(|cb| {
+                for button in LevelCompleteButton::ALL {
+                    cb.spawn((
+                        button,
+                        Button,
+                        BackgroundColor(BLUE),
+                        Node {
+                            height: Val::Px(44.),
+                            width: Val::Px(160.),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|cb| {
+                        cb.spawn((
+                            Text::new(button.label()),
+                            TextColor(WHITE),
+                            TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                            Node {
+                                margin: UiRect::all(Val::Auto),
+                                ..default()
+                            },
+                        ));
+                    });
+                }
+            });
+            cb.spawn((
+                InputPrompt::new("{confirm} to choose"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+#[expect(clippy::type_complexity)]
+fn on_show_level_complete(
+    trigger: Trigger<ShowLevelComplete>,
+    mut title_query: Query<
+        &mut Text,
+        (
+            With<LevelCompleteTitle>,
+            Without<LevelCompleteStats>,
+            Without<LevelCompleteAttempts>,
+        ),
+    >,
+    mut stats_query: Query<&mut Text, (With<LevelCompleteStats>, Without<LevelCompleteAttempts>)>,
+    mut attempts_query: Query<
+        &mut Text,
+        (With<LevelCompleteAttempts>, Without<LevelCompleteTitle>),
+    >,
+    mut state: ResMut<LevelCompleteState>,
+    game_state: Res<GameState>,
+    levels: Res<Levels>,
+) {
+    let ShowLevelComplete {
+        result,
+        secret_level,
+    } = trigger.event();
+    let level = game_state.current_level;
+
+    for mut text in &mut title_query {
+        text.0 = format!("Level {level} Complete");
+    }
+
+    for mut text in &mut stats_query {
+        text.0 = format!("{}\n{}", moves_line(result), time_line(result));
+    }
+
+    for mut text in &mut attempts_query {
+        text.0 = game_state
+            .level_stats
+            .get(&level)
+            .cloned()
+            .unwrap_or_default()
+            .attempt_summary();
+    }
+
+    let next_level = secret_level.or_else(|| {
+        let next_level = level.checked_add(1)?;
+        levels.get(next_level).is_some().then_some(next_level)
+    });
+    let next_unfinished_level = levels.numbers().find(|level| {
+        !game_state.is_hub_level(*level) && !game_state.finished_levels.contains(level)
+    });
+
+    let shown = ShownLevelComplete {
+        level,
+        next_level,
+        hub: game_state.current_hub,
+        next_unfinished_level,
+        standings: None,
+    };
+    let selection = [
+        LevelCompleteButton::NextLevel,
+        LevelCompleteButton::NextUnfinished,
+    ]
+    .into_iter()
+    .find(|button| button.is_available(&shown))
+    .unwrap_or(LevelCompleteButton::BackToHub);
+
+    *state = LevelCompleteState {
+        shown: Some(shown),
+        selection,
+    };
+}
+
+fn on_show_standings(
+    trigger: Trigger<ShowStandings>,
+    mut commands: Commands,
+    mut state: ResMut<LevelCompleteState>,
+) {
+    let ShowStandings { level, summary } = trigger.event();
+    match &mut state.shown {
+        Some(shown) if shown.level == *level => shown.standings = Some(summary.clone()),
+        _ => commands.trigger(ShowToast::new(format!("Level {level}: {summary}"))),
+    }
+}
+
+/// Describes the moves of the attempt, compared to the previous best.
+fn moves_line(result: &LevelResult) -> String {
+    let moves = match result.moves {
+        1 => "1 move".to_owned(),
+        moves => format!("{moves} moves"),
+    };
+    match result.previous_stats.best_moves {
+        Some(best) if result.moves < best => format!("{moves} (new best!)"),
+        Some(best) if result.moves == best => format!("{moves} (ties best)"),
+        Some(best) => format!("{moves} (best: {best})"),
+        None => moves,
+    }
+}
+
+/// Describes the time of the attempt, compared to the previous best.
+fn time_line(result: &LevelResult) -> String {
+    let time = format_duration(result.time);
+    match result.previous_stats.best_time {
+        Some(_) if result.is_new_best_time() => format!("{time} (new best!)"),
+        Some(best) => format!("{time} (best: {})", format_duration(best)),
+        None => time,
+    }
+}
+
+/// Lets the player choose from the buttons on the completion screen, using
+/// the keyboard, a gamepad, the mouse or touch.
+#[expect(clippy::too_many_arguments)]
+fn on_level_complete_input(
+    button_query: Query<(&Interaction, &LevelCompleteButton), Changed<Interaction>>,
+    gamepads: Query<&Gamepad>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut exit_state: ResMut<ExitState>,
+    mut state: ResMut<LevelCompleteState>,
+    editor: Res<EditorState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<MenuState>,
+) {
+    let Some(shown) = &state.shown else {
+        return;
+    };
+
+    // The screen is gone once another level got loaded, such as from the
+    // editor or the console.
+    if exit_state.next_level.is_none() {
+        state.shown = None;
+        return;
+    }
+
+    if editor.is_open || menu_state.is_open() {
+        return;
+    }
+
+    let available: Vec<LevelCompleteButton> = LevelCompleteButton::ALL
+        .into_iter()
+        .filter(|button| button.is_available(shown))
+        .collect();
+    let move_selection = |state: &mut LevelCompleteState, delta: isize| {
+        let index = available
+            .iter()
+            .position(|button| *button == state.selection)
+            .unwrap_or_default() as isize;
+        let len = available.len() as isize;
+        state.selection = available[(index + delta).rem_euclid(len) as usize];
+    };
+
+    let mut is_pressed = false;
+
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::ArrowLeft => move_selection(&mut state, -1),
+            KeyCode::ArrowRight => move_selection(&mut state, 1),
+            KeyCode::Enter | KeyCode::Space => is_pressed = true,
+            _ => {}
+        }
+    }
+
+    for gamepad in &gamepads {
+        if gamepad.just_pressed(GamepadButton::DPadLeft) {
+            move_selection(&mut state, -1);
+        } else if gamepad.just_pressed(GamepadButton::DPadRight) {
+            move_selection(&mut state, 1);
+        } else if gamepad.just_pressed(GamepadButton::South) {
+            is_pressed = true;
+        }
+    }
+
+    for (interaction, button) in &button_query {
+        match *interaction {
+            Interaction::Pressed => {
+                state.selection = *button;
+                is_pressed = true;
+            }
+            Interaction::Hovered => state.selection = *button,
+            Interaction::None => {}
+        }
+    }
+
+    if !is_pressed {
+        return;
+    }
+
+    let Some(shown) = state.shown.take() else {
+        return;
+    };
+
+    exit_state.next_level = match state.selection {
+        LevelCompleteButton::NextLevel => shown.next_level,
+        LevelCompleteButton::NextUnfinished => shown.next_unfinished_level,
+        LevelCompleteButton::Replay => Some(shown.level),
+        LevelCompleteButton::BackToHub => Some(shown.hub),
+    };
+    background_events.send(UpdateBackgroundTransform::LevelExit);
+}
+
+fn render_level_complete(
+    mut panel_query: Query<&mut Node, With<LevelComplete>>,
+    mut button_query: Query<
+        (&LevelCompleteButton, &mut Node, &mut BackgroundColor),
+        Without<LevelComplete>,
+    >,
+    mut standings_query: Query<&mut Text, With<LevelCompleteStandings>>,
+    state: Res<LevelCompleteState>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+
+    let Ok(mut panel_node) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    let Some(shown) = &state.shown else {
+        panel_node.display = Display::None;
+        return;
+    };
+
+    panel_node.display = Display::Flex;
+
+    for mut text in &mut standings_query {
+        text.0 = shown.standings.clone().unwrap_or_default();
+    }
+
+    for (button, mut node, mut background_color) in &mut button_query {
+        node.display = if button.is_available(shown) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        *background_color = if *button == state.selection {
+            RED
+        } else {
+            BLUE
+        }
+        .into();
+    }
+}
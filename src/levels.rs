@@ -1,14 +1,30 @@
 use std::{
     borrow::Cow,
     cmp::Ordering,
-    collections::{btree_map::Entry, BTreeMap},
-    fmt::Write,
+    collections::{btree_map::Entry, BTreeMap, BTreeSet},
+    fmt::{Display, Write},
+    fs,
     str::FromStr,
 };
 
-use bevy::prelude::Resource;
+use bevy::{
+    log::warn,
+    prelude::{Resource, Vec2},
+};
+
+use crate::{
+    constants::GRID_SIZE,
+    errors::{LevelError, UnknownKeyMode, UnknownTheme, UnknownWeather},
+    game_object::{Decoration, Direction, ObjectType, Position},
+    inventory::Inventory,
+    utils::user_levels_dir,
+};
 
-use crate::game_object::{Direction, ObjectType, Position};
+/// Largest width and height of a level, in tiles.
+///
+/// Big enough for a single overworld that connects all levels, while keeping
+/// positions well within the range of their coordinates.
+pub const MAX_LEVEL_SIZE: i16 = 1024;
 
 pub const LEVELS: &[(u16, &str)] = &[
     (0, include_str!("../assets/levels/level000")),
@@ -54,6 +70,11 @@ pub const LEVELS: &[(u16, &str)] = &[
     (100, include_str!("../assets/levels/level100")),
 ];
 
+/// The contents of all levels.
+///
+/// Levels come from two layers: the levels that are built into the game, and
+/// the user's own versions of them that were saved from the editor, which
+/// take precedence.
 #[derive(Resource)]
 pub struct Levels(BTreeMap<u16, LevelData>);
 
@@ -66,6 +87,25 @@ impl Levels {
         })
     }
 
+    /// Returns the numbers of all levels, in ascending order.
+    pub fn numbers(&self) -> impl Iterator<Item = u16> + '_ {
+        self.0.keys().copied()
+    }
+
+    /// Returns the numbers of all levels that are hubs.
+    pub fn hubs(&self) -> BTreeSet<u16> {
+        self.0
+            .iter()
+            .filter(|(_, data)| data.is_hub)
+            .map(|(level, _)| *level)
+            .collect()
+    }
+
+    /// Returns the stored contents of a level, ignoring any in-memory state.
+    pub fn get_stored(&self, level: u16) -> Option<&str> {
+        self.0.get(&level).map(|data| data.stored.as_ref())
+    }
+
     /// Inserts the contents as the new current state of the level.
     pub fn insert_current(&mut self, level: u16, contents: String) {
         if let Some(data) = self.0.get_mut(&level) {
@@ -77,12 +117,16 @@ impl Levels {
     ///
     /// This should only be used by the level editor.
     pub fn insert_stored(&mut self, level: u16, contents: String) {
-        let level_data = LevelData {
-            stored: Cow::Owned(contents),
-            current: None,
-        };
+        self.0
+            .insert(level, LevelData::new(level, Cow::Owned(contents)));
+    }
 
-        self.0.insert(level, level_data);
+    /// Replaces the stored contents of the level with the built-in version,
+    /// discarding the user's own version of it.
+    pub fn revert_to_builtin(&mut self, level: u16) {
+        if let Some(contents) = builtin_level(level) {
+            self.0.insert(level, LevelData::new(level, contents.into()));
+        }
     }
 
     /// Resets the given level to its original state.
@@ -95,100 +139,363 @@ impl Levels {
 
 impl Default for Levels {
     fn default() -> Self {
-        Self(
-            LEVELS
-                .iter()
-                .map(|(level_num, contents)| {
-                    let data = LevelData {
-                        current: None,
-                        stored: (*contents).into(),
-                    };
+        let mut levels: BTreeMap<u16, LevelData> = LEVELS
+            .iter()
+            .map(|(level_num, contents)| {
+                (*level_num, LevelData::new(*level_num, (*contents).into()))
+            })
+            .collect();
 
-                    (*level_num, data)
-                })
-                .collect(),
-        )
+        // Levels saved from the editor take precedence over the bundled ones.
+        for (level_num, contents) in load_user_levels() {
+            levels.insert(level_num, LevelData::new(level_num, contents.into()));
+        }
+
+        Self(levels)
     }
 }
 
+/// Returns the contents of the level as built into the game.
+pub fn builtin_level(level: u16) -> Option<&'static str> {
+    LEVELS
+        .iter()
+        .find(|(level_num, _)| *level_num == level)
+        .map(|(_, contents)| *contents)
+}
+
+/// Loads the levels the user has saved from the editor.
+fn load_user_levels() -> Vec<(u16, String)> {
+    let Ok(entries) = fs::read_dir(user_levels_dir()) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let level_num = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("level")?
+                .parse()
+                .ok()?;
+
+            match fs::read_to_string(entry.path()) {
+                // A corrupted level is skipped, so the bundled one is used
+                // in its place.
+                Ok(contents) => match Level::load(&contents) {
+                    Ok(_) => Some((level_num, contents)),
+                    Err(error) => {
+                        warn!("Could not load user level {level_num}: {error}");
+                        None
+                    }
+                },
+                Err(error) => {
+                    warn!("Could not read user level {level_num}: {error}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 struct LevelData {
     current: Option<String>,
     stored: Cow<'static, str>,
+
+    /// Whether the stored level is a hub, which is looked up often enough
+    /// not to parse the level every time.
+    is_hub: bool,
+}
+
+impl LevelData {
+    fn new(level: u16, stored: Cow<'static, str>) -> Self {
+        let is_hub = level == 0 || Level::load(&stored).is_ok_and(|level| level.is_hub);
+        Self {
+            current: None,
+            stored,
+            is_hub,
+        }
+    }
+}
+
+/// Warnings encountered while loading the current level, so they can be shown
+/// in the editor.
+#[derive(Default, Resource)]
+pub struct LevelWarnings(pub Vec<String>);
+
+/// Maximum number of warnings that are listed in the toast shown after loading
+/// a level.
+const MAX_TOAST_WARNINGS: usize = 3;
+
+impl LevelWarnings {
+    /// Returns a message summarizing the warnings for the given level, or
+    /// `None` if there are none.
+    pub fn toast_message(&self, level: u16) -> Option<String> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        let mut message = format!("Level {level} has problems:");
+        for warning in self.0.iter().take(MAX_TOAST_WARNINGS) {
+            message.push('\n');
+            message.push_str(warning);
+        }
+        if self.0.len() > MAX_TOAST_WARNINGS {
+            message.push_str(&format!(
+                "\n...and {} more, see the editor",
+                self.0.len() - MAX_TOAST_WARNINGS
+            ));
+        }
+
+        Some(message)
+    }
+}
+
+/// Checksum of the contents of the current level, so scores set on modified
+/// levels can be told apart from those set on the shipped versions.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub struct LevelChecksum(pub u64);
+
+impl LevelChecksum {
+    /// Calculates the checksum using 64-bit FNV-1a, which is stable across
+    /// platforms and builds. Line endings and surrounding whitespace are
+    /// ignored, so a checkout with different line endings doesn't count as a
+    /// modification.
+    pub fn of(content: &str) -> Self {
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+
+        let mut hash = OFFSET_BASIS;
+        for line in content.lines() {
+            for byte in line.trim().bytes().chain([b'\n']) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(PRIME);
+            }
+        }
+        Self(hash)
+    }
+
+    /// Returns whether the checksum differs from that of the built-in level
+    /// with the given number. Levels that aren't built in always count as
+    /// modified.
+    pub fn is_modified(self, level: u16) -> bool {
+        builtin_level(level).is_none_or(|content| Self::of(content) != self)
+    }
+}
+
+impl Display for LevelChecksum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
 }
 
 pub struct Level {
     pub dimensions: Dimensions,
+
+    /// Whether the level is a hub, from which entrances lead to other levels.
+    /// Level 0 is always a hub.
+    pub is_hub: bool,
+
+    pub theme: Theme,
+    pub key_mode: KeyMode,
+    pub push_strength: PushStrength,
+    pub diagonal_movement: DiagonalMovement,
+    pub weather: Weather,
+
+    /// Items the player is carrying, for levels that were saved while in
+    /// progress.
+    pub inventory: Inventory,
+
     pub objects: BTreeMap<ObjectType, Vec<InitialPositionAndMetadata>>,
+
+    /// Problems encountered while parsing the level.
+    ///
+    /// Parsing is lenient about unknown keys and values, so the level remains
+    /// usable regardless.
+    pub warnings: Vec<String>,
 }
 
 impl Level {
-    pub fn load(content: &str) -> Self {
+    /// Parses the contents of a level.
+    ///
+    /// Content that is malformed beyond an unknown key or value, such as a
+    /// truncated line, fails the whole level. Otherwise a corrupted file
+    /// could load with only part of its objects.
+    pub fn load(content: &str) -> Result<Self, LevelError> {
         let mut dimensions = Dimensions::default();
+        let mut is_hub = false;
+        let mut theme = Theme::default();
+        let mut key_mode = KeyMode::default();
+        let mut push_strength = PushStrength::default();
+        let mut diagonal_movement = DiagonalMovement::default();
+        let mut weather = Weather::default();
+        let mut inventory = Inventory::default();
+        let mut decoration = None;
         let mut direction = None;
         let mut identifier = None;
         let mut level = None;
         let mut open = false;
+        let mut capacity = None;
+        let mut interval = None;
+        let mut required_count = None;
+        let mut seconds = None;
+        let mut text = None;
         let mut objects: BTreeMap<ObjectType, Vec<InitialPositionAndMetadata>> = BTreeMap::new();
+        let mut warnings = Vec::new();
 
         let mut section_name = None;
-        for line in content.lines() {
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
             let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
 
             if line.starts_with('[') && line.ends_with(']') {
+                decoration = None;
                 direction = None;
                 identifier = None;
                 level = None;
                 open = false;
+                capacity = None;
+                interval = None;
+                required_count = None;
+                seconds = None;
+                text = None;
                 section_name = Some(&line[1..line.len() - 1]);
                 continue;
             }
 
             let Some((key, value)) = line.split_once('=') else {
-                continue;
+                return Err(LevelError::UnexpectedLine(line_number));
             };
 
             let Some(section_name) = section_name else {
-                continue;
+                return Err(LevelError::KeyOutsideSection(line_number));
             };
 
             if section_name == "General" {
-                match (key, value.parse()) {
-                    ("Width", Ok(value)) => dimensions.width = value,
-                    ("Height", Ok(value)) => dimensions.height = value,
-                    (_, Ok(_)) => println!("Unknown key: {key}"),
-                    (_, Err(error)) => println!("Invalid dimension in key {key}: {error}"),
+                if key == "Theme" {
+                    match Theme::from_str(value) {
+                        Ok(value) => theme = value,
+                        Err(_) => warnings.push(format!("Unknown theme: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "Keys" {
+                    match KeyMode::from_str(value) {
+                        Ok(value) => key_mode = value,
+                        Err(_) => warnings.push(format!("Unknown key mode: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "PushStrength" {
+                    match value.parse() {
+                        Ok(value) if value > 0 => push_strength = PushStrength(value),
+                        _ => warnings.push(format!("Invalid push strength: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "Hub" {
+                    match value.parse() {
+                        Ok(value) => is_hub = value,
+                        Err(_) => warnings.push(format!("Cannot parse hub: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "Diagonals" {
+                    match value.parse() {
+                        Ok(value) => diagonal_movement = DiagonalMovement(value),
+                        Err(_) => warnings.push(format!("Cannot parse diagonals: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "Weather" {
+                    match Weather::from_str(value) {
+                        Ok(value) => weather = value,
+                        Err(_) => warnings.push(format!("Unknown weather: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "CarriedKeys" {
+                    match value.parse() {
+                        Ok(value) => inventory.keys = value,
+                        Err(_) => warnings.push(format!("Cannot parse carried keys: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "CarriedBombs" {
+                    match value.parse() {
+                        Ok(value) => inventory.bombs = value,
+                        Err(_) => warnings.push(format!("Cannot parse carried bombs: {value}")),
+                    }
+                    continue;
+                }
+
+                if key == "Width" || key == "Height" {
+                    let dimension = match value.parse() {
+                        Ok(value) if (1..=MAX_LEVEL_SIZE).contains(&value) => value,
+                        _ => {
+                            return Err(LevelError::InvalidDimension {
+                                line: line_number,
+                                value: value.to_owned(),
+                            })
+                        }
+                    };
+                    if key == "Width" {
+                        dimensions.width = dimension;
+                    } else {
+                        dimensions.height = dimension;
+                    }
+                    continue;
                 }
+
+                warnings.push(format!("Unknown key: {key}"));
                 continue;
             }
 
             let object_type = match ObjectType::from_str(section_name) {
                 Ok(object_type) => object_type,
                 Err(_) => {
-                    println!("Unknown object type: {section_name}");
+                    warnings.push(format!("Unknown object type: {section_name}"));
                     continue;
                 }
             };
 
             if key == "Position" {
-                let positions: Vec<InitialPositionAndMetadata> = value
-                    .split(';')
-                    .filter_map(|location| match location.split_once(',') {
-                        Some((x, y)) => match (x.parse(), y.parse()) {
-                            (Ok(x), Ok(y)) => Some(InitialPositionAndMetadata {
-                                position: Position { x, y },
-                                direction,
-                                identifier,
-                                level,
-                                open,
-                            }),
-                            _ => {
-                                println!("Invalid location ({x},{y})");
-                                None
-                            }
-                        },
-                        _ => None,
-                    })
-                    .collect();
+                let mut positions = Vec::new();
+                // Positions may end with a trailing separator.
+                for location in value.split(';').filter(|location| !location.is_empty()) {
+                    let Some((Ok(x), Ok(y))) = location
+                        .split_once(',')
+                        .map(|(x, y)| (x.parse(), y.parse()))
+                    else {
+                        return Err(LevelError::InvalidPosition {
+                            line: line_number,
+                            value: location.to_owned(),
+                        });
+                    };
+                    positions.push(InitialPositionAndMetadata {
+                        position: Position { x, y },
+                        decoration,
+                        direction,
+                        identifier,
+                        level,
+                        open,
+                        capacity,
+                        interval,
+                        required_count,
+                        seconds,
+                        text: text.clone(),
+                    });
+                }
 
                 if !positions.is_empty() {
                     let entry = objects.entry(object_type);
@@ -199,25 +506,32 @@ impl Level {
                         }
                     }
                 }
+            } else if key == "Kind" {
+                match Decoration::from_str(value) {
+                    Ok(value) => decoration = Some(value),
+                    Err(_) => {
+                        warnings.push(format!("Unknown decoration: {value}"));
+                    }
+                }
             } else if key == "Direction" {
                 match Direction::from_str(value) {
                     Ok(value) => direction = Some(value),
                     Err(_) => {
-                        println!("Unknown direction: {value}");
+                        warnings.push(format!("Unknown direction: {value}"));
                     }
                 }
             } else if key == "Identifier" {
                 match value.parse() {
                     Ok(value) => identifier = Some(value),
                     Err(_) => {
-                        println!("Cannot parse identifier: {value}");
+                        warnings.push(format!("Cannot parse identifier: {value}"));
                     }
                 }
             } else if key == "Level" {
                 match value.parse() {
                     Ok(value) => level = Some(value),
                     Err(_) => {
-                        println!("Cannot parse level number: {value}");
+                        warnings.push(format!("Cannot parse level number: {value}"));
                     }
                 }
             } else if key == "Open" {
@@ -225,49 +539,148 @@ impl Level {
                     "true" => open = true,
                     "false" => open = false,
                     _ => {
-                        println!("Cannot parse open value: {value}");
+                        warnings.push(format!("Cannot parse open value: {value}"));
+                    }
+                }
+            } else if key == "RequiredCount" {
+                match value.parse() {
+                    Ok(value) => required_count = Some(value),
+                    Err(_) => {
+                        warnings.push(format!("Cannot parse required count: {value}"));
+                    }
+                }
+            } else if key == "Capacity" {
+                match value.parse() {
+                    Ok(value) => capacity = Some(value),
+                    Err(_) => {
+                        warnings.push(format!("Cannot parse capacity: {value}"));
+                    }
+                }
+            } else if key == "Interval" {
+                match value.parse() {
+                    Ok(value) if value > 0 => interval = Some(value),
+                    _ => {
+                        warnings.push(format!("Invalid interval: {value}"));
+                    }
+                }
+            } else if key == "Seconds" {
+                match value.parse() {
+                    Ok(value) => seconds = Some(value),
+                    Err(_) => {
+                        warnings.push(format!("Cannot parse seconds: {value}"));
                     }
                 }
+            } else if key == "Text" {
+                text = Some(value.replace("\\n", "\n"));
             } else {
-                println!("Unknown key: {key}");
+                warnings.push(format!("Unknown key: {key}"));
             }
         }
 
-        Self {
+        Ok(Self {
             dimensions,
+            is_hub,
+            theme,
+            key_mode,
+            push_strength,
+            diagonal_movement,
+            weather,
+            inventory,
             objects,
-        }
+            warnings,
+        })
     }
 
     pub fn save(self) -> String {
         let Dimensions { width, height } = self.dimensions;
 
         let mut content = format!("[General]\nWidth={width}\nHeight={height}");
+        if self.is_hub {
+            content.push_str("\nHub=true");
+        }
+        if self.theme != Theme::default() {
+            write!(content, "\nTheme={}", self.theme).expect("writing failed");
+        }
+        if self.key_mode != KeyMode::default() {
+            write!(content, "\nKeys={}", self.key_mode).expect("writing failed");
+        }
+        if self.push_strength != PushStrength::default() {
+            write!(content, "\nPushStrength={}", self.push_strength.0).expect("writing failed");
+        }
+        if self.diagonal_movement.0 {
+            content.push_str("\nDiagonals=true");
+        }
+        if self.weather != Weather::default() {
+            write!(content, "\nWeather={}", self.weather).expect("writing failed");
+        }
+        if self.inventory.keys > 0 {
+            write!(content, "\nCarriedKeys={}", self.inventory.keys).expect("writing failed");
+        }
+        if self.inventory.bombs > 0 {
+            write!(content, "\nCarriedBombs={}", self.inventory.bombs).expect("writing failed");
+        }
 
         for (object_type, mut positions) in self.objects {
             writeln!(content, "\n\n[{object_type}]").expect("writing failed");
 
-            positions.sort_unstable_by(|a, b| match a.level.cmp(&b.level) {
-                Ordering::Equal => match a.direction.cmp(&b.direction) {
-                    Ordering::Equal => a.position.cmp(&b.position),
-                    ordering => ordering,
-                },
-                ordering => ordering,
+            // Objects without a required count, capacity, interval or seconds
+            // go first, since these are not reset within a section.
+            positions.sort_unstable_by(|a, b| {
+                a.required_count
+                    .cmp(&b.required_count)
+                    .then_with(|| a.capacity.cmp(&b.capacity))
+                    .then_with(|| a.interval.cmp(&b.interval))
+                    .then_with(|| a.seconds.cmp(&b.seconds))
+                    .then_with(|| match a.level.cmp(&b.level) {
+                        Ordering::Equal => match a.direction.cmp(&b.direction) {
+                            Ordering::Equal => match a.decoration.cmp(&b.decoration) {
+                                Ordering::Equal => match a.text.cmp(&b.text) {
+                                    Ordering::Equal => a.position.cmp(&b.position),
+                                    ordering => ordering,
+                                },
+                                ordering => ordering,
+                            },
+                            ordering => ordering,
+                        },
+                        ordering => ordering,
+                    })
             });
 
+            let mut current_decoration = None;
             let mut current_direction = Direction::default();
             let mut current_identifier = 0;
             let mut current_level = 0;
             let mut current_open = false;
+            let mut current_capacity = 0;
+            let mut current_interval = 1;
+            let mut current_required_count = 0;
+            let mut current_seconds = None;
+            let mut current_text = None;
             let mut last_x = None;
             for InitialPositionAndMetadata {
                 position,
+                decoration,
                 direction,
                 identifier,
                 level,
                 open,
+                capacity,
+                interval,
+                required_count,
+                seconds,
+                text,
             } in positions
             {
+                if decoration.is_some() && decoration != current_decoration {
+                    if !content.ends_with('\n') {
+                        content.push('\n');
+                    }
+
+                    let decoration = decoration.unwrap_or(Decoration::Flower);
+                    writeln!(content, "Kind={decoration}").expect("writing failed");
+                    current_decoration = Some(decoration);
+                }
+
                 if let Some(direction) = direction {
                     if direction != current_direction {
                         if !content.ends_with('\n') {
@@ -301,6 +714,51 @@ impl Level {
                     }
                 }
 
+                if let Some(required_count) = required_count {
+                    if required_count != current_required_count {
+                        if !content.ends_with('\n') {
+                            content.push('\n');
+                        }
+
+                        writeln!(content, "RequiredCount={required_count}")
+                            .expect("writing failed");
+                        current_required_count = required_count;
+                    }
+                }
+
+                if let Some(capacity) = capacity {
+                    if capacity != current_capacity {
+                        if !content.ends_with('\n') {
+                            content.push('\n');
+                        }
+
+                        writeln!(content, "Capacity={capacity}").expect("writing failed");
+                        current_capacity = capacity;
+                    }
+                }
+
+                if let Some(interval) = interval {
+                    if interval != current_interval {
+                        if !content.ends_with('\n') {
+                            content.push('\n');
+                        }
+
+                        writeln!(content, "Interval={interval}").expect("writing failed");
+                        current_interval = interval;
+                    }
+                }
+
+                // Zero seconds is a valid value, for gates that have closed.
+                if seconds.is_some() && seconds != current_seconds {
+                    if !content.ends_with('\n') {
+                        content.push('\n');
+                    }
+
+                    let seconds = seconds.unwrap_or_default();
+                    writeln!(content, "Seconds={seconds}").expect("writing failed");
+                    current_seconds = Some(seconds);
+                }
+
                 if open != current_open {
                     if !content.ends_with('\n') {
                         content.push('\n');
@@ -310,6 +768,16 @@ impl Level {
                     current_open = open;
                 }
 
+                if text.is_some() && text != current_text {
+                    if !content.ends_with('\n') {
+                        content.push('\n');
+                    }
+
+                    let escaped_text = text.as_deref().unwrap_or_default().replace('\n', "\\n");
+                    writeln!(content, "Text={escaped_text}").expect("writing failed");
+                    current_text = text;
+                }
+
                 if content.ends_with('\n') {
                     write!(content, "Position={position}").expect("writing failed");
                 } else if last_x != Some(position.x) {
@@ -327,6 +795,14 @@ impl Level {
     }
 }
 
+impl FromStr for Level {
+    type Err = LevelError;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        Self::load(content)
+    }
+}
+
 #[derive(Clone, Copy, Resource)]
 pub struct Dimensions {
     pub width: i16,
@@ -347,25 +823,293 @@ impl Dimensions {
         let Position { x, y } = position;
         x >= 1 && x <= self.width && y >= 1 && y <= self.height
     }
+
+    /// Returns the size of the level in pixels, before zooming.
+    ///
+    /// Computed with floats, as the pixel size of large levels doesn't fit
+    /// in the coordinates of a [Position].
+    pub fn size_in_pixels(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32) * GRID_SIZE as f32
+    }
+
+    /// Returns the translation of the center of the tile at the given
+    /// position, relative to the center of the level.
+    pub fn translation_of(&self, position: Position) -> Vec2 {
+        let half_size = 0.5 * self.size_in_pixels();
+        Vec2::new(
+            -half_size.x + ((position.x as f32 - 0.5) * GRID_SIZE as f32),
+            half_size.y - ((position.y as f32 - 0.5) * GRID_SIZE as f32),
+        )
+    }
+}
+
+/// Visual theme of a level, which determines its background.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub enum Theme {
+    #[default]
+    Grass,
+    Cave,
+    Ice,
+    Desert,
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Grass => "Grass",
+            Self::Cave => "Cave",
+            Self::Ice => "Ice",
+            Self::Desert => "Desert",
+        })
+    }
+}
+
+impl FromStr for Theme {
+    type Err = UnknownTheme;
+
+    fn from_str(theme: &str) -> Result<Self, Self::Err> {
+        match theme {
+            "Grass" => Ok(Self::Grass),
+            "Cave" => Ok(Self::Cave),
+            "Ice" => Ok(Self::Ice),
+            "Desert" => Ok(Self::Desert),
+            _ => Err(UnknownTheme),
+        }
+    }
+}
+
+/// How keys are used to open doors in a level.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub enum KeyMode {
+    /// Keys are pushed onto doors.
+    #[default]
+    Push,
+
+    /// Keys are picked up by the player, and used when bumping into a door.
+    Carry,
+}
+
+impl Display for KeyMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Push => "Push",
+            Self::Carry => "Carry",
+        })
+    }
+}
+
+impl FromStr for KeyMode {
+    type Err = UnknownKeyMode;
+
+    fn from_str(key_mode: &str) -> Result<Self, Self::Err> {
+        match key_mode {
+            "Push" => Ok(Self::Push),
+            "Carry" => Ok(Self::Carry),
+            _ => Err(UnknownKeyMode),
+        }
+    }
+}
+
+/// Weather that is shown over a level, purely for its looks.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Snow,
+}
+
+impl Display for Weather {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Clear => "Clear",
+            Self::Rain => "Rain",
+            Self::Snow => "Snow",
+        })
+    }
+}
+
+impl FromStr for Weather {
+    type Err = UnknownWeather;
+
+    fn from_str(weather: &str) -> Result<Self, Self::Err> {
+        match weather {
+            "Clear" => Ok(Self::Clear),
+            "Rain" => Ok(Self::Rain),
+            "Snow" => Ok(Self::Snow),
+            _ => Err(UnknownWeather),
+        }
+    }
+}
+
+/// Maximum number of objects in a row the player can push at once in a
+/// level.
+#[derive(Clone, Copy, Eq, PartialEq, Resource)]
+pub struct PushStrength(pub u16);
+
+impl Default for PushStrength {
+    fn default() -> Self {
+        Self(1)
+    }
 }
 
+/// Whether the player can move diagonally in a level, on top of the four
+/// cardinal directions.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Resource)]
+pub struct DiagonalMovement(pub bool);
+
 #[derive(Clone)]
 pub struct InitialPositionAndMetadata {
     pub position: Position,
+    pub decoration: Option<Decoration>,
     pub direction: Option<Direction>,
     pub identifier: Option<u16>,
     pub level: Option<u16>,
     pub open: bool,
+    pub capacity: Option<u16>,
+    pub interval: Option<u16>,
+    pub required_count: Option<u16>,
+    pub seconds: Option<u16>,
+    pub text: Option<String>,
 }
 
 impl From<&Position> for InitialPositionAndMetadata {
     fn from(position: &Position) -> Self {
         Self {
             position: *position,
+            decoration: None,
             direction: None,
             identifier: None,
             level: None,
             open: false,
+            capacity: None,
+            interval: None,
+            required_count: None,
+            seconds: None,
+            text: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+    use super::*;
+
+    const OBJECT_TYPES: &[ObjectType] = &[
+        ObjectType::BlueBlock,
+        ObjectType::Bridge,
+        ObjectType::Button,
+        ObjectType::Decoration,
+        ObjectType::Door,
+        ObjectType::Entrance,
+        ObjectType::Gate,
+        ObjectType::Sign,
+        ObjectType::Transporter,
+        ObjectType::Water,
+    ];
+
+    const DIRECTIONS: &[Direction] = &[
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+        Direction::UpRight,
+        Direction::DownRight,
+        Direction::DownLeft,
+        Direction::UpLeft,
+    ];
+
+    const DECORATIONS: &[Decoration] = &[Decoration::Flower, Decoration::Rock, Decoration::Rug];
+
+    /// Saves the level, loads it back and saves it again, and returns both
+    /// saved contents.
+    fn round_trip(level: Level) -> (String, String) {
+        let saved = level.save();
+        let reloaded = Level::load(&saved).expect("saved level should load");
+        assert!(reloaded.warnings.is_empty(), "{:?}", reloaded.warnings);
+        (saved, reloaded.save())
+    }
+
+    fn random_option<T>(
+        rng: &mut StdRng,
+        is_used: bool,
+        value: impl FnOnce(&mut StdRng) -> T,
+    ) -> Option<T> {
+        is_used.then(|| value(rng))
+    }
+
+    fn random_level(rng: &mut StdRng) -> Level {
+        let width = rng.gen_range(1..=20);
+        let height = rng.gen_range(1..=20);
+        let mut level = Level::load(&format!("[General]\nWidth={width}\nHeight={height}"))
+            .expect("empty level should load");
+        level.is_hub = rng.gen_bool(0.2);
+        level.diagonal_movement = DiagonalMovement(rng.gen_bool(0.2));
+
+        // Metadata that is used by an object type is given for all objects of
+        // that type, as it is carried over from one object to the next while
+        // loading.
+        let mut used_metadata: BTreeMap<ObjectType, [bool; 9]> = BTreeMap::new();
+
+        for _ in 0..rng.gen_range(0..40) {
+            let object_type = *OBJECT_TYPES.choose(rng).unwrap();
+            let used = *used_metadata
+                .entry(object_type)
+                .or_insert_with(|| rng.gen());
+            let position = Position {
+                x: rng.gen_range(1..=width),
+                y: rng.gen_range(1..=height),
+            };
+            let initial_position = InitialPositionAndMetadata {
+                position,
+                decoration: random_option(rng, used[0], |rng| *DECORATIONS.choose(rng).unwrap()),
+                direction: random_option(rng, used[1], |rng| *DIRECTIONS.choose(rng).unwrap()),
+                identifier: random_option(rng, used[2], |rng| rng.gen_range(1..5)),
+                level: random_option(rng, used[3], |rng| rng.gen_range(1..100)),
+                open: rng.gen_bool(0.5),
+                capacity: random_option(rng, used[4], |rng| rng.gen_range(1..5)),
+                interval: random_option(rng, used[5], |rng| rng.gen_range(1..5)),
+                required_count: random_option(rng, used[6], |rng| rng.gen_range(1..5)),
+                seconds: random_option(rng, used[7], |rng| rng.gen_range(1..60)),
+                text: random_option(rng, used[8], |rng| {
+                    let words = ["Push", "the", "block", "onto", "the", "button"];
+                    (0..rng.gen_range(1..5))
+                        .map(|_| *words.choose(rng).unwrap())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }),
+            };
+            level
+                .objects
+                .entry(object_type)
+                .or_default()
+                .push(initial_position);
+        }
+
+        level
+    }
+
+    #[test]
+    fn bundled_levels_save_stably() {
+        for (number, content) in LEVELS {
+            let level = Level::load(content)
+                .unwrap_or_else(|error| panic!("level {number} should load: {error}"));
+            let (saved, resaved) = round_trip(level);
+            assert_eq!(saved, resaved, "saving level {number} is not stable");
+        }
+    }
+
+    #[test]
+    fn generated_levels_save_stably() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for iteration in 0..500 {
+            let (saved, resaved) = round_trip(random_level(&mut rng));
+            assert_eq!(
+                saved, resaved,
+                "saving generated level {iteration} is not stable"
+            );
         }
     }
 }
@@ -0,0 +1,1912 @@
+mod achievements;
+mod attract_mode;
+mod background;
+mod cli;
+#[cfg(feature = "clip_capture")]
+mod clip_capture;
+mod console;
+mod constants;
+mod credits;
+mod cutscene;
+mod day_night;
+mod dialogue;
+mod editor;
+mod errors;
+mod fast_travel;
+mod fonts;
+mod game_object;
+mod game_state;
+mod gameover;
+mod graves;
+mod hot_reload;
+mod input_display;
+mod input_prompt;
+mod inventory;
+mod joystick;
+mod leaderboard;
+mod level_banner;
+mod level_complete;
+pub mod levels;
+mod logging;
+mod menu;
+mod modifiers;
+mod overworld;
+mod photo_mode;
+mod practice_mode;
+mod randomizer;
+mod rewind;
+mod rng;
+mod save_error;
+mod screenshot;
+mod settings;
+mod sign_dialog;
+mod simulation;
+mod skins;
+mod streamer_mode;
+mod text_input;
+mod timers;
+mod toast;
+mod touch_gestures;
+mod tutorial;
+mod ui_state;
+mod utils;
+mod validation;
+mod weather;
+
+use std::{collections::BTreeMap, fs, io, num::NonZero};
+
+use achievements::AchievementsPlugin;
+use attract_mode::AttractModePlugin;
+use background::{Background, BackgroundPlugin, UpdateBackgroundTransform};
+use bevy::{
+    input::{
+        gestures::PinchGesture,
+        mouse::{MouseScrollUnit, MouseWheel},
+    },
+    log::LogPlugin,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode, WindowResized, WindowResolution},
+    winit::WinitWindows,
+};
+use cli::CliOptions;
+use console::ConsolePlugin;
+use constants::*;
+use credits::{CreditsPlugin, CreditsState};
+use cutscene::{CutscenePlugin, CutsceneState};
+use day_night::DayNightPlugin;
+use dialogue::{DialoguePlugin, DialogueState};
+use editor::{
+    on_editor_keyboard_input, on_editor_mouse_input, EditorPlugin, EditorState, SelectionOverlay,
+    ToggleEditor,
+};
+use fast_travel::{FastTravelPlugin, OpenFastTravel};
+use fonts::Fonts;
+use game_object::{
+    behaviors::*, spawn_object_of_type, BlocksMovement, Bridge, CollisionObjectQuery, Decoration,
+    Direction, Entrance, Exit, GameObjectAssets, Glide, LevelObject, Massive, Npc, ObjectType,
+    Openable, Player, Position, RevealedByLevel, Sign, Teleporter, TileIndex, TimerGate,
+    Transporter, Weight, PLAYER_ASSET,
+};
+use game_state::GameState;
+use gameover::{
+    check_for_game_over, flash_killer_highlight, on_game_over_input, render_game_over_buttons,
+    setup_gameover, show_death_recap, GameOverSelection,
+};
+use graves::GravesPlugin;
+use hot_reload::HotReloadPlugin;
+use input_display::InputDisplayPlugin;
+use input_prompt::InputPromptPlugin;
+use inventory::{Inventory, InventoryPlugin};
+use joystick::JoystickPlugin;
+use leaderboard::LeaderboardPlugin;
+use level_banner::LevelBannerPlugin;
+use level_complete::LevelCompletePlugin;
+use levels::{
+    builtin_level, DiagonalMovement, Dimensions, InitialPositionAndMetadata, KeyMode, Level,
+    LevelChecksum, LevelWarnings, Levels, PushStrength, Theme, Weather,
+};
+use logging::{file_log_layer, install_panic_hook};
+use menu::{on_menu_keyboard_input, MenuKind, MenuPlugin, MenuState};
+use modifiers::{MirroredControls, ModifiersPlugin};
+use overworld::{OpenOverworld, OverworldPlugin};
+use photo_mode::{PhotoMode, PhotoModePlugin};
+use practice_mode::{PracticeMode, PracticeModePlugin, TogglePracticeMode};
+use rewind::{MoveHistory, RewindPlugin};
+use rng::{reseed_rng, GameRng};
+use save_error::{SaveErrorPlugin, SaveFailed};
+use screenshot::ScreenshotPlugin;
+use settings::{Settings, SettingsPlugin};
+use sign_dialog::SignDialogPlugin;
+use simulation::{simulate, SimulationPlugin, SimulationResult};
+use skins::SkinsPlugin;
+use streamer_mode::{StreamerModePlugin, ToggleStreamerMode};
+use text_input::TextInputPlugin;
+use timers::{AnimationTimer, MovementRepeatTimer, RespawnTimer};
+use toast::{ShowToast, ToastPlugin};
+use touch_gestures::TouchGesturePlugin;
+use tutorial::TutorialPlugin;
+use ui_state::UiState;
+use utils::get_user_level_path;
+use validation::validate_levels;
+use weather::WeatherPlugin;
+use winit::window::Icon;
+
+const WINDOW_TITLE: &str = "Chunky's Challenge";
+
+/// Rate at which the camera slows down after a drag is released, as the
+/// fraction of its speed that is lost every second.
+const DRAG_DECELERATION: f32 = 5.;
+
+/// Speed below which the camera comes to a stop after a drag, in tiles per
+/// second.
+const MIN_DRAG_VELOCITY: f32 = 0.5;
+
+/// Speed at which the camera pans while looking around using the keyboard, in
+/// tiles per second.
+const CAMERA_PAN_SPEED: f32 = 10.;
+
+/// Keys that pan the camera while Alt is held, and the direction they pan in.
+const CAMERA_PAN_KEYS: [(KeyCode, Vec2); 4] = [
+    (KeyCode::KeyW, Vec2::NEG_Y),
+    (KeyCode::KeyA, Vec2::NEG_X),
+    (KeyCode::KeyS, Vec2::Y),
+    (KeyCode::KeyD, Vec2::X),
+];
+
+/// Maximum number of level objects that get spawned in a single frame.
+///
+/// Larger levels are spawned over the course of multiple frames, starting
+/// with the objects closest to the player, so loading them doesn't freeze
+/// the game.
+const SPAWN_CHUNK_SIZE: usize = 4096;
+
+/// Level that is loaded when there is no level with the requested number.
+const PLACEHOLDER_LEVEL: &str = r#"[Player]
+Position=1,1
+
+[Exit]
+Position=2,1
+"#;
+
+#[derive(Default, Resource)]
+struct ExitState {
+    next_level: Option<u16>,
+}
+
+/// Snapshot of the level taken when the player last reached a checkpoint.
+///
+/// Dying respawns the player from the snapshot instead of showing the game
+/// over screen. The snapshot is discarded whenever the level is loaded or
+/// restarted.
+#[derive(Default, Resource)]
+struct LevelCheckpoint {
+    position: Option<Position>,
+    contents: String,
+}
+
+/// Snapshot of the level taken by quick saving, which the player can restore
+/// for as long as they stay in the level.
+#[derive(Default, Resource)]
+struct QuickSave {
+    level: Option<u16>,
+    contents: String,
+}
+
+/// Number of pressed triggers, for every trigger identifier in the level.
+#[derive(Default, Resource)]
+struct PressedTriggers {
+    num_pressed_triggers: BTreeMap<u16, usize>,
+}
+
+/// The level as it was last spawned.
+///
+/// Used for restarting the level without despawning and respawning all its
+/// objects.
+#[derive(Default, Resource)]
+struct SpawnedLevel {
+    level: u16,
+    contents: String,
+    objects: Vec<(ObjectType, InitialPositionAndMetadata)>,
+
+    /// Indices of the objects that are yet to be spawned, with the next ones
+    /// to spawn at the end.
+    pending: Vec<usize>,
+}
+
+impl SpawnedLevel {
+    /// Queues all objects for spawning, with the ones closest to the player
+    /// first.
+    fn queue_objects(&mut self) {
+        self.pending = (0..self.objects.len()).collect();
+        if self.pending.len() <= SPAWN_CHUNK_SIZE {
+            return;
+        }
+
+        let Some(player_position) = self
+            .objects
+            .iter()
+            .find(|(object_type, _)| *object_type == ObjectType::Player)
+            .map(|(_, initial_position)| initial_position.position)
+        else {
+            return;
+        };
+        let distance = |index: &usize| {
+            let position = self.objects[*index].1.position;
+            (position.x - player_position.x)
+                .abs()
+                .max((position.y - player_position.y).abs())
+        };
+        self.pending
+            .sort_by_key(|index| std::cmp::Reverse(distance(index)));
+    }
+
+    /// Takes the next chunk of objects to spawn from the queue.
+    fn next_chunk(&mut self) -> Vec<bool> {
+        let mut is_in_chunk = vec![false; self.objects.len()];
+        let start = self.pending.len().saturating_sub(SPAWN_CHUNK_SIZE);
+        for index in self.pending.drain(start..) {
+            is_in_chunk[index] = true;
+        }
+        is_in_chunk
+    }
+}
+
+/// Multiplies the zoom factor by the given factor.
+///
+/// If a cursor position is given, the camera is moved so that the part of the
+/// level under the cursor stays in place. Otherwise, the zoom is centered on
+/// the focus of the camera.
+#[derive(Event)]
+struct ChangeZoom {
+    factor: f32,
+    cursor_position: Option<Vec2>,
+}
+
+#[derive(Event)]
+enum GameEvent {
+    MovePlayer(i16, i16),
+    /// Moves the player while pulling along the object behind them.
+    PullPlayer(i16, i16),
+}
+
+/// Loads the given level.
+#[derive(Event)]
+struct LoadLevel(u16);
+
+/// Loads the relative level.
+///
+/// The level to load is calculated by adding the given delta to the current
+/// level. Commonly used to load the next/previous level by specifying 1/-1,
+/// respectively. Also used to reload the current level using a delta of 0.
+#[derive(Event)]
+struct LoadRelativeLevel(i16);
+
+/// Restarts the current level by resetting its objects to their initial state.
+///
+/// This should only be triggered when the contents of the level haven't
+/// changed since it was spawned. Use `LoadRelativeLevel(0)` to restart in a
+/// way that falls back to reloading the level when needed.
+#[derive(Event)]
+struct RestartLevel;
+
+/// Respawns the level from a snapshot of its state.
+#[derive(Event)]
+enum RestoreSnapshot {
+    /// Restores the snapshot of the last reached checkpoint.
+    Checkpoint,
+
+    /// Restores the quick save of the current level.
+    QuickSave,
+
+    /// Restores the latest snapshot of the move history.
+    History,
+
+    /// Restores the savestate that was set in practice mode.
+    Practice,
+}
+
+/// Resets the current level.
+///
+/// Resetting differs from restarting (using `LoadRelativeLevel(0)`) because it
+/// always resets to the version from disk and ignores what was saved in-memory.
+#[derive(Event)]
+struct ResetLevel;
+
+/// Discards the user's own version of the current level, so the built-in
+/// version gets used again.
+#[derive(Event)]
+struct RevertLevel;
+
+#[derive(Event)]
+struct SaveLevel {
+    target: SaveTarget,
+}
+
+/// Where to save the level to.
+enum SaveTarget {
+    Disk,
+
+    /// Keeps the level in memory, so its state is restored when returning to
+    /// it.
+    Memory,
+
+    /// Takes a snapshot to respawn from after dying, for the checkpoint at the
+    /// given position.
+    Checkpoint(Position),
+
+    /// Takes a snapshot the player can restore when an experiment goes wrong.
+    QuickSave,
+
+    /// Remembers the level the player is leaving unfinished, so they can
+    /// resume it from the hub, even after restarting the game.
+    InProgress,
+
+    /// Adds a snapshot to the move history, so the move can be rewound.
+    History,
+
+    /// Sets a savestate for practice mode, which is kept until the player
+    /// leaves the level.
+    Practice,
+}
+
+#[derive(Event)]
+struct SpawnObject {
+    object_type: ObjectType,
+    position: InitialPositionAndMetadata,
+}
+
+/// Launches the game, or runs one of the command-line tools instead.
+pub fn run() {
+    let cli_options = CliOptions::from_args();
+    if cli_options.validate {
+        std::process::exit(if validate_levels() { 0 } else { 1 });
+    }
+    if let Some((level, moves)) = &cli_options.simulate {
+        match simulate_level(*level, moves) {
+            Ok(result) => {
+                print!("{result}");
+                std::process::exit(if result.has_reached_exit { 0 } else { 1 });
+            }
+            Err(error) => {
+                println!("{error}");
+                std::process::exit(1);
+            }
+        }
+    }
+
+    install_panic_hook();
+
+    let settings = Settings::load();
+
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(LogPlugin {
+                    custom_layer: file_log_layer,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        title: WINDOW_TITLE.to_owned(),
+                        mode: get_initial_window_mode(&cli_options, &settings),
+                        position: settings.initial_window_position(),
+                        resolution: get_initial_window_resolution(&cli_options, &settings)
+                            .with_scale_factor_override(1.),
+                        desired_maximum_frame_latency: NonZero::new(60),
+                        ..default()
+                    }),
+                    ..default()
+                }),
+        )
+        .add_plugins((
+            AchievementsPlugin,
+            AttractModePlugin,
+            BackgroundPlugin,
+            ConsolePlugin,
+            CreditsPlugin,
+            CutscenePlugin,
+            DayNightPlugin,
+            DialoguePlugin,
+            EditorPlugin,
+            FastTravelPlugin,
+            GravesPlugin,
+            HotReloadPlugin,
+            InputDisplayPlugin,
+            InputPromptPlugin,
+            InventoryPlugin,
+        ))
+        .add_plugins((
+            JoystickPlugin,
+            LeaderboardPlugin,
+            LevelBannerPlugin,
+            LevelCompletePlugin,
+            MenuPlugin,
+            ModifiersPlugin,
+            OverworldPlugin,
+            PhotoModePlugin,
+            PracticeModePlugin,
+            RewindPlugin,
+            SaveErrorPlugin,
+            ScreenshotPlugin,
+            SettingsPlugin,
+            SignDialogPlugin,
+            SimulationPlugin,
+        ))
+        .add_plugins((
+            SkinsPlugin,
+            StreamerModePlugin,
+            TextInputPlugin,
+            ToastPlugin,
+            TouchGesturePlugin,
+            TutorialPlugin,
+            WeatherPlugin,
+        ))
+        .init_resource::<AnimationTimer>()
+        .init_resource::<ExitState>()
+        .init_resource::<Fonts>()
+        .init_resource::<GameObjectAssets>()
+        .init_resource::<GameOverSelection>()
+        .init_resource::<KeyMode>()
+        .init_resource::<Weather>()
+        .init_resource::<LevelCheckpoint>()
+        .init_resource::<LevelChecksum>()
+        .init_resource::<LevelWarnings>()
+        .init_resource::<Levels>()
+        .init_resource::<QuickSave>()
+        .init_resource::<RespawnTimer>()
+        .init_resource::<SpawnedLevel>()
+        .init_resource::<Theme>()
+        .init_resource::<UiState>()
+        .insert_resource(GameRng::new(cli_options.seed))
+        .insert_resource(cli_options)
+        .insert_resource(MovementRepeatTimer::new(
+            settings.movement_repeat_delay(),
+            settings.movement_repeat_interval(),
+        ))
+        .insert_resource(UiScale(settings.ui_scale))
+        .insert_resource(settings)
+        .insert_resource(GameState::load())
+        .add_event::<ChangeZoom>()
+        .add_event::<LoadLevel>()
+        .add_event::<LoadRelativeLevel>()
+        .add_event::<ResetLevel>()
+        .add_event::<RestartLevel>()
+        .add_event::<RevertLevel>()
+        .add_event::<SaveLevel>()
+        .add_event::<SpawnObject>()
+        .add_observer(load_level)
+        .add_observer(load_relative_level)
+        .add_observer(on_zoom_change)
+        .add_observer(discard_checkpoint::<LoadLevel>)
+        .add_observer(discard_checkpoint::<RestartLevel>)
+        .add_observer(reseed_rng::<LoadLevel>)
+        .add_observer(reseed_rng::<RestartLevel>)
+        .add_observer(reset_level)
+        .add_observer(restart_level)
+        .add_observer(restore_snapshot)
+        .add_observer(revert_level)
+        .add_observer(save_level)
+        .add_observer(spawn_object)
+        .add_systems(PreStartup, update_hub_levels)
+        .add_systems(PreUpdate, update_hub_levels)
+        .add_systems(Startup, (set_window_icon, setup))
+        .add_systems(PostStartup, post_setup)
+        .add_systems(
+            Update,
+            (
+                on_keyboard_input,
+                on_mouse_input,
+                on_mouse_wheel,
+                on_resize,
+                update_window_title,
+            ),
+        )
+        .add_systems(Update, apply_drag_inertia.after(on_mouse_input))
+        .add_systems(Update, on_camera_pan_input.after(on_keyboard_input))
+        .add_systems(
+            Update,
+            repeat_held_moves
+                .after(on_keyboard_input)
+                .before(on_game_event),
+        )
+        .add_systems(
+            Update,
+            (
+                animate_objects,
+                apply_variations,
+                check_for_entrance,
+                check_for_exit,
+                on_game_over_input,
+            )
+                .after(on_keyboard_input),
+        )
+        .add_systems(
+            Update,
+            (
+                check_for_game_over,
+                show_death_recap,
+                flash_killer_highlight,
+                render_game_over_buttons.after(on_game_over_input),
+            )
+                .chain()
+                .after(check_for_deadly)
+                .after(check_for_explosive)
+                .after(check_for_liquid),
+        )
+        .add_systems(
+            Update,
+            (
+                check_for_checkpoint,
+                check_for_finished_levels,
+                on_player_moved,
+                reveal_decorations,
+                update_best_moves_labels,
+                update_checkpoint_sprites,
+            )
+                .after(move_objects)
+                .after(on_mouse_input),
+        )
+        .add_systems(Update, spawn_pending_level_objects)
+        .add_systems(
+            Update,
+            (
+                position_entities,
+                update_autotiles,
+                update_entity_directions,
+            )
+                .after(spawn_pending_level_objects)
+                .after(check_for_explosive)
+                .after(check_for_liquid)
+                .after(check_for_paint)
+                .after(check_for_slippery)
+                .after(check_for_teleporter)
+                .after(check_for_transform_on_push),
+        )
+        .run();
+}
+
+/// Plays a built-in level with the given moves.
+fn simulate_level(level: u16, moves: &[Direction]) -> Result<SimulationResult, String> {
+    let content = builtin_level(level).ok_or_else(|| format!("Unknown level: {level}"))?;
+    let level =
+        Level::load(content).map_err(|error| format!("Can't load level {level}: {error}"))?;
+    Ok(simulate(level, moves))
+}
+
+fn get_initial_window_mode(cli_options: &CliOptions, settings: &Settings) -> WindowMode {
+    if let Some(window_mode) = cli_options.window_mode {
+        window_mode
+    } else if cfg!(target_os = "ios") || std::env::var_os("SteamTenfoot").is_some() {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        settings.initial_window_mode()
+    }
+}
+
+fn get_initial_window_resolution(
+    cli_options: &CliOptions,
+    settings: &Settings,
+) -> WindowResolution {
+    match (cli_options.resolution, settings.window_size) {
+        (Some((width, height)), _) => WindowResolution::from((width, height)),
+        (None, Some((width, height))) => WindowResolution::new(width as f32, height as f32),
+        (None, None) => WindowResolution::from((DEFAULT_WINDOW_SIZE, DEFAULT_WINDOW_SIZE)),
+    }
+}
+
+fn set_window_icon(windows: NonSend<WinitWindows>) {
+    let (icon_rgba, icon_width, icon_height) = {
+        let image = image::load_from_memory_with_format(PLAYER_ASSET, image::ImageFormat::Png)
+            .unwrap()
+            .into_rgba8();
+        let (width, height) = image.dimensions();
+        let rgba = image.into_raw();
+        (rgba, width, height)
+    };
+    let icon = Icon::from_rgba(icon_rgba, icon_width, icon_height).unwrap();
+    for window in windows.windows.values() {
+        window.set_window_icon(Some(icon.clone()));
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut fonts: ResMut<Fonts>,
+    mut font_assets: ResMut<Assets<Font>>,
+    mut game_object_assets: ResMut<GameObjectAssets>,
+    mut image_assets: ResMut<Assets<Image>>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    settings: Res<Settings>,
+) {
+    *game_object_assets.as_mut() = GameObjectAssets::load(
+        &mut image_assets,
+        &mut texture_atlas_layouts,
+        settings.sprite_theme.as_deref(),
+    );
+
+    fonts.poppins_light = font_assets.add(
+        Font::try_from_bytes(Vec::from(include_bytes!(
+            "../assets/font/Poppins/Poppins-Light.ttf"
+        )))
+        .unwrap(),
+    );
+
+    commands.spawn(Camera2d);
+
+    setup_gameover(&mut commands, &fonts);
+}
+
+fn post_setup(
+    mut commands: Commands,
+    mut menu_state: ResMut<MenuState>,
+    cli_options: Res<CliOptions>,
+) {
+    let level = cli_options.level.unwrap_or_default();
+    if level != 0 {
+        // Skip the hub menu when launching straight into a level.
+        menu_state.close();
+    }
+
+    commands.trigger(LoadLevel(level));
+}
+
+#[expect(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn on_mouse_input(
+    mut commands: Commands,
+    selection_query: Query<&mut Transform, With<SelectionOverlay>>,
+    background_query: Query<(Entity, &Transform), (With<Background>, Without<SelectionOverlay>)>,
+    objects: Query<(Entity, &ObjectType, &Position)>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    editor_state: ResMut<EditorState>,
+    mut ui_state: ResMut<UiState>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    dimensions: Res<Dimensions>,
+    menu_state: Res<MenuState>,
+    time: Res<Time>,
+    ui_scale: Res<UiScale>,
+) {
+    if editor_state.is_open {
+        on_editor_mouse_input(
+            commands,
+            selection_query,
+            background_query,
+            objects,
+            window_query,
+            editor_state,
+            buttons,
+            dimensions,
+            ui_scale,
+        );
+        return;
+    } else if menu_state.is_open() || credits_state.is_playing() || cutscene_state.is_playing() {
+        return;
+    }
+
+    if !buttons.pressed(MouseButton::Left) {
+        if ui_state.drag_start.is_some() {
+            ui_state.drag_start = None;
+        }
+        return;
+    }
+
+    let window = window_query.single();
+    let Some(cursor_position) = window.cursor_position() else {
+        return;
+    };
+
+    let zoom_factor = ui_state.zoom_factor;
+    let x = cursor_position.x / (zoom_factor * GRID_SIZE as f32);
+    let y = cursor_position.y / (zoom_factor * GRID_SIZE as f32);
+
+    if let Some((start_x, start_y)) = ui_state.drag_start {
+        let new_camera_offset = (start_x - x, start_y - y);
+        if ui_state.camera_offset != new_camera_offset {
+            ui_state.camera_offset.0 += new_camera_offset.0;
+            ui_state.camera_offset.1 += new_camera_offset.1;
+            commands.send_event(UpdateBackgroundTransform::Fast);
+        }
+
+        // The velocity is smoothed, so a single jittery frame right before
+        // the release doesn't decide where the camera glides to.
+        let delta_secs = time.delta_secs();
+        if delta_secs > 0. {
+            let (velocity_x, velocity_y) = ui_state.drag_velocity;
+            ui_state.drag_velocity = (
+                0.5 * (velocity_x + new_camera_offset.0 / delta_secs),
+                0.5 * (velocity_y + new_camera_offset.1 / delta_secs),
+            );
+        }
+    } else {
+        ui_state.drag_velocity = (0., 0.);
+    }
+
+    ui_state.drag_start = Some((x, y));
+}
+
+/// Lets the camera glide on after a drag is released, slowing down until it
+/// comes to a stop.
+fn apply_drag_inertia(mut commands: Commands, mut ui_state: ResMut<UiState>, time: Res<Time>) {
+    let (velocity_x, velocity_y) = ui_state.drag_velocity;
+    if ui_state.drag_start.is_some() || (velocity_x, velocity_y) == (0., 0.) {
+        return;
+    }
+
+    if velocity_x.hypot(velocity_y) < MIN_DRAG_VELOCITY {
+        ui_state.drag_velocity = (0., 0.);
+        return;
+    }
+
+    let delta_secs = time.delta_secs();
+    ui_state.camera_offset.0 += velocity_x * delta_secs;
+    ui_state.camera_offset.1 += velocity_y * delta_secs;
+
+    let decay = (-DRAG_DECELERATION * delta_secs).exp();
+    ui_state.drag_velocity = (velocity_x * decay, velocity_y * decay);
+    commands.send_event(UpdateBackgroundTransform::Fast);
+}
+
+#[expect(clippy::too_many_arguments)]
+fn on_keyboard_input(
+    mut commands: Commands,
+    mut game_events: EventWriter<GameEvent>,
+    app_exit_events: EventWriter<AppExit>,
+    mut menu_state: ResMut<MenuState>,
+    editor_state: ResMut<EditorState>,
+    ui_state: ResMut<UiState>,
+    credits_state: Res<CreditsState>,
+    exit_state: Res<ExitState>,
+    game_state: Res<GameState>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if editor_state.is_open {
+        on_editor_keyboard_input(commands, editor_state, ui_state, keys);
+        return;
+    } else if menu_state.is_open() {
+        on_menu_keyboard_input(commands, app_exit_events, menu_state, keys);
+        return;
+    } else if exit_state.next_level.is_some() || credits_state.is_playing() {
+        return;
+    }
+
+    // Holding Shift pulls the object behind the player along.
+    let move_event = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        GameEvent::PullPlayer
+    } else {
+        GameEvent::MovePlayer
+    };
+
+    let is_panning = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+
+    for key in keys.get_just_pressed() {
+        if is_panning && CAMERA_PAN_KEYS.iter().any(|(pan_key, _)| pan_key == key) {
+            continue;
+        }
+
+        if let Some((dx, dy)) = direction_for_key(*key) {
+            game_events.send(move_event(dx, dy));
+            continue;
+        }
+
+        use KeyCode::*;
+        match key {
+            Equal => {
+                commands.trigger(ChangeZoom {
+                    factor: 1.25,
+                    cursor_position: None,
+                });
+            }
+            Minus => {
+                commands.trigger(ChangeZoom {
+                    factor: 0.8,
+                    cursor_position: None,
+                });
+            }
+            BracketRight => {
+                commands.trigger(LoadRelativeLevel(1));
+            }
+            BracketLeft => {
+                commands.trigger(LoadRelativeLevel(-1));
+            }
+            KeyE => {
+                commands.trigger(ToggleEditor);
+            }
+            KeyM => {
+                commands.trigger(OpenFastTravel);
+            }
+            KeyO => {
+                commands.trigger(OpenOverworld);
+            }
+            KeyP => {
+                commands.trigger(TogglePracticeMode);
+            }
+            KeyR => {
+                commands.trigger(LoadRelativeLevel(0));
+            }
+            F5 => {
+                commands.trigger(SaveLevel {
+                    target: SaveTarget::QuickSave,
+                });
+            }
+            F9 => {
+                commands.trigger(RestoreSnapshot::QuickSave);
+            }
+            F8 => {
+                commands.trigger(ToggleStreamerMode);
+            }
+            Escape => {
+                menu_state.set_open(if game_state.is_in_hub() {
+                    MenuKind::Hub
+                } else {
+                    MenuKind::Level
+                });
+            }
+
+            _ => continue,
+        };
+    }
+}
+
+/// Keeps moving the player while a direction key is held, at the rate that is
+/// configured in the settings. Only the direction that was pressed last is
+/// repeated.
+#[expect(clippy::too_many_arguments)]
+fn repeat_held_moves(
+    mut game_events: EventWriter<GameEvent>,
+    mut timer: ResMut<MovementRepeatTimer>,
+    mut held_key: Local<Option<KeyCode>>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    dialogue_state: Res<DialogueState>,
+    editor_state: Res<EditorState>,
+    exit_state: Res<ExitState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<MenuState>,
+    time: Res<Time>,
+) {
+    if editor_state.is_open
+        || menu_state.is_open()
+        || exit_state.next_level.is_some()
+        || credits_state.is_playing()
+        || cutscene_state.is_playing()
+        || dialogue_state.is_open()
+        || keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+    {
+        *held_key = None;
+        return;
+    }
+
+    if let Some(key) = keys
+        .get_just_pressed()
+        .find(|key| direction_for_key(**key).is_some())
+    {
+        // The first move is made by `on_keyboard_input`.
+        *held_key = Some(*key);
+        timer.restart();
+        return;
+    }
+
+    let Some(key) = *held_key else {
+        return;
+    };
+    if !keys.pressed(key) {
+        *held_key = None;
+        return;
+    }
+
+    if timer.tick(time.delta()) {
+        if let Some((dx, dy)) = direction_for_key(key) {
+            game_events.send(
+                if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                    GameEvent::PullPlayer(dx, dy)
+                } else {
+                    GameEvent::MovePlayer(dx, dy)
+                },
+            );
+        }
+    }
+}
+
+/// Lets the player look around the level by holding Alt while pressing W, A,
+/// S or D. The camera pans back to the player as soon as they move.
+#[expect(clippy::too_many_arguments)]
+fn on_camera_pan_input(
+    mut commands: Commands,
+    mut ui_state: ResMut<UiState>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    editor_state: Res<EditorState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<MenuState>,
+    time: Res<Time>,
+) {
+    if editor_state.is_open
+        || menu_state.is_open()
+        || credits_state.is_playing()
+        || cutscene_state.is_playing()
+        || !keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+    {
+        return;
+    }
+
+    let mut direction = Vec2::ZERO;
+    for (key, delta) in CAMERA_PAN_KEYS {
+        if keys.pressed(key) {
+            direction += delta;
+        }
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    let delta = direction.normalize() * CAMERA_PAN_SPEED * time.delta_secs();
+    ui_state.camera_offset.0 += delta.x;
+    ui_state.camera_offset.1 += delta.y;
+    ui_state.drag_velocity = (0., 0.);
+    commands.send_event(UpdateBackgroundTransform::Fast);
+}
+
+/// Returns the direction the player moves in when the given key is pressed.
+///
+/// Besides the arrow keys, WASD and the HJKL keys of vi move the player too.
+/// While practice mode is on, its own use of S and L takes precedence.
+///
+/// The numpad moves the player as well. Its corners move diagonally, which
+/// only has an effect in levels with [DiagonalMovement].
+fn direction_for_key(key: KeyCode) -> Option<(i16, i16)> {
+    match key {
+        KeyCode::ArrowUp | KeyCode::KeyW | KeyCode::KeyK | KeyCode::Numpad8 => Some((0, -1)),
+        KeyCode::ArrowRight | KeyCode::KeyD | KeyCode::KeyL | KeyCode::Numpad6 => Some((1, 0)),
+        KeyCode::ArrowDown | KeyCode::KeyS | KeyCode::KeyJ | KeyCode::Numpad2 => Some((0, 1)),
+        KeyCode::ArrowLeft | KeyCode::KeyA | KeyCode::KeyH | KeyCode::Numpad4 => Some((-1, 0)),
+        KeyCode::Numpad9 => Some((1, -1)),
+        KeyCode::Numpad3 => Some((1, 1)),
+        KeyCode::Numpad1 => Some((-1, 1)),
+        KeyCode::Numpad7 => Some((-1, -1)),
+        _ => None,
+    }
+}
+
+/// Places entities at their positions, gliding them over if they are sliding.
+fn position_entities(
+    mut commands: Commands,
+    mut query: Query<(Entity, Ref<Position>, &mut Transform, Option<&mut Glide>)>,
+    dimensions: Res<Dimensions>,
+    time: Res<Time>,
+) {
+    for (entity, position, mut transform, glide) in &mut query {
+        if !position.is_changed() && !dimensions.is_changed() && glide.is_none() {
+            continue;
+        }
+
+        let target = dimensions.translation_of(*position);
+
+        let translation = match glide {
+            Some(mut glide) if !dimensions.is_changed() => {
+                if position.is_changed() || glide.from.is_none() {
+                    glide.from = Some(transform.translation.truncate());
+                    glide.timer.reset();
+                }
+                glide.timer.tick(time.delta());
+                if glide.timer.finished() {
+                    commands.entity(entity).remove::<Glide>();
+                }
+
+                // Entities that got teleported jump there right away.
+                let from = glide
+                    .from
+                    .filter(|from| from.distance(target) <= 1.5 * GRID_SIZE as f32)
+                    .unwrap_or(target);
+                from.lerp(target, glide.timer.fraction())
+            }
+            _ => target,
+        };
+        transform.translation.x = translation.x;
+        transform.translation.y = translation.y;
+    }
+}
+
+fn update_entity_directions(
+    mut query: Query<(&Direction, &ObjectType, &mut Sprite), Changed<Direction>>,
+    assets: Res<GameObjectAssets>,
+) {
+    for (direction, object_type, mut sprite) in &mut query {
+        assets
+            .for_object_type(*object_type)
+            .set_frame(&mut sprite, direction.nearest_cardinal() as usize);
+    }
+}
+
+type PlayerComponents<'a> = (
+    Entity,
+    &'a mut Position,
+    Option<&'a mut Direction>,
+    Option<&'a Weight>,
+);
+
+#[expect(clippy::too_many_arguments)]
+fn on_game_event(
+    mut commands: Commands,
+    mut level_events: EventReader<GameEvent>,
+    mut collision_objects_query: Query<CollisionObjectQuery, Without<Player>>,
+    mut player_query: Query<PlayerComponents, With<Player>>,
+    mut game_state: ResMut<GameState>,
+    mut tile_index: ResMut<TileIndex>,
+    mut ui_state: ResMut<UiState>,
+    diagonal_movement: Res<DiagonalMovement>,
+    dimensions: Res<Dimensions>,
+    mirrored_controls: Res<MirroredControls>,
+    push_strength: Res<PushStrength>,
+) {
+    for event in level_events.read() {
+        match event {
+            GameEvent::MovePlayer(dx, dy) | GameEvent::PullPlayer(dx, dy) => {
+                if *dx != 0 && *dy != 0 && !diagonal_movement.0 {
+                    continue;
+                }
+
+                let (dx, dy) = &mirrored_controls.apply(&game_state, (*dx, *dy));
+                if let Ok((player, mut position, player_direction, weight)) =
+                    player_query.get_single_mut()
+                {
+                    ui_state.camera_offset = Default::default();
+                    ui_state.drag_velocity = Default::default();
+
+                    let weight = weight.copied().unwrap_or_default();
+                    push_chain(
+                        *position,
+                        (*dx, *dy),
+                        &dimensions,
+                        &mut collision_objects_query,
+                        &mut tile_index,
+                        weight,
+                        push_strength.0,
+                    );
+
+                    let move_or_pull = if matches!(event, GameEvent::PullPlayer(..)) {
+                        pull_object
+                    } else {
+                        move_object
+                    };
+                    if let Ok(new_position) = move_or_pull(
+                        player,
+                        *position,
+                        (*dx, *dy),
+                        &dimensions,
+                        &mut collision_objects_query,
+                        &mut tile_index,
+                        weight,
+                    ) {
+                        *position = new_position;
+                        game_state.record_move();
+
+                        if let Ok(direction) = Direction::try_from((*dx, *dy)) {
+                            if let Some(mut player_direction) = player_direction {
+                                *player_direction = direction;
+                            } else {
+                                commands.entity(player).insert(direction);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn on_player_moved(mut commands: Commands, query: Query<Ref<Position>, With<Player>>) {
+    for player_position in &query {
+        if player_position.is_changed() {
+            commands.send_event(UpdateBackgroundTransform::Fast);
+        }
+    }
+}
+
+/// Keeps track of which levels are hubs, as levels can be changed from the
+/// editor.
+fn update_hub_levels(mut game_state: ResMut<GameState>, levels: Res<Levels>) {
+    if !levels.is_changed() {
+        return;
+    }
+
+    let hub_levels = levels.hubs();
+    if game_state.hub_levels != hub_levels {
+        game_state.hub_levels = hub_levels;
+    }
+}
+
+/// Shows the current level in the window title, with an asterisk while the
+/// editor has unsaved changes.
+fn update_window_title(
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    editor_state: Res<EditorState>,
+    game_state: Res<GameState>,
+) {
+    if !editor_state.is_changed() && !game_state.is_changed() {
+        return;
+    }
+
+    let location = if game_state.current_level == 0 {
+        "Hub".to_owned()
+    } else if game_state.is_in_hub() {
+        format!("Hub {}", game_state.current_level)
+    } else {
+        format!("Level {}", game_state.current_level)
+    };
+    let unsaved_marker = if editor_state.has_unsaved_changes {
+        "*"
+    } else {
+        ""
+    };
+    let title = format!("{WINDOW_TITLE} — {location}{unsaved_marker}");
+
+    for mut window in &mut window_query {
+        if window.title != title {
+            window.title = title.clone();
+        }
+    }
+}
+
+fn on_resize(mut commands: Commands, mut resize_reader: EventReader<WindowResized>) {
+    if resize_reader.read().last().is_some() {
+        commands.send_event(UpdateBackgroundTransform::Immediate);
+    }
+}
+
+/// Zooms in or out using the mouse wheel, or by pinching on a trackpad.
+fn on_mouse_wheel(
+    mut commands: Commands,
+    mut pinch_events: EventReader<PinchGesture>,
+    mut wheel_events: EventReader<MouseWheel>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    menu_state: Res<MenuState>,
+) {
+    if menu_state.is_open() || credits_state.is_playing() || cutscene_state.is_playing() {
+        wheel_events.clear();
+        pinch_events.clear();
+        return;
+    }
+
+    let mut factor = 1.;
+    for event in wheel_events.read() {
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y,
+            MouseScrollUnit::Pixel => event.y / 50.,
+        };
+        factor *= 1.25f32.powf(lines);
+    }
+    for PinchGesture(delta) in pinch_events.read() {
+        factor *= 1. + delta;
+    }
+
+    if factor != 1. {
+        commands.trigger(ChangeZoom {
+            factor,
+            cursor_position: window_query
+                .get_single()
+                .ok()
+                .and_then(Window::cursor_position),
+        });
+    }
+}
+
+fn on_zoom_change(
+    trigger: Trigger<ChangeZoom>,
+    mut commands: Commands,
+    mut ui_state: ResMut<UiState>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    editor_state: Res<EditorState>,
+    photo_mode: Res<PhotoMode>,
+    ui_scale: Res<UiScale>,
+) {
+    let ChangeZoom {
+        factor,
+        cursor_position,
+    } = trigger.event();
+
+    // Photo mode allows zooming further, for close-ups and overviews.
+    let (min_zoom_factor, max_zoom_factor) = if photo_mode.is_active() {
+        (0.05, 20.)
+    } else {
+        (0.2, 5.)
+    };
+
+    let zoom_factor = ui_state.zoom_factor;
+    if (*factor < 1. && zoom_factor >= min_zoom_factor)
+        || (*factor > 1. && zoom_factor <= max_zoom_factor)
+    {
+        let new_zoom_factor = zoom_factor * factor;
+        ui_state.zoom_factor = new_zoom_factor;
+
+        if let (Some(cursor_position), Ok(window)) = (cursor_position, window_query.get_single()) {
+            // Offset of the cursor from the center of the level area, in the
+            // same direction as the camera offset.
+            let x = cursor_position.x - 0.5 * (window.width() - editor_state.width(&ui_scale));
+            let y = cursor_position.y - 0.5 * window.height();
+
+            let grid_size = GRID_SIZE as f32;
+            ui_state.camera_offset.0 += (x / zoom_factor - x / new_zoom_factor) / grid_size;
+            ui_state.camera_offset.1 += (y / zoom_factor - y / new_zoom_factor) / grid_size;
+        }
+
+        commands.send_event(UpdateBackgroundTransform::Fast);
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn load_level(
+    trigger: Trigger<LoadLevel>,
+    mut commands: Commands,
+    mut background_query: Query<Entity, With<Background>>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut dimensions: ResMut<Dimensions>,
+    mut exit_state: ResMut<ExitState>,
+    mut game_state: ResMut<GameState>,
+    mut pressed_triggers: ResMut<PressedTriggers>,
+    mut spawned_level: ResMut<SpawnedLevel>,
+    mut theme: ResMut<Theme>,
+    mut tile_index: ResMut<TileIndex>,
+    assets: Res<GameObjectAssets>,
+    fonts: Res<Fonts>,
+    levels: Res<Levels>,
+    menu_state: Res<MenuState>,
+    time: Res<Time>,
+) {
+    let LoadLevel(level) = trigger.event();
+    game_state.set_current_level(*level);
+    game_state.start_attempt(time.elapsed());
+    spawned_level.level = *level;
+
+    // A level the player left unfinished resumes where they left off.
+    let level_in_progress = game_state.take_level_in_progress(*level);
+    let level_data = level_in_progress
+        .as_deref()
+        .or_else(|| levels.get(*level))
+        .unwrap_or(PLACEHOLDER_LEVEL);
+
+    let (level_data, mut level) = match Level::load(level_data) {
+        Ok(parsed_level) => (level_data, parsed_level),
+        Err(error) => {
+            error!("Can't load level {level}: {error}");
+            commands.trigger(ShowToast::new(format!(
+                "Level {level} is corrupted and can't be loaded"
+            )));
+            builtin_level(*level)
+                .and_then(|fallback| Some((fallback, Level::load(fallback).ok()?)))
+                .unwrap_or_else(|| {
+                    let placeholder =
+                        Level::load(PLACEHOLDER_LEVEL).expect("placeholder level should be valid");
+                    (PLACEHOLDER_LEVEL, placeholder)
+                })
+        }
+    };
+    for warning in &level.warnings {
+        warn!("{warning}");
+    }
+
+    // A level that is resumed counts as the level it was started from.
+    let checksum = match level_in_progress.as_deref() {
+        Some(in_progress) if in_progress == level_data => LevelChecksum::of(
+            levels
+                .get(game_state.current_level)
+                .unwrap_or(PLACEHOLDER_LEVEL),
+        ),
+        _ => LevelChecksum::of(level_data),
+    };
+    commands.insert_resource(checksum);
+
+    // Levels are stored with their original entrances, which the randomizer
+    // only shuffles once loaded.
+    if let Some(randomizer) = &game_state.randomizer {
+        let entrances = level.objects.get_mut(&ObjectType::Entrance);
+        for entrance in entrances.into_iter().flatten() {
+            entrance.level = entrance
+                .level
+                .map(|level| randomizer.level_for_entrance(level));
+        }
+    }
+
+    let warnings = LevelWarnings(std::mem::take(&mut level.warnings));
+    if let Some(message) = warnings.toast_message(game_state.current_level) {
+        commands.trigger(ShowToast::new(message));
+    }
+    commands.insert_resource(warnings);
+
+    // If we come from a previous level, we check if the new level has an
+    // entrance to the previous level. If it does, it will be the player's
+    // starting position instead of the one specified by the level.
+    if let Some(previous_level) = game_state.previous_level {
+        let entrance_position = level
+            .objects
+            .get(&ObjectType::Entrance)
+            .and_then(|entrances| {
+                entrances
+                    .iter()
+                    .find(|entrance| entrance.level == Some(previous_level))
+            })
+            .map(|entrance| entrance.position);
+
+        if let Some(entrance_position) = entrance_position {
+            if let Some(players) = level.objects.get_mut(&ObjectType::Player) {
+                for player in players {
+                    player.position = entrance_position;
+                }
+            }
+        }
+    }
+
+    spawned_level.contents = level_data.to_owned();
+    spawned_level.objects = level
+        .objects
+        .into_iter()
+        .flat_map(|(object_type, initial_positions)| {
+            initial_positions
+                .into_iter()
+                .map(move |initial_position| (object_type, initial_position))
+        })
+        .collect();
+
+    spawned_level.queue_objects();
+    let is_in_chunk = spawned_level.next_chunk();
+
+    let background_entity = background_query.single_mut();
+    let mut background = commands.entity(background_entity);
+    background.despawn_descendants();
+    tile_index.clear();
+    background.with_children(|cb| {
+        spawn_level_objects(
+            cb,
+            &spawned_level.objects,
+            |index| is_in_chunk[index],
+            &mut tile_index,
+            &assets,
+            &fonts,
+        );
+    });
+
+    pressed_triggers.num_pressed_triggers.clear();
+
+    *dimensions = level.dimensions;
+    theme.set_if_neq(level.theme);
+    commands.insert_resource(level.key_mode);
+    commands.insert_resource(level.push_strength);
+    commands.insert_resource(level.diagonal_movement);
+    commands.insert_resource(level.weather);
+    commands.insert_resource(level.inventory);
+
+    exit_state.next_level = None;
+
+    background_events.send(if menu_state.is_in_hub_menu() {
+        UpdateBackgroundTransform::Immediate
+    } else {
+        UpdateBackgroundTransform::LevelEntrance
+    });
+}
+
+fn load_relative_level(
+    trigger: Trigger<LoadRelativeLevel>,
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    levels: Res<Levels>,
+    spawned_level: Res<SpawnedLevel>,
+) {
+    let LoadRelativeLevel(delta) = trigger.event();
+    let new_level = game_state.current_level.saturating_add_signed(*delta);
+
+    if new_level == spawned_level.level
+        && levels.get(new_level) == Some(spawned_level.contents.as_str())
+    {
+        commands.trigger(RestartLevel);
+    } else {
+        commands.trigger(LoadLevel(new_level));
+    }
+}
+
+#[expect(clippy::too_many_arguments, clippy::type_complexity)]
+fn restart_level(
+    _trigger: Trigger<RestartLevel>,
+    mut commands: Commands,
+    mut objects_query: Query<(
+        Entity,
+        Option<&ObjectType>,
+        Option<&LevelObject>,
+        Option<&mut Position>,
+        Option<&mut Direction>,
+        Option<&mut BlocksMovement>,
+        Option<&mut Transporter>,
+    )>,
+    background_query: Query<(Entity, &Children), With<Background>>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut exit_state: ResMut<ExitState>,
+    mut game_state: ResMut<GameState>,
+    mut pressed_triggers: ResMut<PressedTriggers>,
+    assets: Res<GameObjectAssets>,
+    fonts: Res<Fonts>,
+    menu_state: Res<MenuState>,
+    mut spawned_level: ResMut<SpawnedLevel>,
+    mut tile_index: ResMut<TileIndex>,
+    time: Res<Time>,
+) {
+    let level = game_state.current_level;
+    game_state.set_current_level(level);
+    game_state.start_attempt(time.elapsed());
+
+    let (background, children) = background_query
+        .get_single()
+        .expect("there should be only one background");
+
+    // Objects that can be reset in place are left alone, everything else that
+    // was spawned into the level is despawned and, if it was part of the
+    // level, respawned.
+    // Objects that were still waiting to be spawned are spawned along with
+    // the ones that get respawned.
+    spawned_level.pending.clear();
+    let mut restarted = vec![false; spawned_level.objects.len()];
+    let mut objects = objects_query.iter_many_mut(children);
+    while let Some((
+        entity,
+        object_type,
+        level_object,
+        position,
+        direction,
+        blocks_movement,
+        transporter,
+    )) = objects.fetch_next()
+    {
+        let initial_position = level_object.and_then(|LevelObject(index)| {
+            spawned_level
+                .objects
+                .get(*index)
+                .map(|(_, initial_position)| (*index, initial_position))
+        });
+
+        match (initial_position, position) {
+            (Some((index, initial_position)), Some(mut position))
+                if object_type.is_some_and(|object_type| object_type.can_restart_in_place()) =>
+            {
+                position.set_if_neq(initial_position.position);
+                tile_index.insert(entity, initial_position.position);
+                if let Some(mut direction) = direction {
+                    direction.set_if_neq(initial_position.direction.unwrap_or_default());
+                }
+                if let Some(mut blocks_movement) = blocks_movement {
+                    blocks_movement.set_if_neq(BlocksMovement::Enabled);
+                }
+                if let Some(mut transporter) = transporter {
+                    transporter.reset();
+                }
+                restarted[index] = true;
+            }
+            _ => {
+                commands.entity(entity).despawn_recursive();
+                tile_index.remove(entity);
+            }
+        }
+    }
+
+    commands.entity(background).with_children(|cb| {
+        spawn_level_objects(
+            cb,
+            &spawned_level.objects,
+            |index| !restarted[index],
+            &mut tile_index,
+            &assets,
+            &fonts,
+        );
+    });
+
+    pressed_triggers.num_pressed_triggers.clear();
+
+    exit_state.next_level = None;
+
+    background_events.send(if menu_state.is_in_hub_menu() {
+        UpdateBackgroundTransform::Immediate
+    } else {
+        UpdateBackgroundTransform::LevelEntrance
+    });
+}
+
+/// Discards the reached checkpoint whenever the level starts over.
+fn discard_checkpoint<E: Event>(_trigger: Trigger<E>, mut checkpoint: ResMut<LevelCheckpoint>) {
+    if checkpoint.position.is_some() {
+        *checkpoint = LevelCheckpoint::default();
+    }
+}
+
+/// Respawns all objects from a snapshot of the level.
+///
+/// The respawned objects are not [LevelObject]s, so restarting the level
+/// afterwards still respawns all the objects as they were in the level.
+#[expect(clippy::too_many_arguments)]
+fn restore_snapshot(
+    trigger: Trigger<RestoreSnapshot>,
+    mut commands: Commands,
+    background_query: Query<Entity, With<Background>>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut pressed_triggers: ResMut<PressedTriggers>,
+    mut tile_index: ResMut<TileIndex>,
+    assets: Res<GameObjectAssets>,
+    checkpoint: Res<LevelCheckpoint>,
+    fonts: Res<Fonts>,
+    game_state: Res<GameState>,
+    history: Res<MoveHistory>,
+    practice_mode: Res<PracticeMode>,
+    quick_save: Res<QuickSave>,
+) {
+    let contents = match trigger.event() {
+        RestoreSnapshot::Checkpoint if checkpoint.position.is_some() => &checkpoint.contents,
+        RestoreSnapshot::Checkpoint => return,
+        RestoreSnapshot::QuickSave if quick_save.level == Some(game_state.current_level) => {
+            commands.trigger(ShowToast::new("Quick save loaded"));
+            &quick_save.contents
+        }
+        RestoreSnapshot::QuickSave => {
+            commands.trigger(ShowToast::new("There is no quick save for this level"));
+            return;
+        }
+        RestoreSnapshot::History => match history.latest() {
+            Some(contents) => contents,
+            None => return,
+        },
+        RestoreSnapshot::Practice => match practice_mode.savestate() {
+            Some(contents) => {
+                commands.trigger(ShowToast::new("Savestate loaded"));
+                contents
+            }
+            None => {
+                commands.trigger(ShowToast::new("There is no savestate yet"));
+                return;
+            }
+        },
+    };
+
+    let level = match Level::load(contents) {
+        Ok(level) => level,
+        Err(error) => {
+            error!("Can't restore snapshot: {error}");
+            return;
+        }
+    };
+    commands.insert_resource(level.inventory);
+
+    let background = background_query
+        .get_single()
+        .expect("there should be only one background");
+    let mut background = commands.entity(background);
+    background.despawn_descendants();
+    tile_index.clear();
+    background.with_children(|cb| {
+        for (object_type, initial_positions) in level.objects {
+            for initial_position in initial_positions {
+                let position = initial_position.position;
+                let entity =
+                    spawn_object_of_type(cb, &assets, &fonts, object_type, initial_position).id();
+                tile_index.insert(entity, position);
+            }
+        }
+    });
+
+    pressed_triggers.num_pressed_triggers.clear();
+
+    background_events.send(UpdateBackgroundTransform::Fast);
+}
+
+fn reset_level(
+    _trigger: Trigger<ResetLevel>,
+    mut game_state: ResMut<GameState>,
+    mut levels: ResMut<Levels>,
+) {
+    let level = game_state.current_level;
+    levels.reset_level(level);
+    game_state.set_current_level(level);
+}
+
+fn revert_level(
+    _trigger: Trigger<RevertLevel>,
+    mut commands: Commands,
+    mut levels: ResMut<Levels>,
+    game_state: Res<GameState>,
+) {
+    let level = game_state.current_level;
+    if builtin_level(level).is_none() {
+        commands.trigger(ShowToast::new("This level isn't built into the game"));
+        return;
+    }
+
+    match fs::remove_file(get_user_level_path(level)) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+        Err(error) => {
+            error!("Could not revert level: {error}");
+            commands.trigger(ShowToast::new(format!("Could not revert level: {error}")));
+            return;
+        }
+    }
+
+    levels.revert_to_builtin(level);
+    commands.trigger(LoadLevel(level));
+    commands.trigger(ShowToast::new("Level reverted to the built-in version"));
+}
+
+#[expect(clippy::too_many_arguments, clippy::type_complexity)]
+fn save_level(
+    trigger: Trigger<SaveLevel>,
+    mut commands: Commands,
+    mut checkpoint: ResMut<LevelCheckpoint>,
+    mut editor_state: ResMut<EditorState>,
+    mut game_state: ResMut<GameState>,
+    mut history: ResMut<MoveHistory>,
+    mut levels: ResMut<Levels>,
+    mut practice_mode: ResMut<PracticeMode>,
+    mut quick_save: ResMut<QuickSave>,
+    dimensions: Res<Dimensions>,
+    inventory: Res<Inventory>,
+    (key_mode, push_strength, diagonal_movement): (
+        Res<KeyMode>,
+        Res<PushStrength>,
+        Res<DiagonalMovement>,
+    ),
+    spawned_level: Res<SpawnedLevel>,
+    theme: Res<Theme>,
+    weather: Res<Weather>,
+    objects_query: Query<(
+        &ObjectType,
+        &Position,
+        Option<&Bridge>,
+        (Option<&Decoration>, Option<&RevealedByLevel>),
+        Option<&Direction>,
+        Option<&Entrance>,
+        Option<&Exit>,
+        Option<&Massive>,
+        Option<&Npc>,
+        Option<&Openable>,
+        Option<&Sign>,
+        Option<&Teleporter>,
+        Option<&TimerGate>,
+        Option<&Transporter>,
+        Option<&game_object::Trigger>,
+    )>,
+) {
+    let SaveLevel { target } = trigger.event();
+
+    // Levels saved to disk start their countdowns from the beginning, while
+    // snapshots keep the time that is left.
+    let keeps_countdowns = !matches!(target, SaveTarget::Disk);
+
+    let mut objects = BTreeMap::new();
+    for (
+        object_type,
+        position,
+        bridge,
+        (decoration, revealed_by_level),
+        direction,
+        entrance,
+        exit,
+        massive,
+        npc,
+        openable,
+        sign,
+        teleporter,
+        timer_gate,
+        transporter,
+        trigger,
+    ) in &objects_query
+    {
+        if position.x > 0
+            && position.x <= dimensions.width
+            && position.y > 0
+            && position.y <= dimensions.height
+        {
+            let positions = objects.entry(*object_type).or_insert(Vec::new());
+            positions.push(InitialPositionAndMetadata {
+                position: *position,
+                decoration: decoration.copied(),
+                direction: direction.copied(),
+                identifier: teleporter
+                    .map(|teleporter| teleporter.0)
+                    .or(trigger.map(|trigger| trigger.0))
+                    .or(match openable {
+                        Some(Openable::Trigger(identifier)) => Some(*identifier),
+                        _ => None,
+                    })
+                    .or_else(|| timer_gate.and_then(|timer_gate| timer_gate.trigger)),
+                level: entrance
+                    .map(|entrance| match &game_state.randomizer {
+                        Some(randomizer) => randomizer.entrance_for_level(entrance.0),
+                        None => entrance.0,
+                    })
+                    .or_else(|| exit.and_then(|exit| exit.0))
+                    .or(revealed_by_level.map(|revealed_by_level| revealed_by_level.0))
+                    .or_else(|| {
+                        openable.and_then(|openable| match openable {
+                            Openable::Key => None,
+                            Openable::LevelFinished(level) => Some(*level),
+                            Openable::FinishedCount(_) => None,
+                            Openable::Trigger(_) => None,
+                        })
+                    }),
+                // Entrances are opened based on the game state only.
+                open: openable.is_some() && massive.is_none() && entrance.is_none(),
+                capacity: bridge.map(|bridge| bridge.0),
+                interval: transporter.map(|transporter| transporter.interval),
+                required_count: openable.and_then(|openable| match openable {
+                    Openable::FinishedCount(count) => Some(*count),
+                    _ => None,
+                }),
+                seconds: timer_gate.map(|timer_gate| match (keeps_countdowns, massive) {
+                    (true, Some(_)) => 0,
+                    (true, None) => timer_gate.seconds_left(),
+                    (false, _) => timer_gate.timer.duration().as_secs() as u16,
+                }),
+                text: sign
+                    .map(|sign| sign.0.clone())
+                    .or_else(|| npc.map(|npc| npc.0.clone())),
+            });
+        }
+    }
+
+    // Objects of large levels that are still waiting to be spawned are saved
+    // as they were loaded.
+    for index in &spawned_level.pending {
+        let (object_type, initial_position) = &spawned_level.objects[*index];
+        let mut initial_position = initial_position.clone();
+        if let (ObjectType::Entrance, Some(randomizer)) = (object_type, &game_state.randomizer) {
+            initial_position.level = initial_position
+                .level
+                .map(|level| randomizer.entrance_for_level(level));
+        }
+        objects
+            .entry(*object_type)
+            .or_insert(Vec::new())
+            .push(initial_position);
+    }
+
+    if objects
+        .get(&ObjectType::Player)
+        .is_none_or(|player_locations| player_locations.len() != 1)
+    {
+        return; // Only save levels with exactly one player.
+    }
+
+    let level = Level {
+        dimensions: *dimensions,
+        // Level 0 is a hub without saying so.
+        is_hub: game_state.current_level != 0 && game_state.is_in_hub(),
+        theme: *theme,
+        key_mode: *key_mode,
+        push_strength: *push_strength,
+        diagonal_movement: *diagonal_movement,
+        weather: *weather,
+        inventory: *inventory,
+        objects,
+        warnings: Vec::new(),
+    };
+    let content = level.save();
+    let current_level = game_state.current_level;
+
+    match target {
+        SaveTarget::Disk => {
+            match fs::write(get_user_level_path(current_level), &content) {
+                Ok(()) => {
+                    editor_state.has_unsaved_changes = false;
+                    commands.trigger(ShowToast::new("Level saved"));
+                }
+                Err(error) => {
+                    error!("Could not save level: {error}");
+                    commands.trigger(SaveFailed {
+                        level: current_level,
+                        contents: content.clone(),
+                        error: error.to_string(),
+                    });
+                }
+            }
+
+            levels.insert_stored(current_level, content);
+        }
+        SaveTarget::Memory => levels.insert_current(current_level, content),
+        SaveTarget::Checkpoint(position) => {
+            *checkpoint = LevelCheckpoint {
+                position: Some(*position),
+                contents: content,
+            };
+        }
+        SaveTarget::History => history.push(content),
+        SaveTarget::InProgress => {
+            // Practicing may have put the player where they couldn't have
+            // gotten by themselves.
+            if !game_state.is_in_hub() && !practice_mode.is_active() {
+                game_state.set_level_in_progress(current_level, content);
+            }
+        }
+        SaveTarget::QuickSave => {
+            *quick_save = QuickSave {
+                level: Some(current_level),
+                contents: content,
+            };
+            commands.trigger(ShowToast::new("Quick saved"));
+        }
+        SaveTarget::Practice => {
+            practice_mode.set_savestate(content);
+            commands.trigger(ShowToast::new("Savestate set"));
+        }
+    }
+}
+
+/// Spawns the level objects for which `filter` returns `true`, given their
+/// index.
+///
+/// The objects are added to the tile index right away, so that systems running
+/// later in the same frame can already find them.
+fn spawn_level_objects(
+    cb: &mut ChildBuilder,
+    objects: &[(ObjectType, InitialPositionAndMetadata)],
+    filter: impl Fn(usize) -> bool,
+    tile_index: &mut TileIndex,
+    assets: &GameObjectAssets,
+    fonts: &Fonts,
+) {
+    for (index, (object_type, initial_position)) in objects.iter().enumerate() {
+        if filter(index) {
+            let entity =
+                spawn_object_of_type(cb, assets, fonts, *object_type, initial_position.clone())
+                    .insert(LevelObject(index))
+                    .id();
+            tile_index.insert(entity, initial_position.position);
+        }
+    }
+}
+
+/// Spawns the next chunk of objects of a level that is too large to spawn at
+/// once.
+fn spawn_pending_level_objects(
+    mut commands: Commands,
+    background_query: Query<Entity, With<Background>>,
+    mut spawned_level: ResMut<SpawnedLevel>,
+    mut tile_index: ResMut<TileIndex>,
+    assets: Res<GameObjectAssets>,
+    fonts: Res<Fonts>,
+) {
+    if spawned_level.pending.is_empty() {
+        return;
+    }
+
+    let Ok(background) = background_query.get_single() else {
+        return;
+    };
+
+    let is_in_chunk = spawned_level.next_chunk();
+    commands.entity(background).with_children(|cb| {
+        spawn_level_objects(
+            cb,
+            &spawned_level.objects,
+            |index| is_in_chunk[index],
+            &mut tile_index,
+            &assets,
+            &fonts,
+        );
+    });
+}
+
+fn spawn_object(
+    trigger: Trigger<SpawnObject>,
+    mut commands: Commands,
+    background_query: Query<Entity, With<Background>>,
+    assets: Res<GameObjectAssets>,
+    fonts: Res<Fonts>,
+) {
+    let SpawnObject {
+        object_type,
+        position,
+    } = trigger.event();
+
+    let background = background_query
+        .get_single()
+        .expect("there should be only one background");
+    let mut background = commands.entity(background);
+    background.with_children(|cb| {
+        spawn_object_of_type(cb, &assets, &fonts, *object_type, position.clone());
+    });
+}
@@ -0,0 +1,76 @@
+use std::{
+    backtrace::Backtrace,
+    fs::{self, File},
+    panic,
+    path::PathBuf,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    log::{tracing_subscriber::fmt, BoxedLayer},
+    prelude::*,
+};
+
+use crate::utils::ensure_chunky_dir;
+
+/// Number of log files from previous runs that are kept around.
+const NUM_OLD_LOG_FILES: usize = 3;
+
+fn log_path(index: usize) -> PathBuf {
+    ensure_chunky_dir().join(if index == 0 {
+        "chunky.log".to_owned()
+    } else {
+        format!("chunky.{index}.log")
+    })
+}
+
+/// Rotates the log files of previous runs, so only the most recent ones are
+/// kept.
+fn rotate_log_files() {
+    for index in (0..NUM_OLD_LOG_FILES).rev() {
+        let path = log_path(index);
+        if path.exists() {
+            let _ = fs::rename(path, log_path(index + 1));
+        }
+    }
+}
+
+/// Returns a layer for the [LogPlugin] that writes all logs to `chunky.log`
+/// in the Chunky dir, in addition to the console.
+pub fn file_log_layer(_app: &mut App) -> Option<BoxedLayer> {
+    rotate_log_files();
+
+    let file = File::create(log_path(0))
+        .map_err(|err| eprintln!("Can't create log file: {err}"))
+        .ok()?;
+
+    Some(Box::new(
+        fmt::layer().with_ansi(false).with_writer(Mutex::new(file)),
+    ))
+}
+
+/// Installs a panic hook that writes a crash report to the Chunky dir, before
+/// invoking the default hook.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let report = format!(
+            "Chunky's Challenge {} crashed at {timestamp}.\n\n{info}\n\n{}",
+            env!("CARGO_PKG_VERSION"),
+            Backtrace::force_capture()
+        );
+
+        let path = ensure_chunky_dir().join(format!("crash-{timestamp}.txt"));
+        match fs::write(&path, report) {
+            Ok(()) => error!("Crash report written to {path:?}: {info}"),
+            Err(err) => error!("Can't write crash report: {err}"),
+        }
+
+        default_hook(info);
+    }));
+}
@@ -1,28 +1,103 @@
-use bevy::prelude::*;
+use std::{collections::BTreeSet, path::Path};
+
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+};
+use rand::{thread_rng, Rng};
 
 use crate::{
-    background::UpdateBackgroundTransform, constants::*, editor::ToggleEditor, fonts::Fonts, setup,
-    LoadLevel, ResetLevel,
+    achievements::Achievement,
+    background::UpdateBackgroundTransform,
+    constants::*,
+    editor::{EditorState, ToggleEditor},
+    fast_travel::OpenFastTravel,
+    fonts::Fonts,
+    game_state::{progress_export_path, GameState, LevelStats},
+    levels::LEVELS,
+    modifiers::{Modifier, Modifiers},
+    overworld::OpenOverworld,
+    photo_mode::{PhotoMode, TogglePhotoMode},
+    settings::Settings,
+    setup,
+    text_input::TextInput,
+    timers::{
+        AnimationTimer, MovementTimer, RespawnTimer, SlideTimer, TemporaryTimer, TransporterTimer,
+    },
+    toast::ShowToast,
+    utils::{format_date, format_duration},
+    LoadLevel, ResetLevel, RestoreSnapshot, SaveLevel, SaveTarget,
 };
 
 pub const MENU_WIDTH: f32 = 500.;
 pub const MENU_HEIGHT: f32 = 400.;
 
-const NUM_HUB_BUTTONS: usize = 4;
-const NUM_LEVEL_BUTTONS: usize = 4;
+/// The hub menu has more buttons than the others, as well as the completion
+/// summary.
+const HUB_MENU_HEIGHT: f32 = 920.;
+
+/// The level menu has room for the quick save and photo mode buttons.
+const LEVEL_MENU_HEIGHT: f32 = 620.;
+
+const MODIFIERS_MENU_HEIGHT: f32 = 500.;
+
+const SETTINGS_MENU_HEIGHT: f32 = 900.;
+
+const PROGRESS_MENU_HEIGHT: f32 = 500.;
+
+const STATISTICS_MENU_HEIGHT: f32 = 600.;
+const STATISTICS_LIST_HEIGHT: f32 = 460.;
+
+/// Distance to scroll the statistics for every arrow key press or line of
+/// mouse wheel movement.
+const STATISTICS_SCROLL_STEP: f32 = 40.;
 
 #[derive(Component)]
 pub struct Menu {
     kind: MenuKind,
 }
 
+#[derive(Component)]
+struct CompletionText;
+
+#[derive(Component)]
+struct StatisticsList;
+
+#[derive(Component)]
+struct StatisticsText;
+
+#[derive(Component)]
+struct AchievementsText;
+
+#[derive(Component)]
+struct ProgressStatusText;
+
+/// Path of the file that progress gets exported to and imported from, which
+/// the player can edit from the progress menu.
+#[derive(Component)]
+struct ProgressPathText;
+
+#[derive(Component)]
+struct UiScaleText;
+
+const RESET_CONFIRMATION_TEXT: &str =
+    "This will erase all your progress.\nAre you sure you want to continue?";
+
 #[derive(Event)]
 struct ButtonPress;
 
+/// Adjusts the value of the selected button, for buttons that act as a slider.
+#[derive(Event)]
+struct ButtonAdjust(i8);
+
 #[derive(Resource)]
 pub struct MenuState {
     open_menu: Option<MenuKind>,
     selected_button: MenuButtonKind,
+
+    /// The level the player left unfinished, which can be resumed from the
+    /// hub menu.
+    resumable_level: Option<u16>,
 }
 
 impl Default for MenuState {
@@ -30,11 +105,16 @@ impl Default for MenuState {
         Self {
             open_menu: Some(MenuKind::Hub),
             selected_button: MenuButtonKind::Start,
+            resumable_level: None,
         }
     }
 }
 
 impl MenuState {
+    pub fn close(&mut self) {
+        self.open_menu = None;
+    }
+
     pub fn is_open(&self) -> bool {
         self.open_menu.is_some()
     }
@@ -44,12 +124,16 @@ impl MenuState {
     }
 
     fn move_selected_button(&mut self, delta: isize) {
-        let kinds = if self.is_in_hub_menu() {
-            MenuButtonKind::hub_buttons()
-        } else {
-            MenuButtonKind::level_buttons()
+        let Some(menu) = self.open_menu else {
+            return;
         };
 
+        let kinds: Vec<MenuButtonKind> = MenuButtonKind::for_menu(menu)
+            .iter()
+            .copied()
+            .filter(|kind| self.is_available(*kind))
+            .collect();
+
         let current_index = kinds
             .iter()
             .position(|kind| *kind == self.selected_button)
@@ -59,12 +143,22 @@ impl MenuState {
         self.selected_button = kinds[new_index as usize];
     }
 
+    /// Returns whether the button is shown, since some buttons are only
+    /// available some of the time.
+    fn is_available(&self, kind: MenuButtonKind) -> bool {
+        kind != MenuButtonKind::Resume || self.resumable_level.is_some()
+    }
+
+    /// Returns to the parent of the open menu, if it has one.
+    fn go_back(&mut self) {
+        if let Some(parent) = self.open_menu.and_then(MenuKind::parent) {
+            self.set_open(parent);
+        }
+    }
+
     pub fn set_open(&mut self, menu: MenuKind) {
         self.open_menu = Some(menu);
-        self.selected_button = match menu {
-            MenuKind::Hub => MenuButtonKind::Start,
-            MenuKind::Level => MenuButtonKind::Restart,
-        };
+        self.selected_button = MenuButtonKind::for_menu(menu)[0];
     }
 }
 
@@ -72,6 +166,38 @@ impl MenuState {
 pub enum MenuKind {
     Hub,
     Level,
+    Statistics,
+    Achievements,
+    Progress,
+    ResetConfirmation,
+    Settings,
+    Modifiers,
+}
+
+impl MenuKind {
+    fn height(self) -> f32 {
+        match self {
+            Self::Hub => HUB_MENU_HEIGHT,
+            Self::Statistics => STATISTICS_MENU_HEIGHT,
+            Self::Level => LEVEL_MENU_HEIGHT,
+            Self::Modifiers => MODIFIERS_MENU_HEIGHT,
+            Self::Progress => PROGRESS_MENU_HEIGHT,
+            Self::Settings => SETTINGS_MENU_HEIGHT,
+            Self::Achievements | Self::ResetConfirmation => MENU_HEIGHT,
+        }
+    }
+
+    /// Returns the menu to return to when going back from this menu.
+    fn parent(self) -> Option<Self> {
+        match self {
+            Self::Hub | Self::Level => None,
+            Self::Statistics | Self::Achievements | Self::Progress | Self::Settings => {
+                Some(Self::Hub)
+            }
+            Self::ResetConfirmation => Some(Self::Progress),
+            Self::Modifiers => Some(Self::Settings),
+        }
+    }
 }
 
 pub struct MenuPlugin;
@@ -80,110 +206,504 @@ impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup_menus.after(setup))
             .init_resource::<MenuState>()
+            .add_event::<ButtonAdjust>()
             .add_event::<ButtonPress>()
+            .add_observer(on_button_adjust)
             .add_observer(on_button_press)
             .add_systems(Update, (on_menu_interaction_input, on_resize))
-            .add_systems(Update, render_menu.after(on_menu_interaction_input));
+            .add_systems(Update, render_menu.after(on_menu_interaction_input))
+            .add_systems(Update, render_statistics.after(on_menu_interaction_input))
+            .add_systems(Update, render_achievements.after(on_menu_interaction_input))
+            .add_systems(Update, (render_completion, scroll_statistics))
+            .add_systems(
+                Update,
+                (
+                    render_resume_button,
+                    render_ui_scale,
+                    render_modifier_buttons,
+                    render_randomizer_button,
+                    render_input_display_button,
+                    render_reduce_motion_button,
+                    render_level_preview_button,
+                    render_persistent_graves_button,
+                    render_skin_button,
+                ),
+            )
+            .add_systems(Update, pause_gameplay);
     }
 }
 
 #[derive(Clone, Component, Copy, Eq, PartialEq)]
 enum MenuButtonKind {
     Start,
+    Resume,
+    FastTravel,
+    WorldMap,
     Restart,
+    QuickSave,
+    QuickLoad,
+    PhotoMode,
     BackToHub,
     Editor,
+    Statistics,
+    Achievements,
+    Progress,
+    ProgressPath,
+    ExportProgress,
+    ImportProgress,
+    ResetProgress,
+    Settings,
+    UiScale,
+    Modifiers,
+    Modifier(Modifier),
+    Randomizer,
+    InputDisplay,
+    ReduceMotion,
+    LevelPreview,
+    PersistentGraves,
+    Skin,
     OtherGames,
     Quit,
+    Back,
+    ConfirmReset,
+    Cancel,
 }
 
 impl MenuButtonKind {
-    fn hub_buttons() -> [Self; NUM_HUB_BUTTONS] {
-        [Self::Start, Self::Editor, Self::OtherGames, Self::Quit]
-    }
-
-    fn level_buttons() -> [Self; NUM_LEVEL_BUTTONS] {
-        [Self::Restart, Self::BackToHub, Self::Editor, Self::Quit]
+    fn for_menu(menu: MenuKind) -> &'static [Self] {
+        match menu {
+            MenuKind::Hub => &[
+                Self::Start,
+                Self::Resume,
+                Self::FastTravel,
+                Self::WorldMap,
+                Self::Editor,
+                Self::Statistics,
+                Self::Achievements,
+                Self::Progress,
+                Self::Settings,
+                Self::OtherGames,
+                Self::Quit,
+            ],
+            MenuKind::Level => &[
+                Self::Restart,
+                Self::QuickSave,
+                Self::QuickLoad,
+                Self::PhotoMode,
+                Self::BackToHub,
+                Self::Editor,
+                Self::Quit,
+            ],
+            MenuKind::Statistics | MenuKind::Achievements => &[Self::Back],
+            MenuKind::Progress => &[
+                Self::ProgressPath,
+                Self::ExportProgress,
+                Self::ImportProgress,
+                Self::ResetProgress,
+                Self::Back,
+            ],
+            MenuKind::ResetConfirmation => &[Self::Cancel, Self::ConfirmReset],
+            MenuKind::Settings => &[
+                Self::UiScale,
+                Self::Skin,
+                Self::Modifiers,
+                Self::Randomizer,
+                Self::InputDisplay,
+                Self::ReduceMotion,
+                Self::LevelPreview,
+                Self::PersistentGraves,
+                Self::Back,
+            ],
+            MenuKind::Modifiers => &[
+                Self::Modifier(Modifier::MoveBudget),
+                Self::Modifier(Modifier::InvisibleWater),
+                Self::Modifier(Modifier::MirroredControls),
+                Self::Modifier(Modifier::FastCreatures),
+                Self::Back,
+            ],
+        }
     }
 
     fn label(self) -> &'static str {
         match self {
             Self::Start => "Start",
+            Self::Resume => "Resume Level",
+            Self::FastTravel => "Fast Travel",
+            Self::WorldMap => "World Map",
             Self::Restart => "Restart Level",
+            Self::QuickSave => "Quick Save",
+            Self::QuickLoad => "Quick Load",
+            Self::PhotoMode => "Photo Mode",
             Self::BackToHub => "Exit Level",
             Self::Editor => "Level Editor",
+            Self::Statistics => "Statistics",
+            Self::Achievements => "Achievements",
             Self::OtherGames => "Other Games",
             Self::Quit => "Quit Game",
+            Self::Progress => "Progress",
+            Self::ProgressPath => "Path",
+            Self::ExportProgress => "Export Progress",
+            Self::ImportProgress => "Import Progress",
+            Self::ResetProgress => "Reset Progress",
+            Self::Settings => "Settings",
+            Self::UiScale => "UI Scale",
+            Self::Modifiers => "Modifiers",
+            Self::Modifier(modifier) => modifier.label(),
+            Self::Randomizer => "Randomizer",
+            Self::InputDisplay => "Input Display",
+            Self::ReduceMotion => "Reduce Motion",
+            Self::LevelPreview => "Level Preview",
+            Self::PersistentGraves => "Persistent Graves",
+            Self::Skin => "Skin",
+            Self::Back => "Back",
+            Self::ConfirmReset => "Reset",
+            Self::Cancel => "Cancel",
         }
     }
 }
 
-fn setup_menus(mut commands: Commands, window_query: Query<&Window>, fonts: Res<Fonts>) {
+fn setup_menus(
+    mut commands: Commands,
+    window_query: Query<&Window>,
+    fonts: Res<Fonts>,
+    ui_scale: Res<UiScale>,
+) {
     let window = window_query
         .get_single()
         .expect("there should be only one window");
 
+    // Menus are laid out in UI pixels, which are scaled with the UI.
+    let window_size = window.size() / ui_scale.0;
+
+    for kind in [MenuKind::Hub, MenuKind::Level] {
+        commands
+            .spawn(menu_bundle(kind, window_size))
+            .with_children(|cb| {
+                if kind == MenuKind::Hub {
+                    cb.spawn((
+                        CompletionText,
+                        Text::default(),
+                        TextColor(WHITE),
+                        TextFont::from_font(fonts.poppins_light.clone()).with_font_size(18.),
+                    ));
+                }
+
+                for kind in MenuButtonKind::for_menu(kind) {
+                    cb.spawn(MenuButton::new(*kind))
+                        .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
+                }
+            });
+    }
+
     commands
-        .spawn((
-            Menu {
-                kind: MenuKind::Hub,
-            },
-            BackgroundColor(GRAY_BACKGROUND),
-            BorderColor(RED),
-            GlobalZIndex(100),
-            Node {
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                width: Val::Px(MENU_WIDTH),
-                height: Val::Px(MENU_HEIGHT),
-                border: UiRect::all(Val::Px(2.)),
-                margin: UiRect::all(Val::Auto)
-                    .with_top(Val::Px(calculate_top_margin(window.size()))),
-                padding: UiRect::all(Val::Auto),
-                row_gap: Val::Px(40.),
-                position_type: PositionType::Absolute,
-                ..default()
-            },
-        ))
+        .spawn(menu_bundle(MenuKind::Statistics, window_size))
         .with_children(|cb| {
-            for kind in MenuButtonKind::hub_buttons() {
-                cb.spawn(MenuButton::new(kind))
+            cb.spawn((
+                StatisticsList,
+                ScrollPosition::default(),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Px(MENU_WIDTH - 40.),
+                    height: Val::Px(STATISTICS_LIST_HEIGHT),
+                    overflow: Overflow::scroll_y(),
+                    ..default()
+                },
+            ))
+            .with_children(|cb| {
+                cb.spawn((
+                    StatisticsText,
+                    Text::default(),
+                    TextColor(WHITE),
+                    TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+                ));
+            });
+
+            for kind in MenuButtonKind::for_menu(MenuKind::Statistics) {
+                cb.spawn(MenuButton::new(*kind))
                     .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
             }
         });
 
     commands
-        .spawn((
-            Menu {
-                kind: MenuKind::Level,
-            },
-            BackgroundColor(GRAY_BACKGROUND),
-            BorderColor(RED),
-            GlobalZIndex(100),
-            Node {
-                display: Display::Flex,
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                width: Val::Px(MENU_WIDTH),
-                height: Val::Px(MENU_HEIGHT),
-                border: UiRect::all(Val::Px(2.)),
-                margin: UiRect::all(Val::Auto)
-                    .with_top(Val::Px(calculate_top_margin(window.size()))),
-                padding: UiRect::all(Val::Auto),
-                row_gap: Val::Px(40.),
-                position_type: PositionType::Absolute,
-                ..default()
-            },
-        ))
+        .spawn(menu_bundle(MenuKind::Achievements, window_size))
         .with_children(|cb| {
-            for kind in MenuButtonKind::level_buttons() {
-                cb.spawn(MenuButton::new(kind))
+            cb.spawn((
+                AchievementsText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+                Node {
+                    max_width: Val::Px(MENU_WIDTH - 40.),
+                    ..default()
+                },
+            ));
+
+            for kind in MenuButtonKind::for_menu(MenuKind::Achievements) {
+                cb.spawn(MenuButton::new(*kind))
                     .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
             }
         });
+
+    commands
+        .spawn(menu_bundle(MenuKind::Progress, window_size))
+        .with_children(|cb| {
+            for kind in MenuButtonKind::for_menu(MenuKind::Progress) {
+                if *kind == MenuButtonKind::ProgressPath {
+                    // The path is too long for the regular button text.
+                    cb.spawn(MenuButton::new(*kind)).with_children(|cb| {
+                        cb.spawn((
+                            ProgressPathText,
+                            TextInput::new(progress_export_path().display().to_string()),
+                            TextColor(WHITE),
+                            TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+                            TextLayout::new_with_justify(JustifyText::Center),
+                            Node {
+                                max_width: Val::Px(280.),
+                                margin: UiRect::all(Val::Auto),
+                                ..default()
+                            },
+                        ));
+                    });
+                } else {
+                    cb.spawn(MenuButton::new(*kind))
+                        .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
+                }
+            }
+
+            cb.spawn((
+                ProgressStatusText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    max_width: Val::Px(MENU_WIDTH - 40.),
+                    ..default()
+                },
+            ));
+        });
+
+    commands
+        .spawn(menu_bundle(MenuKind::ResetConfirmation, window_size))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new(RESET_CONFIRMATION_TEXT),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(24.),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+
+            for kind in MenuButtonKind::for_menu(MenuKind::ResetConfirmation) {
+                cb.spawn(MenuButton::new(*kind))
+                    .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
+            }
+        });
+
+    commands
+        .spawn(menu_bundle(MenuKind::Modifiers, window_size))
+        .with_children(|cb| {
+            for kind in MenuButtonKind::for_menu(MenuKind::Modifiers) {
+                cb.spawn(MenuButton::new(*kind))
+                    .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
+            }
+        });
+
+    commands
+        .spawn(menu_bundle(MenuKind::Settings, window_size))
+        .with_children(|cb| {
+            for kind in MenuButtonKind::for_menu(MenuKind::Settings) {
+                if *kind == MenuButtonKind::UiScale {
+                    cb.spawn(MenuButton::new(*kind)).with_children(|cb| {
+                        cb.spawn((
+                            UiScaleText,
+                            Text::default(),
+                            TextColor(WHITE),
+                            TextFont::from_font(fonts.poppins_light.clone()).with_font_size(28.),
+                            Node {
+                                margin: UiRect::all(Val::Auto),
+                                ..default()
+                            },
+                        ));
+                    });
+                } else {
+                    cb.spawn(MenuButton::new(*kind))
+                        .with_children(|cb| MenuButton::populate(cb, kind.label(), &fonts));
+                }
+            }
+
+            cb.spawn((
+                Text::new("Use the left and right arrow keys to adjust"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+fn menu_bundle(kind: MenuKind, window_size: Vec2) -> impl Bundle {
+    (
+        Menu { kind },
+        BackgroundColor(GRAY_BACKGROUND),
+        BorderColor(RED),
+        GlobalZIndex(100),
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            width: Val::Px(MENU_WIDTH),
+            height: Val::Px(kind.height()),
+            border: UiRect::all(Val::Px(2.)),
+            margin: UiRect::all(Val::Auto)
+                .with_top(Val::Px(calculate_top_margin(window_size, kind.height()))),
+            padding: UiRect::all(Val::Auto),
+            row_gap: Val::Px(20.),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+    )
+}
+
+fn render_completion(
+    mut text_query: Query<&mut Text, With<CompletionText>>,
+    game_state: Res<GameState>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    // The hub doesn't count as a level to finish.
+    let levels = LEVELS.iter().filter(|(level, _)| *level != 0);
+    let num_levels = levels.clone().count();
+    let num_finished = levels
+        .filter(|(level, _)| game_state.finished_levels.contains(level))
+        .count();
+    let percentage = (100 * num_finished)
+        .checked_div(num_levels)
+        .unwrap_or_default();
+
+    for mut text in &mut text_query {
+        text.0 = format!("{num_finished} / {num_levels} levels finished ({percentage}%)");
+    }
+}
+
+fn scroll_statistics(
+    mut list_query: Query<&mut ScrollPosition, With<StatisticsList>>,
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    keys: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<MenuState>,
+) {
+    if menu_state.open_menu != Some(MenuKind::Statistics) {
+        mouse_wheel_events.clear();
+        return;
+    }
+
+    let mut delta: f32 = mouse_wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => -event.y * STATISTICS_SCROLL_STEP,
+            MouseScrollUnit::Pixel => -event.y,
+        })
+        .sum();
+    if keys.just_pressed(KeyCode::ArrowUp) {
+        delta -= STATISTICS_SCROLL_STEP;
+    }
+    if keys.just_pressed(KeyCode::ArrowDown) {
+        delta += STATISTICS_SCROLL_STEP;
+    }
+
+    if delta != 0. {
+        for mut scroll_position in &mut list_query {
+            // The maximum offset is clamped by the UI layout.
+            scroll_position.offset_y = (scroll_position.offset_y + delta).max(0.);
+        }
+    }
+}
+
+fn render_statistics(
+    mut text_query: Query<&mut Text, With<StatisticsText>>,
+    game_state: Res<GameState>,
+    menu_state: Res<MenuState>,
+) {
+    if !menu_state.is_changed() || menu_state.open_menu != Some(MenuKind::Statistics) {
+        return;
+    }
+
+    let mut text = text_query
+        .get_single_mut()
+        .expect("there should be only one statistics text");
+
+    text.0 = if game_state.level_stats.is_empty() {
+        "No levels played yet".to_owned()
+    } else {
+        game_state
+            .level_stats
+            .iter()
+            .map(|(level, stats)| {
+                format_level_stats(*level, stats, game_state.modifier_completions.get(level))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+}
+
+fn render_achievements(
+    mut text_query: Query<&mut Text, With<AchievementsText>>,
+    game_state: Res<GameState>,
+    menu_state: Res<MenuState>,
+) {
+    if !menu_state.is_changed() || menu_state.open_menu != Some(MenuKind::Achievements) {
+        return;
+    }
+
+    for mut text in &mut text_query {
+        text.0 = Achievement::ALL
+            .iter()
+            .map(|achievement| {
+                let status = if game_state.achievements.contains(achievement) {
+                    "unlocked".to_owned()
+                } else {
+                    let (progress, required) = achievement.progress(&game_state);
+                    format!("{} / {required}", progress.min(required))
+                };
+                format!(
+                    "{}: {} ({status})",
+                    achievement.title(),
+                    achievement.description()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+fn format_level_stats(
+    level: u16,
+    stats: &LevelStats,
+    modifiers: Option<&BTreeSet<Modifier>>,
+) -> String {
+    let LevelStats {
+        attempts,
+        deaths,
+        best_moves,
+        best_time,
+        completed_at,
+    } = stats;
+
+    let mut line = format!("Level {level}: {attempts} attempts, {deaths} deaths");
+    if let Some(best_moves) = best_moves {
+        line.push_str(&format!(", {best_moves} moves"));
+    }
+    if let Some(best_time) = best_time {
+        line.push_str(&format!(", {}", format_duration(*best_time)));
+    }
+    if let Some(completed_at) = completed_at {
+        line.push_str(&format!(", finished {}", format_date(*completed_at)));
+    }
+    if let Some(modifiers) = modifiers.filter(|modifiers| !modifiers.is_empty()) {
+        let labels: Vec<&str> = modifiers.iter().map(|modifier| modifier.label()).collect();
+        line.push_str(&format!(", modifiers: {}", labels.join(", ")));
+    }
+    line
 }
 
 fn render_menu(
@@ -259,10 +779,15 @@ pub fn on_menu_keyboard_input(
         match key {
             ArrowUp => menu_state.move_selected_button(-1),
             ArrowDown => menu_state.move_selected_button(1),
+            ArrowLeft => commands.trigger(ButtonAdjust(-1)),
+            ArrowRight => commands.trigger(ButtonAdjust(1)),
             Enter | Space => {
                 commands.trigger(ButtonPress);
                 return;
             }
+            Escape if menu_state.open_menu.and_then(MenuKind::parent).is_some() => {
+                menu_state.go_back();
+            }
             Escape => {
                 app_exit_events.send(AppExit::Success);
             }
@@ -272,6 +797,8 @@ pub fn on_menu_keyboard_input(
     }
 }
 
+/// Lets the player type the path for exporting and importing progress, while
+/// the path button is being edited.
 fn on_menu_interaction_input(
     mut commands: Commands,
     button_query: Query<(&Interaction, &MenuButtonKind), Changed<Interaction>>,
@@ -292,49 +819,475 @@ fn on_menu_interaction_input(
     }
 }
 
+/// Pauses the timers that drive the level while a menu is open or photo mode
+/// is active, so nothing moves until the player returns to the level. The
+/// clock of the current attempt is paused along with them.
+///
+/// The editor pauses some of the same timers, which stay paused if a menu is
+/// closed while the editor is open.
+#[expect(clippy::too_many_arguments)]
+fn pause_gameplay(
+    mut animation_timer: ResMut<AnimationTimer>,
+    mut movement_timer: ResMut<MovementTimer>,
+    mut respawn_timer: ResMut<RespawnTimer>,
+    mut slide_timer: ResMut<SlideTimer>,
+    mut temporary_timer: ResMut<TemporaryTimer>,
+    mut transporter_timer: ResMut<TransporterTimer>,
+    mut game_state: ResMut<GameState>,
+    editor_state: Res<EditorState>,
+    menu_state: Res<MenuState>,
+    photo_mode: Res<PhotoMode>,
+    time: Res<Time>,
+) {
+    if !menu_state.is_changed() && !photo_mode.is_changed() {
+        return;
+    }
+
+    let is_paused = menu_state.is_open() || photo_mode.is_active();
+    if is_paused {
+        game_state.pause_attempt(time.elapsed());
+    } else {
+        game_state.resume_attempt(time.elapsed());
+    }
+
+    let set_paused = |timer: &mut Timer| {
+        if is_paused {
+            timer.pause();
+        } else {
+            timer.unpause();
+        }
+    };
+
+    set_paused(&mut animation_timer);
+    set_paused(&mut respawn_timer);
+    if !editor_state.is_open {
+        set_paused(&mut movement_timer);
+        set_paused(&mut slide_timer);
+        set_paused(&mut temporary_timer);
+        set_paused(&mut transporter_timer);
+    }
+}
+
 fn on_resize(
-    mut menu_query: Query<&mut Node, With<Menu>>,
-    window_query: Query<&Window, Changed<Window>>,
+    mut menu_query: Query<(&mut Node, &Menu)>,
+    window_query: Query<Ref<Window>>,
+    ui_scale: Res<UiScale>,
 ) {
     for window in &window_query {
-        for mut node in &mut menu_query {
-            node.margin.top = Val::Px(calculate_top_margin(window.size()));
+        if !window.is_changed() && !ui_scale.is_changed() {
+            continue;
+        }
+
+        let window_size = window.size() / ui_scale.0;
+        for (mut node, menu) in &mut menu_query {
+            node.margin.top = Val::Px(calculate_top_margin(window_size, menu.kind.height()));
         }
     }
 }
 
+#[expect(clippy::too_many_arguments)]
 fn on_button_press(
     _trigger: Trigger<ButtonPress>,
     mut commands: Commands,
     mut app_exit_events: EventWriter<AppExit>,
     mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut status_text_query: Query<&mut Text, With<ProgressStatusText>>,
+    mut game_state: ResMut<GameState>,
     mut menu_state: ResMut<MenuState>,
+    mut path_input_query: Query<&mut TextInput, With<ProgressPathText>>,
+    mut settings: ResMut<Settings>,
+    mut modifiers: Modifiers,
 ) {
+    let Ok(mut path_input) = path_input_query.get_single_mut() else {
+        return;
+    };
+
+    // Pressing any other button finishes editing the path.
+    if menu_state.selected_button != MenuButtonKind::ProgressPath && path_input.is_editing() {
+        path_input.set_editing(false);
+    }
+
+    let mut set_status = |status: String| {
+        for mut text in &mut status_text_query {
+            text.0.clone_from(&status);
+        }
+    };
+
     match menu_state.selected_button {
         MenuButtonKind::Start => {
             background_events.send(UpdateBackgroundTransform::HubIntro);
             menu_state.open_menu = None;
         }
+        MenuButtonKind::Resume => {
+            if let Some(level) = menu_state.resumable_level {
+                commands.trigger(LoadLevel(level));
+                menu_state.open_menu = None;
+            }
+        }
+        MenuButtonKind::FastTravel => {
+            commands.trigger(OpenFastTravel);
+            menu_state.open_menu = None;
+        }
+        MenuButtonKind::WorldMap => {
+            commands.trigger(OpenOverworld);
+            menu_state.open_menu = None;
+        }
         MenuButtonKind::Restart => {
             commands.trigger(ResetLevel);
             menu_state.open_menu = None;
         }
+        MenuButtonKind::QuickSave => {
+            commands.trigger(SaveLevel {
+                target: SaveTarget::QuickSave,
+            });
+            menu_state.open_menu = None;
+        }
+        MenuButtonKind::QuickLoad => {
+            commands.trigger(RestoreSnapshot::QuickSave);
+            menu_state.open_menu = None;
+        }
+        MenuButtonKind::PhotoMode => {
+            commands.trigger(TogglePhotoMode);
+            menu_state.open_menu = None;
+        }
         MenuButtonKind::BackToHub => {
-            commands.trigger(LoadLevel(0));
+            commands.trigger(SaveLevel {
+                target: SaveTarget::InProgress,
+            });
+            commands.trigger(LoadLevel(game_state.current_hub));
             menu_state.open_menu = None;
         }
         MenuButtonKind::Editor => {
             commands.trigger(ToggleEditor);
             menu_state.open_menu = None;
         }
+        MenuButtonKind::Statistics => {
+            menu_state.set_open(MenuKind::Statistics);
+        }
+        MenuButtonKind::Achievements => {
+            menu_state.set_open(MenuKind::Achievements);
+        }
         MenuButtonKind::OtherGames => { /* TODO */ }
         MenuButtonKind::Quit => {
+            commands.trigger(SaveLevel {
+                target: SaveTarget::InProgress,
+            });
             app_exit_events.send(AppExit::Success);
         }
+        MenuButtonKind::Progress => {
+            set_status(String::new());
+            menu_state.set_open(MenuKind::Progress);
+        }
+        MenuButtonKind::ProgressPath => {
+            let is_editing = path_input.is_editing();
+            path_input.set_editing(!is_editing);
+        }
+        MenuButtonKind::ExportProgress => {
+            let path = Path::new(&path_input.value);
+            set_status(match game_state.export_progress(path) {
+                Ok(()) => format!("Progress exported to {}", path.display()),
+                Err(err) => format!("Can't export progress: {err}"),
+            });
+        }
+        MenuButtonKind::ImportProgress => {
+            let path = Path::new(&path_input.value);
+            set_status(match game_state.import_progress(path) {
+                Ok(()) => format!("Progress imported from {}", path.display()),
+                Err(err) => format!("Can't import progress from {}: {err}", path.display()),
+            });
+        }
+        MenuButtonKind::ResetProgress => {
+            menu_state.set_open(MenuKind::ResetConfirmation);
+        }
+        MenuButtonKind::Settings => {
+            menu_state.set_open(MenuKind::Settings);
+        }
+        MenuButtonKind::UiScale => {
+            settings.cycle_ui_scale();
+        }
+        MenuButtonKind::Modifiers => {
+            menu_state.set_open(MenuKind::Modifiers);
+        }
+        MenuButtonKind::Modifier(modifier) => {
+            modifiers.toggle(modifier);
+        }
+        MenuButtonKind::Randomizer => {
+            if !game_state.completed {
+                commands.trigger(ShowToast::new("Finish the game to unlock the randomizer"));
+                return;
+            }
+
+            let seed = game_state.randomizer.is_none().then(|| thread_rng().gen());
+            game_state.set_randomizer_seed(seed);
+            commands.trigger(ShowToast::new(match seed {
+                Some(seed) => format!("Entrances shuffled with seed {seed}"),
+                None => "Entrances restored".to_owned(),
+            }));
+
+            // Respawns the hub with its entrances remapped, without moving
+            // the player.
+            if game_state.is_in_hub() {
+                commands.trigger(SaveLevel {
+                    target: SaveTarget::Memory,
+                });
+                commands.trigger(LoadLevel(game_state.current_level));
+            }
+        }
+        MenuButtonKind::InputDisplay => {
+            settings.input_display ^= true;
+        }
+        MenuButtonKind::ReduceMotion => {
+            settings.reduce_motion ^= true;
+        }
+        MenuButtonKind::LevelPreview => {
+            settings.level_preview ^= true;
+        }
+        MenuButtonKind::PersistentGraves => {
+            settings.persistent_graves ^= true;
+        }
+        MenuButtonKind::Skin => {
+            game_state.cycle_player_skin(1);
+        }
+        MenuButtonKind::Back | MenuButtonKind::Cancel => {
+            menu_state.go_back();
+        }
+        MenuButtonKind::ConfirmReset => {
+            game_state.reset_progress();
+            set_status("Progress has been reset".to_owned());
+            menu_state.go_back();
+        }
+    }
+}
+
+fn on_button_adjust(
+    trigger: Trigger<ButtonAdjust>,
+    mut game_state: ResMut<GameState>,
+    mut settings: ResMut<Settings>,
+    menu_state: Res<MenuState>,
+) {
+    let ButtonAdjust(steps) = trigger.event();
+    match menu_state.selected_button {
+        MenuButtonKind::UiScale => settings.adjust_ui_scale(*steps),
+        MenuButtonKind::Skin => game_state.cycle_player_skin(*steps as isize),
+        _ => {}
+    }
+}
+
+fn render_resume_button(
+    mut button_query: Query<(&MenuButtonKind, &mut Node, &Children)>,
+    mut text_query: Query<&mut Text>,
+    mut menu_state: ResMut<MenuState>,
+    game_state: Res<GameState>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    let resumable_level = game_state
+        .level_in_progress
+        .as_ref()
+        .map(|in_progress| in_progress.level);
+    if menu_state.resumable_level != resumable_level {
+        menu_state.resumable_level = resumable_level;
+        if !menu_state.is_available(menu_state.selected_button) {
+            menu_state.selected_button = MenuButtonKind::Start;
+        }
+    }
+
+    for (kind, mut node, children) in &mut button_query {
+        if *kind != MenuButtonKind::Resume {
+            continue;
+        }
+
+        let Some(level) = resumable_level else {
+            node.display = Display::None;
+            continue;
+        };
+
+        node.display = Display::Flex;
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Resume Level {level}");
+        }
+    }
+}
+
+fn render_ui_scale(mut text_query: Query<&mut Text, With<UiScaleText>>, settings: Res<Settings>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for mut text in &mut text_query {
+        text.0 = format!("UI Scale: < {:.2}x >", settings.ui_scale);
+    }
+}
+
+fn render_randomizer_button(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    game_state: Res<GameState>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        if *kind != MenuButtonKind::Randomizer {
+            continue;
+        }
+
+        let state = if game_state.randomizer.is_some() {
+            "On"
+        } else {
+            "Off"
+        };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Randomizer: {state}");
+        }
+    }
+}
+
+fn render_input_display_button(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    settings: Res<Settings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        if *kind != MenuButtonKind::InputDisplay {
+            continue;
+        }
+
+        let state = if settings.input_display { "On" } else { "Off" };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Input Display: {state}");
+        }
+    }
+}
+
+fn render_reduce_motion_button(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    settings: Res<Settings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        if *kind != MenuButtonKind::ReduceMotion {
+            continue;
+        }
+
+        let state = if settings.reduce_motion { "On" } else { "Off" };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Reduce Motion: {state}");
+        }
+    }
+}
+
+fn render_level_preview_button(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    settings: Res<Settings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        if *kind != MenuButtonKind::LevelPreview {
+            continue;
+        }
+
+        let state = if settings.level_preview { "On" } else { "Off" };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Level Preview: {state}");
+        }
+    }
+}
+
+fn render_persistent_graves_button(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    settings: Res<Settings>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        if *kind != MenuButtonKind::PersistentGraves {
+            continue;
+        }
+
+        let state = if settings.persistent_graves {
+            "On"
+        } else {
+            "Off"
+        };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Persistent Graves: {state}");
+        }
+    }
+}
+
+fn render_skin_button(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    game_state: Res<GameState>,
+) {
+    if !game_state.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        if *kind != MenuButtonKind::Skin {
+            continue;
+        }
+
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("Skin: {}", game_state.player_skin.label());
+        }
+    }
+}
+
+fn render_modifier_buttons(
+    button_query: Query<(&MenuButtonKind, &Children)>,
+    mut text_query: Query<&mut Text>,
+    modifiers: Modifiers,
+) {
+    if !modifiers.is_changed() {
+        return;
+    }
+
+    for (kind, children) in &button_query {
+        let MenuButtonKind::Modifier(modifier) = *kind else {
+            continue;
+        };
+
+        let state = if modifiers.is_enabled(modifier) {
+            "On"
+        } else {
+            "Off"
+        };
+        let mut texts = text_query.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0 = format!("{}: {state}", modifier.label());
+        }
     }
 }
 
-fn calculate_top_margin(window_size: Vec2) -> f32 {
+fn calculate_top_margin(window_size: Vec2, menu_height: f32) -> f32 {
     // Add a small extra margin at the end so the written logo is revealed well.
-    0.5 * (window_size.y - MENU_HEIGHT) + 50.
+    0.5 * (window_size.y - menu_height) + 50.
 }
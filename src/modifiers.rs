@@ -0,0 +1,261 @@
+use std::collections::BTreeSet;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game_object::{behaviors::check_for_exit, Liquid, Player, Shoreline},
+    game_state::{DeathCause, GameState},
+    on_game_event,
+    timers::MovementTimer,
+    toast::ShowToast,
+    ExitState, LoadLevel,
+};
+
+/// Budget for levels that haven't been finished yet. Levels that have been
+/// finished get a budget based on the best number of moves instead.
+const DEFAULT_MOVE_BUDGET: u32 = 200;
+
+/// Number of remaining moves at which the player gets warned.
+const MOVE_BUDGET_WARNING: u32 = 10;
+
+/// Optional challenges that make levels harder.
+///
+/// Modifiers are toggled from the settings menu, and apply to every level
+/// the player starts while they are enabled. The hub is never affected.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+pub enum Modifier {
+    MoveBudget,
+    InvisibleWater,
+    MirroredControls,
+    FastCreatures,
+}
+
+impl Modifier {
+    pub const ALL: [Self; 4] = [
+        Self::MoveBudget,
+        Self::InvisibleWater,
+        Self::MirroredControls,
+        Self::FastCreatures,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::MoveBudget => "Move Budget",
+            Self::InvisibleWater => "Invisible Water",
+            Self::MirroredControls => "Mirrored",
+            Self::FastCreatures => "Fast Creatures",
+        }
+    }
+}
+
+/// Limits the number of moves the player can make in a level.
+#[derive(Default, Resource)]
+pub struct MoveBudget {
+    pub is_enabled: bool,
+
+    /// Number of moves allowed in the current level, if it has a budget.
+    limit: Option<u32>,
+}
+
+/// Hides the water, so the player needs to remember where it is.
+#[derive(Default, Resource)]
+pub struct InvisibleWater(pub bool);
+
+/// Swaps left and right.
+#[derive(Default, Resource)]
+pub struct MirroredControls(pub bool);
+
+/// Makes creatures move twice as fast.
+#[derive(Default, Resource)]
+pub struct FastCreatures(pub bool);
+
+impl MirroredControls {
+    /// Returns the direction to move in for the given input.
+    pub fn apply(&self, game_state: &GameState, (dx, dy): (i16, i16)) -> (i16, i16) {
+        if self.0 && applies_to_current_level(game_state) {
+            (-dx, dy)
+        } else {
+            (dx, dy)
+        }
+    }
+}
+
+/// All modifiers, for turning them on and off.
+#[derive(SystemParam)]
+pub struct Modifiers<'w> {
+    move_budget: ResMut<'w, MoveBudget>,
+    invisible_water: ResMut<'w, InvisibleWater>,
+    mirrored_controls: ResMut<'w, MirroredControls>,
+    fast_creatures: ResMut<'w, FastCreatures>,
+}
+
+impl Modifiers<'_> {
+    pub fn is_enabled(&self, modifier: Modifier) -> bool {
+        match modifier {
+            Modifier::MoveBudget => self.move_budget.is_enabled,
+            Modifier::InvisibleWater => self.invisible_water.0,
+            Modifier::MirroredControls => self.mirrored_controls.0,
+            Modifier::FastCreatures => self.fast_creatures.0,
+        }
+    }
+
+    pub fn toggle(&mut self, modifier: Modifier) {
+        match modifier {
+            Modifier::MoveBudget => self.move_budget.is_enabled ^= true,
+            Modifier::InvisibleWater => self.invisible_water.0 ^= true,
+            Modifier::MirroredControls => self.mirrored_controls.0 ^= true,
+            Modifier::FastCreatures => self.fast_creatures.0 ^= true,
+        }
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.move_budget.is_changed()
+            || self.invisible_water.is_changed()
+            || self.mirrored_controls.is_changed()
+            || self.fast_creatures.is_changed()
+    }
+
+    fn enabled(&self) -> BTreeSet<Modifier> {
+        Modifier::ALL
+            .into_iter()
+            .filter(|modifier| self.is_enabled(*modifier))
+            .collect()
+    }
+}
+
+pub struct ModifiersPlugin;
+
+impl Plugin for ModifiersPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MoveBudget>()
+            .init_resource::<InvisibleWater>()
+            .init_resource::<MirroredControls>()
+            .init_resource::<FastCreatures>()
+            .add_observer(set_move_budget)
+            .add_systems(
+                Update,
+                (check_move_budget, record_modifier_completion)
+                    .after(on_game_event)
+                    .after(check_for_exit),
+            )
+            .add_systems(Update, (apply_creature_speed, apply_invisible_water));
+    }
+}
+
+/// Returns whether modifiers apply to the level the player is in, which
+/// excludes the hub and the demo of the attract mode.
+fn applies_to_current_level(game_state: &GameState) -> bool {
+    !game_state.is_in_hub() && !game_state.is_playing_demo
+}
+
+fn set_move_budget(
+    trigger: Trigger<LoadLevel>,
+    mut commands: Commands,
+    mut move_budget: ResMut<MoveBudget>,
+    game_state: Res<GameState>,
+) {
+    let LoadLevel(level) = trigger.event();
+    if !move_budget.is_enabled || game_state.is_hub_level(*level) || game_state.is_playing_demo {
+        move_budget.limit = None;
+        return;
+    }
+
+    let limit = game_state
+        .level_stats
+        .get(level)
+        .and_then(|stats| stats.best_moves)
+        .map_or(DEFAULT_MOVE_BUDGET, |best_moves| {
+            best_moves + best_moves / 2
+        });
+    move_budget.limit = Some(limit);
+    commands.trigger(ShowToast::new(format!("Move budget: {limit} moves")));
+}
+
+/// Makes the player run out of moves once the budget is used up without
+/// having reached the exit.
+fn check_move_budget(
+    mut commands: Commands,
+    player_query: Query<Entity, With<Player>>,
+    mut game_state: ResMut<GameState>,
+    mut num_moves: Local<u32>,
+    exit_state: Res<ExitState>,
+    move_budget: Res<MoveBudget>,
+) {
+    let current_moves = game_state.current_moves();
+    let has_moved = current_moves > *num_moves;
+    *num_moves = current_moves;
+
+    let Some(limit) = move_budget.limit else {
+        return;
+    };
+    if !has_moved || exit_state.next_level.is_some() {
+        return;
+    }
+
+    let remaining = limit.saturating_sub(current_moves);
+    if remaining == MOVE_BUDGET_WARNING {
+        commands.trigger(ShowToast::new(format!("{remaining} moves left")));
+    } else if remaining == 0 {
+        for player in &player_query {
+            commands.entity(player).despawn();
+        }
+        game_state.record_death(DeathCause::OutOfMoves, None);
+    }
+}
+
+/// Remembers the modifiers that were enabled when the player finished a
+/// level.
+fn record_modifier_completion(
+    mut game_state: ResMut<GameState>,
+    exit_state: Res<ExitState>,
+    modifiers: Modifiers,
+) {
+    if !exit_state.is_changed()
+        || exit_state.next_level.is_none()
+        || !applies_to_current_level(&game_state)
+    {
+        return;
+    }
+
+    let enabled = modifiers.enabled();
+    if !enabled.is_empty() {
+        game_state.record_modifier_completion(enabled);
+    }
+}
+
+fn apply_creature_speed(
+    mut movement_timer: ResMut<MovementTimer>,
+    fast_creatures: Res<FastCreatures>,
+    game_state: Res<GameState>,
+) {
+    let base_duration = MovementTimer::default().duration();
+    let duration = if fast_creatures.0 && applies_to_current_level(&game_state) {
+        base_duration / 2
+    } else {
+        base_duration
+    };
+
+    if movement_timer.duration() != duration {
+        movement_timer.set_duration(duration);
+    }
+}
+
+#[expect(clippy::type_complexity)]
+fn apply_invisible_water(
+    mut sprite_query: Query<&mut Sprite, Or<(With<Liquid>, With<Shoreline>)>>,
+    game_state: Res<GameState>,
+    invisible_water: Res<InvisibleWater>,
+) {
+    let alpha = if invisible_water.0 && applies_to_current_level(&game_state) {
+        0.
+    } else {
+        1.
+    };
+
+    for mut sprite in &mut sprite_query {
+        if sprite.color.alpha() != alpha {
+            sprite.color.set_alpha(alpha);
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*, fonts::Fonts, game_state::GameState, input_prompt::InputPrompt, levels::Levels,
+    on_keyboard_input, on_mouse_input, setup, toast::ShowToast, LoadLevel, SaveLevel, SaveTarget,
+};
+
+/// Number of level nodes per row. Rows alternate direction, so the path
+/// snakes from one row into the next.
+const COLUMNS: usize = 10;
+
+const NODE_SIZE: f32 = 48.;
+const PATH_LENGTH: f32 = 16.;
+const PATH_WIDTH: f32 = 4.;
+
+/// Opens the overworld, which shows the levels as nodes along a path, for
+/// players who'd rather not walk across the hub.
+#[derive(Event)]
+pub struct OpenOverworld;
+
+#[derive(Default, Resource)]
+struct Overworld {
+    nodes: Vec<OverworldLevel>,
+    selection: usize,
+    is_open: bool,
+}
+
+impl Overworld {
+    /// Moves the selection by the given number of columns and rows, as they
+    /// are laid out on screen.
+    fn move_selection(&mut self, dx: isize, dy: isize) {
+        let (column, row) = grid_position(self.selection);
+        let (column, row) = (column as isize + dx, row as isize + dy);
+        if !(0..COLUMNS as isize).contains(&column) || row < 0 {
+            return;
+        }
+
+        let index = index_at(column as usize, row as usize);
+        if index < self.nodes.len() {
+            self.selection = index;
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct OverworldLevel {
+    level: u16,
+    is_finished: bool,
+    is_unlocked: bool,
+}
+
+#[derive(Component)]
+struct OverworldPanel;
+
+#[derive(Component)]
+struct OverworldGrid;
+
+/// Node for the level with the given index along the path.
+#[derive(Component)]
+struct OverworldNode(usize);
+
+pub struct OverworldPlugin;
+
+impl Plugin for OverworldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Overworld>()
+            .add_observer(on_open_overworld)
+            .add_systems(Startup, setup_overworld.after(setup))
+            .add_systems(
+                Update,
+                on_overworld_input
+                    .before(on_keyboard_input)
+                    .before(on_mouse_input),
+            )
+            .add_systems(Update, render_overworld.after(on_overworld_input));
+    }
+}
+
+/// Returns the column and row at which the node with the given index is
+/// shown.
+fn grid_position(index: usize) -> (usize, usize) {
+    let row = index / COLUMNS;
+    let column = if row.is_multiple_of(2) {
+        index % COLUMNS
+    } else {
+        COLUMNS - 1 - index % COLUMNS
+    };
+    (column, row)
+}
+
+/// Returns the index of the node that is shown at the given column and row.
+fn index_at(column: usize, row: usize) -> usize {
+    let offset = if row.is_multiple_of(2) {
+        column
+    } else {
+        COLUMNS - 1 - column
+    };
+    row * COLUMNS + offset
+}
+
+fn setup_overworld(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            OverworldPanel,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::all(Val::Auto),
+                padding: UiRect::all(Val::Px(20.)),
+                row_gap: Val::Px(10.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new("World Map"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(32.),
+            ));
+            cb.spawn((
+                OverworldGrid,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Px(COLUMNS as f32 * NODE_SIZE + (COLUMNS - 1) as f32 * PATH_LENGTH),
+                    margin: UiRect::vertical(Val::Px(10.)),
+                    ..default()
+                },
+            ));
+            cb.spawn((
+                InputPrompt::new("{confirm} to enter the level"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+/// Lays out the levels along the path, unlocking them one after another.
+///
+/// A level is unlocked once the level before it is finished or skipped, so
+/// players who skip a level using the assist mode can still move on.
+fn on_open_overworld(
+    _trigger: Trigger<OpenOverworld>,
+    mut commands: Commands,
+    grid_query: Query<Entity, With<OverworldGrid>>,
+    mut overworld: ResMut<Overworld>,
+    fonts: Res<Fonts>,
+    game_state: Res<GameState>,
+    levels: Res<Levels>,
+) {
+    if !game_state.is_in_hub() {
+        commands.trigger(ShowToast::new(
+            "The world map can only be opened in the hub",
+        ));
+        return;
+    }
+
+    let Ok(grid) = grid_query.get_single() else {
+        return;
+    };
+
+    let mut nodes: Vec<OverworldLevel> = Vec::new();
+    for level in levels
+        .numbers()
+        .filter(|level| !game_state.is_hub_level(*level))
+    {
+        let is_finished = game_state.finished_levels.contains(&level);
+        let is_unlocked = is_finished
+            || nodes.last().is_none_or(|previous| {
+                previous.is_finished || game_state.skipped_levels.contains(&previous.level)
+            });
+        nodes.push(OverworldLevel {
+            level,
+            is_finished,
+            is_unlocked,
+        });
+    }
+
+    // The selection starts at the furthest level the player can enter.
+    let selection = nodes
+        .iter()
+        .rposition(|node| node.is_unlocked)
+        .unwrap_or_default();
+
+    commands
+        .entity(grid)
+        .despawn_descendants()
+        .with_children(|cb| {
+            for (row, row_nodes) in nodes.chunks(COLUMNS).enumerate() {
+                if row > 0 {
+                    // The path continues into the next row from the side the
+                    // previous row ended on.
+                    let is_unlocked = row_nodes[0].is_unlocked;
+                    cb.spawn(Node {
+                        justify_content: if row.is_multiple_of(2) {
+                            JustifyContent::FlexStart
+                        } else {
+                            JustifyContent::FlexEnd
+                        },
+                        ..default()
+                    })
+                    .with_children(|cb| {
+                        cb.spawn(Node {
+                            justify_content: JustifyContent::Center,
+                            width: Val::Px(NODE_SIZE),
+                            ..default()
+                        })
+                        .with_children(|cb| {
+                            cb.spawn(path_bundle(is_unlocked, PATH_WIDTH, PATH_LENGTH));
+                        });
+                    });
+                }
+
+                cb.spawn(Node {
+                    flex_direction: if row.is_multiple_of(2) {
+                        FlexDirection::Row
+                    } else {
+                        FlexDirection::RowReverse
+                    },
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|cb| {
+                    for (column, node) in row_nodes.iter().enumerate() {
+                        if column > 0 {
+                            cb.spawn(path_bundle(node.is_unlocked, PATH_LENGTH, PATH_WIDTH));
+                        }
+
+                        cb.spawn((
+                            OverworldNode(row * COLUMNS + column),
+                            Button,
+                            BackgroundColor(NORMAL_GRAY),
+                            BorderRadius::all(Val::Px(NODE_SIZE / 2.)),
+                            Node {
+                                width: Val::Px(NODE_SIZE),
+                                height: Val::Px(NODE_SIZE),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|cb| {
+                            cb.spawn((
+                                Text::new(node.level.to_string()),
+                                TextColor(if node.is_unlocked { WHITE } else { LIGHT_GRAY }),
+                                TextFont::from_font(fonts.poppins_light.clone())
+                                    .with_font_size(18.),
+                                Node {
+                                    margin: UiRect::all(Val::Auto),
+                                    ..default()
+                                },
+                            ));
+                        });
+                    }
+                });
+            }
+        });
+
+    *overworld = Overworld {
+        nodes,
+        selection,
+        is_open: true,
+    };
+}
+
+fn path_bundle(is_unlocked: bool, width: f32, height: f32) -> impl Bundle {
+    (
+        BackgroundColor(if is_unlocked { WHITE } else { LIGHT_GRAY }),
+        Node {
+            width: Val::Px(width),
+            height: Val::Px(height),
+            ..default()
+        },
+    )
+}
+
+/// Lets the player pick a level on the world map, using the keyboard, a
+/// gamepad, the mouse or touch.
+///
+/// All input is consumed while the map is shown, so the player doesn't also
+/// move around the hub.
+fn on_overworld_input(
+    mut commands: Commands,
+    node_query: Query<(&Interaction, &OverworldNode), Changed<Interaction>>,
+    mut gamepads: Query<&mut Gamepad>,
+    mut overworld: ResMut<Overworld>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    game_state: Res<GameState>,
+) {
+    if !overworld.is_open {
+        return;
+    }
+
+    if !game_state.is_in_hub() {
+        overworld.is_open = false;
+        return;
+    }
+
+    let mut is_pressed = false;
+    let mut is_closed = false;
+
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::ArrowUp => overworld.move_selection(0, -1),
+            KeyCode::ArrowRight => overworld.move_selection(1, 0),
+            KeyCode::ArrowDown => overworld.move_selection(0, 1),
+            KeyCode::ArrowLeft => overworld.move_selection(-1, 0),
+            KeyCode::Enter | KeyCode::Space => is_pressed = true,
+            KeyCode::Escape | KeyCode::KeyO => is_closed = true,
+            _ => {}
+        }
+    }
+    keys.reset_all();
+    mouse_buttons.reset_all();
+
+    for mut gamepad in &mut gamepads {
+        for button in gamepad.get_just_pressed().copied().collect::<Vec<_>>() {
+            match button {
+                GamepadButton::DPadUp => overworld.move_selection(0, -1),
+                GamepadButton::DPadRight => overworld.move_selection(1, 0),
+                GamepadButton::DPadDown => overworld.move_selection(0, 1),
+                GamepadButton::DPadLeft => overworld.move_selection(-1, 0),
+                GamepadButton::South => is_pressed = true,
+                GamepadButton::East => is_closed = true,
+                _ => {}
+            }
+            gamepad.digital_mut().clear_just_pressed(button);
+        }
+    }
+
+    for (interaction, OverworldNode(index)) in &node_query {
+        match *interaction {
+            Interaction::Pressed => {
+                overworld.selection = *index;
+                is_pressed = true;
+            }
+            Interaction::Hovered => overworld.selection = *index,
+            Interaction::None => {}
+        }
+    }
+
+    if is_closed {
+        overworld.is_open = false;
+        return;
+    }
+
+    if !is_pressed {
+        return;
+    }
+
+    let Some(node) = overworld.nodes.get(overworld.selection).copied() else {
+        return;
+    };
+
+    if !node.is_unlocked {
+        commands.trigger(ShowToast::new(format!(
+            "Level {} unlocks once the level before it is finished",
+            node.level
+        )));
+        return;
+    }
+
+    overworld.is_open = false;
+
+    // The hub is kept as it is, just like when walking into an entrance.
+    commands.trigger(SaveLevel {
+        target: SaveTarget::Memory,
+    });
+    commands.trigger(LoadLevel(node.level));
+}
+
+fn render_overworld(
+    mut panel_query: Query<&mut Node, With<OverworldPanel>>,
+    mut node_query: Query<(&OverworldNode, &mut BackgroundColor)>,
+    overworld: Res<Overworld>,
+) {
+    if !overworld.is_changed() {
+        return;
+    }
+
+    let Ok(mut panel_node) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    panel_node.display = if overworld.is_open {
+        Display::Flex
+    } else {
+        Display::None
+    };
+
+    for (OverworldNode(index), mut background_color) in &mut node_query {
+        let is_finished = overworld
+            .nodes
+            .get(*index)
+            .is_some_and(|node| node.is_finished);
+        background_color.0 = if *index == overworld.selection {
+            RED
+        } else if is_finished {
+            BLUE
+        } else {
+            NORMAL_GRAY
+        };
+    }
+}
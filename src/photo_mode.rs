@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::{
+    background::UpdateBackgroundTransform, game_state::GameState, on_keyboard_input,
+    screenshot::take_screenshot, ui_state::UiState, ChangeZoom,
+};
+
+/// Lets the player take pictures of the level without any UI in the way.
+///
+/// While photo mode is active, the arrow keys pan the camera without being
+/// held back by the edges of the level, `=` and `-` zoom in and out, Space
+/// takes a screenshot, and Escape leaves photo mode.
+#[derive(Default, Resource)]
+pub struct PhotoMode {
+    /// Camera offset and zoom factor from before photo mode was entered,
+    /// which are restored when it is left.
+    saved_camera: Option<((f32, f32), f32)>,
+}
+
+impl PhotoMode {
+    pub fn is_active(&self) -> bool {
+        self.saved_camera.is_some()
+    }
+}
+
+#[derive(Event)]
+pub struct TogglePhotoMode;
+
+pub struct PhotoModePlugin;
+
+impl Plugin for PhotoModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhotoMode>()
+            .add_event::<TogglePhotoMode>()
+            .add_observer(on_toggle_photo_mode)
+            .add_systems(Update, on_photo_mode_input.before(on_keyboard_input))
+            .add_systems(Update, hide_ui);
+    }
+}
+
+fn on_toggle_photo_mode(
+    _trigger: Trigger<TogglePhotoMode>,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut photo_mode: ResMut<PhotoMode>,
+    mut ui_state: ResMut<UiState>,
+) {
+    match photo_mode.saved_camera.take() {
+        Some((camera_offset, zoom_factor)) => {
+            ui_state.camera_offset = camera_offset;
+            ui_state.zoom_factor = zoom_factor;
+        }
+        None => {
+            photo_mode.saved_camera = Some((ui_state.camera_offset, ui_state.zoom_factor));
+        }
+    }
+
+    background_events.send(UpdateBackgroundTransform::Fast);
+}
+
+/// Handles the keys used in photo mode.
+///
+/// All keys are consumed while photo mode is active, so the player cannot
+/// move or open the menu until photo mode is left.
+fn on_photo_mode_input(
+    mut commands: Commands,
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut ui_state: ResMut<UiState>,
+    game_state: Res<GameState>,
+    photo_mode: Res<PhotoMode>,
+) {
+    if !photo_mode.is_active() {
+        return;
+    }
+
+    for key in keys.get_just_pressed() {
+        use KeyCode::*;
+        match key {
+            ArrowUp => ui_state.camera_offset.1 -= 1.,
+            ArrowRight => ui_state.camera_offset.0 += 1.,
+            ArrowDown => ui_state.camera_offset.1 += 1.,
+            ArrowLeft => ui_state.camera_offset.0 -= 1.,
+            Equal => {
+                commands.trigger(ChangeZoom {
+                    factor: 1.25,
+                    cursor_position: None,
+                });
+                continue;
+            }
+            Minus => {
+                commands.trigger(ChangeZoom {
+                    factor: 0.8,
+                    cursor_position: None,
+                });
+                continue;
+            }
+            Space => {
+                take_screenshot(&mut commands, game_state.current_level);
+                continue;
+            }
+            Escape => {
+                commands.trigger(TogglePhotoMode);
+                continue;
+            }
+            _ => continue,
+        }
+
+        background_events.send(UpdateBackgroundTransform::Fast);
+    }
+
+    let pressed_keys: Vec<KeyCode> = keys.get_just_pressed().copied().collect();
+    for key in pressed_keys {
+        keys.clear_just_pressed(key);
+    }
+}
+
+/// Hides all top-level UI nodes while photo mode is active, including those
+/// that get spawned while it is.
+fn hide_ui(
+    mut node_query: Query<&mut Visibility, (With<Node>, Without<Parent>)>,
+    photo_mode: Res<PhotoMode>,
+) {
+    if !photo_mode.is_active() && !photo_mode.is_changed() {
+        return;
+    }
+
+    let visibility = if photo_mode.is_active() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut node_visibility in &mut node_query {
+        node_visibility.set_if_neq(visibility);
+    }
+}
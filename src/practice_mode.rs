@@ -0,0 +1,177 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    background::Background,
+    editor::EditorState,
+    game_object::{Decoration, Player, Position, TileIndex},
+    game_state::GameState,
+    levels::Dimensions,
+    menu::MenuState,
+    on_keyboard_input, on_mouse_input,
+    toast::ShowToast,
+    utils::level_coords_from_pointer_coords,
+    LoadLevel, RestoreSnapshot, SaveLevel, SaveTarget,
+};
+
+/// Lets the player explore a level freely, without it counting towards their
+/// progress.
+///
+/// While practicing, clicking a free tile teleports the player there,
+/// `Ctrl+S` sets a savestate and `Ctrl+L` restores it. Reaching the exit
+/// doesn't finish the level.
+#[derive(Default, Resource)]
+pub struct PracticeMode {
+    /// The level that is being practiced.
+    level: Option<u16>,
+
+    /// Snapshot of the level to return to, which is kept until the player
+    /// leaves the level.
+    savestate: Option<String>,
+}
+
+impl PracticeMode {
+    pub fn is_active(&self) -> bool {
+        self.level.is_some()
+    }
+
+    pub fn savestate(&self) -> Option<&str> {
+        self.savestate.as_deref()
+    }
+
+    pub fn set_savestate(&mut self, contents: String) {
+        self.savestate = Some(contents);
+    }
+}
+
+#[derive(Event)]
+pub struct TogglePracticeMode;
+
+pub struct PracticeModePlugin;
+
+impl Plugin for PracticeModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PracticeMode>()
+            .add_observer(on_toggle_practice_mode)
+            .add_observer(on_level_loaded)
+            .add_systems(
+                Update,
+                (
+                    on_practice_input.before(on_keyboard_input),
+                    on_practice_mouse_input.before(on_mouse_input),
+                ),
+            );
+    }
+}
+
+fn on_toggle_practice_mode(
+    _trigger: Trigger<TogglePracticeMode>,
+    mut commands: Commands,
+    mut practice_mode: ResMut<PracticeMode>,
+    game_state: Res<GameState>,
+) {
+    if practice_mode.is_active() {
+        *practice_mode = PracticeMode::default();
+        commands.trigger(ShowToast::new("Practice mode off"));
+    } else if game_state.is_in_hub() {
+        commands.trigger(ShowToast::new("There is nothing to practice in the hub"));
+    } else {
+        practice_mode.level = Some(game_state.current_level);
+        commands.trigger(ShowToast::new(
+            "Practice mode on: click a tile to go there, Ctrl+S to save, Ctrl+L to load",
+        ));
+    }
+}
+
+/// Leaves practice mode when another level is loaded. Restarting the level
+/// that is being practiced keeps the savestate around.
+fn on_level_loaded(trigger: Trigger<LoadLevel>, mut practice_mode: ResMut<PracticeMode>) {
+    let LoadLevel(level) = trigger.event();
+    if practice_mode
+        .level
+        .is_some_and(|practiced| practiced != *level)
+    {
+        *practice_mode = PracticeMode::default();
+    }
+}
+
+fn on_practice_input(
+    mut commands: Commands,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    editor_state: Res<EditorState>,
+    menu_state: Res<MenuState>,
+    practice_mode: Res<PracticeMode>,
+) {
+    if !practice_mode.is_active() || editor_state.is_open || menu_state.is_open() {
+        return;
+    }
+
+    // Without Ctrl, S and L move the player instead. Holding Alt pans the
+    // camera using WASD.
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight])
+    {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyS) {
+        commands.trigger(SaveLevel {
+            target: SaveTarget::Practice,
+        });
+        keys.clear_just_pressed(KeyCode::KeyS);
+    }
+
+    if keys.just_pressed(KeyCode::KeyL) {
+        commands.trigger(RestoreSnapshot::Practice);
+        keys.clear_just_pressed(KeyCode::KeyL);
+    }
+}
+
+/// Teleports the player to the tile that is clicked, as long as there is
+/// nothing on it.
+#[expect(clippy::too_many_arguments)]
+fn on_practice_mouse_input(
+    mut player_query: Query<&mut Position, With<Player>>,
+    background_query: Query<&Transform, With<Background>>,
+    decoration_query: Query<(), With<Decoration>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    dimensions: Res<Dimensions>,
+    editor_state: Res<EditorState>,
+    menu_state: Res<MenuState>,
+    practice_mode: Res<PracticeMode>,
+    tile_index: Res<TileIndex>,
+) {
+    if !practice_mode.is_active()
+        || editor_state.is_open
+        || menu_state.is_open()
+        || !buttons.just_pressed(MouseButton::Left)
+    {
+        return;
+    }
+
+    let window = window_query.single();
+    let Some(cursor_position) = window.cursor_position() else {
+        return;
+    };
+
+    let Ok(transform) = background_query.get_single() else {
+        return;
+    };
+
+    let (x, y) =
+        level_coords_from_pointer_coords(cursor_position, *dimensions, transform, window.size());
+    let position: Position = (x as i16, y as i16).into();
+
+    let is_free = dimensions.contains(position)
+        && tile_index
+            .get(position)
+            .iter()
+            .all(|entity| decoration_query.contains(*entity));
+    if !is_free {
+        return;
+    }
+
+    for mut player_position in &mut player_query {
+        *player_position = position;
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::levels::LEVELS;
+
+/// Shuffles which entrance in the hub leads to which level, for replaying the
+/// game after finishing it.
+///
+/// Levels are stored using the levels of their original entrances, and only
+/// remapped when they are loaded. The permutation is derived from the seed,
+/// so the same seed always gives the same shuffle.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Randomizer {
+    pub seed: u64,
+
+    /// Maps the level of every original entrance to the level it leads to.
+    entrances: BTreeMap<u16, u16>,
+}
+
+impl Randomizer {
+    /// Creates the shuffle for the given seed. Entrances into hubs keep
+    /// leading to the same hub, so the worlds stay connected as they are.
+    pub fn new(seed: u64, is_hub_level: impl Fn(u16) -> bool) -> Self {
+        let levels: Vec<u16> = LEVELS
+            .iter()
+            .map(|(level, _)| *level)
+            .filter(|level| !is_hub_level(*level))
+            .collect();
+
+        let mut shuffled = levels.clone();
+        shuffled.shuffle(&mut StdRng::seed_from_u64(seed));
+
+        Self {
+            seed,
+            entrances: levels.into_iter().zip(shuffled).collect(),
+        }
+    }
+
+    /// Returns the level that the entrance originally leading to `level`
+    /// leads to instead.
+    pub fn level_for_entrance(&self, level: u16) -> u16 {
+        self.entrances.get(&level).copied().unwrap_or(level)
+    }
+
+    /// Returns the original level of the entrance that leads to `level`,
+    /// which is the inverse of [Self::level_for_entrance()].
+    pub fn entrance_for_level(&self, level: u16) -> u16 {
+        self.entrances
+            .iter()
+            .find(|(_, target)| **target == level)
+            .map_or(level, |(entrance, _)| *entrance)
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    credits::CreditsState,
+    cutscene::CutsceneState,
+    dialogue::DialogueState,
+    editor::EditorState,
+    fonts::Fonts,
+    game_object::{Player, Position},
+    menu::MenuState,
+    on_keyboard_input, setup,
+    timers::RewindTimer,
+    ExitState, LoadLevel, RestartLevel, RestoreSnapshot, SaveLevel, SaveTarget,
+};
+
+/// Maximum number of moves that can be rewound.
+const MAX_HISTORY_LENGTH: usize = 1000;
+
+/// Snapshots of the level after every move of the player, so the moves can be
+/// rewound.
+///
+/// Pressing Backspace or Z steps back a single move, while holding it keeps
+/// stepping back until it's released. Play resumes from wherever the rewind
+/// stopped.
+#[derive(Default, Resource)]
+pub struct MoveHistory {
+    snapshots: VecDeque<String>,
+    is_rewinding: bool,
+}
+
+impl MoveHistory {
+    /// Returns the snapshot of the level after the most recent move.
+    pub fn latest(&self) -> Option<&str> {
+        self.snapshots.back().map(String::as_str)
+    }
+
+    pub fn push(&mut self, contents: String) {
+        if self.latest() == Some(contents.as_str()) {
+            return;
+        }
+
+        if self.snapshots.len() == MAX_HISTORY_LENGTH {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(contents);
+    }
+
+    /// Forgets about the most recent move, so the latest snapshot is the one
+    /// from before it.
+    ///
+    /// Returns `false` if there is no move to rewind.
+    fn pop(&mut self) -> bool {
+        if self.snapshots.len() < 2 {
+            return false;
+        }
+
+        self.snapshots.pop_back();
+        true
+    }
+}
+
+#[derive(Component)]
+struct RewindOverlay;
+
+pub struct RewindPlugin;
+
+impl Plugin for RewindPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_rewind_overlay.after(setup))
+            .init_resource::<MoveHistory>()
+            .init_resource::<RewindTimer>()
+            .add_observer(clear_move_history::<LoadLevel>)
+            .add_observer(clear_move_history::<RestartLevel>)
+            .add_systems(Update, on_rewind_input.before(on_keyboard_input))
+            .add_systems(Update, render_rewind_overlay.after(on_rewind_input))
+            .add_systems(PostUpdate, record_moves);
+    }
+}
+
+fn setup_rewind_overlay(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            RewindOverlay,
+            BackgroundColor(BLUE.with_alpha(0.15)),
+            BorderColor(WHITE.with_alpha(0.5)),
+            GlobalZIndex(90),
+            Node {
+                display: Display::None,
+                justify_content: JustifyContent::Center,
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                border: UiRect::all(Val::Px(4.)),
+                padding: UiRect::top(Val::Px(20.)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new("<< Rewinding"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(28.),
+            ));
+        });
+}
+
+fn clear_move_history<E: Event>(_trigger: Trigger<E>, mut history: ResMut<MoveHistory>) {
+    *history = MoveHistory::default();
+}
+
+/// Records a snapshot whenever the player has moved, including when the player
+/// is spawned, so the level can be rewound to its initial state.
+///
+/// Runs after all the updates of the frame, so the snapshot includes the
+/// consequences of the move.
+fn record_moves(
+    mut commands: Commands,
+    player_query: Query<Ref<Position>, With<Player>>,
+    history: Res<MoveHistory>,
+) {
+    if history.is_rewinding {
+        return;
+    }
+
+    if player_query.iter().any(|position| position.is_changed()) {
+        commands.trigger(SaveLevel {
+            target: SaveTarget::History,
+        });
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn on_rewind_input(
+    mut commands: Commands,
+    mut history: ResMut<MoveHistory>,
+    mut timer: ResMut<RewindTimer>,
+    gamepads: Query<&Gamepad>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    dialogue_state: Res<DialogueState>,
+    editor_state: Res<EditorState>,
+    exit_state: Res<ExitState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    menu_state: Res<MenuState>,
+    time: Res<Time>,
+) {
+    let is_blocked = credits_state.is_playing()
+        || cutscene_state.is_playing()
+        || dialogue_state.is_open()
+        || editor_state.is_open
+        || menu_state.is_open()
+        || exit_state.next_level.is_some();
+
+    let rewind_keys = [KeyCode::Backspace, KeyCode::KeyZ];
+    let is_held = !is_blocked
+        && (keys.any_pressed(rewind_keys)
+            || gamepads
+                .iter()
+                .any(|gamepad| gamepad.pressed(GamepadButton::West)));
+
+    if !is_held {
+        if history.is_rewinding {
+            history.is_rewinding = false;
+        }
+        return;
+    }
+
+    let step = if history.is_rewinding {
+        timer.tick(time.delta());
+        timer.just_finished()
+    } else {
+        history.is_rewinding = true;
+        timer.reset();
+        true
+    };
+
+    if step && history.pop() {
+        commands.trigger(RestoreSnapshot::History);
+    }
+}
+
+fn render_rewind_overlay(
+    mut overlay_query: Query<&mut Node, With<RewindOverlay>>,
+    history: Res<MoveHistory>,
+) {
+    if !history.is_changed() {
+        return;
+    }
+
+    for mut overlay_node in &mut overlay_query {
+        let display = if history.is_rewinding {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if overlay_node.display != display {
+            overlay_node.display = display;
+        }
+    }
+}
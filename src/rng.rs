@@ -0,0 +1,60 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+
+/// Source of all randomness in the game.
+///
+/// The generator is reseeded at the start of every attempt at a level, so an
+/// attempt can be reproduced by passing its seed using `--seed`.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    fixed_seed: Option<u64>,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(fixed_seed: Option<u64>) -> Self {
+        let seed = fixed_seed.unwrap_or_default();
+        Self {
+            seed,
+            fixed_seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Returns the seed of the current attempt.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Reseeds the generator for a new attempt, using a random seed unless a
+    /// fixed seed was given.
+    fn start_attempt(&mut self) {
+        self.seed = self.fixed_seed.unwrap_or_else(|| thread_rng().gen());
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+impl Deref for GameRng {
+    type Target = StdRng;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rng
+    }
+}
+
+impl DerefMut for GameRng {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rng
+    }
+}
+
+/// Reseeds the generator whenever a new attempt at the level starts.
+///
+/// The seed is printed, so the attempt can be reproduced.
+pub fn reseed_rng<E: Event>(_trigger: Trigger<E>, mut rng: ResMut<GameRng>) {
+    rng.start_attempt();
+    info!("Attempt seed: {}", rng.seed());
+}
@@ -0,0 +1,289 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    editor::{on_editor_keyboard_input, on_editor_mouse_input, EditorState},
+    fonts::Fonts,
+    input_prompt::InputPrompt,
+    on_keyboard_input, on_mouse_input, setup,
+    toast::ShowToast,
+    utils::{ensure_chunky_dir, get_user_level_path},
+};
+
+/// Triggered when a level couldn't be saved to disk, so the player can decide
+/// what to do with their changes.
+#[derive(Clone, Event)]
+pub struct SaveFailed {
+    pub level: u16,
+    pub contents: String,
+    pub error: String,
+}
+
+/// The save that is waiting for the player to retry it or save it elsewhere.
+#[derive(Default, Resource)]
+struct FailedSave {
+    save: Option<SaveFailed>,
+    selection: SaveErrorButton,
+}
+
+#[derive(Component)]
+struct SaveErrorDialog;
+
+#[derive(Component)]
+struct SaveErrorText;
+
+#[derive(Clone, Copy, Component, Default, Eq, PartialEq)]
+enum SaveErrorButton {
+    #[default]
+    Retry,
+    SaveAs,
+    Cancel,
+}
+
+impl SaveErrorButton {
+    const ALL: [Self; 3] = [Self::Retry, Self::SaveAs, Self::Cancel];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Retry => "Retry",
+            Self::SaveAs => "Save As",
+            Self::Cancel => "Cancel",
+        }
+    }
+}
+
+pub struct SaveErrorPlugin;
+
+impl Plugin for SaveErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FailedSave>()
+            .add_observer(on_save_failed)
+            .add_systems(Startup, setup_save_error_dialog.after(setup))
+            .add_systems(
+                Update,
+                on_save_error_input
+                    .before(on_keyboard_input)
+                    .before(on_mouse_input)
+                    .before(on_editor_keyboard_input)
+                    .before(on_editor_mouse_input),
+            )
+            .add_systems(Update, render_save_error_dialog.after(on_save_error_input));
+    }
+}
+
+fn setup_save_error_dialog(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            SaveErrorDialog,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                width: Val::Px(500.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::all(Val::Auto),
+                padding: UiRect::all(Val::Px(20.)),
+                row_gap: Val::Px(10.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new("Could not save level"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(32.),
+            ));
+            cb.spawn((
+                SaveErrorText,
+                Text::default(),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(16.),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            cb.spawn(Node {
+                column_gap: Val::Px(10.),
+                margin: UiRect::top(Val::Px(10.)),
+                ..default()
+            })
+            .with_children(|cb| {
+                for button in SaveErrorButton::ALL {
+                    cb.spawn((
+                        button,
+                        Button,
+                        BackgroundColor(BLUE),
+                        Node {
+                            height: Val::Px(44.),
+                            width: Val::Px(130.),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|cb| {
+                        cb.spawn((
+                            Text::new(button.label()),
+                            TextColor(WHITE),
+                            TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                            Node {
+                                margin: UiRect::all(Val::Auto),
+                                ..default()
+                            },
+                        ));
+                    });
+                }
+            });
+            cb.spawn((
+                InputPrompt::new("{confirm} to choose"),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(14.),
+            ));
+        });
+}
+
+fn on_save_failed(trigger: Trigger<SaveFailed>, mut failed_save: ResMut<FailedSave>) {
+    *failed_save = FailedSave {
+        save: Some(trigger.event().clone()),
+        selection: SaveErrorButton::Retry,
+    };
+}
+
+/// Lets the player choose what to do with the level that couldn't be saved.
+///
+/// All input is consumed while the dialog is shown, so the editor doesn't act
+/// on it.
+fn on_save_error_input(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &SaveErrorButton), Changed<Interaction>>,
+    mut editor_state: ResMut<EditorState>,
+    mut failed_save: ResMut<FailedSave>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+) {
+    if failed_save.save.is_none() {
+        return;
+    }
+
+    let move_selection = |failed_save: &mut FailedSave, delta: isize| {
+        let index = SaveErrorButton::ALL
+            .iter()
+            .position(|button| *button == failed_save.selection)
+            .unwrap_or_default() as isize;
+        let len = SaveErrorButton::ALL.len() as isize;
+        failed_save.selection = SaveErrorButton::ALL[(index + delta).rem_euclid(len) as usize];
+    };
+
+    let mut is_pressed = false;
+
+    for key in keys.get_just_pressed() {
+        match key {
+            KeyCode::ArrowLeft => move_selection(&mut failed_save, -1),
+            KeyCode::ArrowRight => move_selection(&mut failed_save, 1),
+            KeyCode::Enter | KeyCode::Space => is_pressed = true,
+            KeyCode::Escape => {
+                failed_save.selection = SaveErrorButton::Cancel;
+                is_pressed = true;
+            }
+            _ => {}
+        }
+    }
+    keys.reset_all();
+    mouse_buttons.reset_all();
+
+    for (interaction, button) in &button_query {
+        match *interaction {
+            Interaction::Pressed => {
+                failed_save.selection = *button;
+                is_pressed = true;
+            }
+            Interaction::Hovered => failed_save.selection = *button,
+            Interaction::None => {}
+        }
+    }
+
+    if !is_pressed {
+        return;
+    }
+
+    let Some(save) = failed_save.save.take() else {
+        return;
+    };
+
+    let path = match failed_save.selection {
+        SaveErrorButton::Retry => get_user_level_path(save.level),
+        SaveErrorButton::SaveAs => save_as_path(save.level),
+        SaveErrorButton::Cancel => {
+            commands.trigger(ShowToast::new("Level not saved"));
+            return;
+        }
+    };
+
+    match fs::write(&path, &save.contents) {
+        Ok(()) => {
+            // A copy that is saved elsewhere doesn't get loaded by the game.
+            if failed_save.selection == SaveErrorButton::Retry {
+                editor_state.has_unsaved_changes = false;
+            }
+            commands.trigger(ShowToast::new(format!("Level saved to {}", path.display())));
+        }
+        Err(error) => {
+            error!("Could not save level: {error}");
+            failed_save.save = Some(SaveFailed {
+                error: error.to_string(),
+                ..save
+            });
+        }
+    }
+}
+
+fn render_save_error_dialog(
+    mut dialog_query: Query<&mut Node, With<SaveErrorDialog>>,
+    mut text_query: Query<&mut Text, With<SaveErrorText>>,
+    mut button_query: Query<(&SaveErrorButton, &mut BackgroundColor)>,
+    failed_save: Res<FailedSave>,
+) {
+    if !failed_save.is_changed() {
+        return;
+    }
+
+    let Ok(mut dialog_node) = dialog_query.get_single_mut() else {
+        return;
+    };
+
+    let Some(save) = &failed_save.save else {
+        dialog_node.display = Display::None;
+        return;
+    };
+
+    dialog_node.display = Display::Flex;
+
+    for mut text in &mut text_query {
+        text.0 = format!(
+            "{}\n\nRetry saving to {}, or save a copy elsewhere.",
+            save.error,
+            get_user_level_path(save.level).display()
+        );
+    }
+
+    for (button, mut background_color) in &mut button_query {
+        *background_color = if *button == failed_save.selection {
+            RED
+        } else {
+            BLUE
+        }
+        .into();
+    }
+}
+
+/// Returns a path in the chunky dir for saving a copy of a level, which
+/// doesn't overwrite any earlier copies.
+fn save_as_path(level: u16) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_millis())
+        .unwrap_or_default();
+    ensure_chunky_dir().join(format!("level{level:03}-{timestamp}"))
+}
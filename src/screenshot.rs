@@ -0,0 +1,71 @@
+use std::{fs, path::PathBuf, time::SystemTime};
+
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    render::view::screenshot::{save_to_disk, Screenshot, ScreenshotCaptured},
+};
+
+use crate::{game_state::GameState, toast::ShowToast, utils::ensure_chunky_dir};
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        // Runs before any other system can consume the key press.
+        app.add_systems(PreUpdate, on_screenshot_input.after(InputSystem));
+
+        // Clips are recorded from frames kept in memory, which mobile builds
+        // can do without.
+        #[cfg(feature = "clip_capture")]
+        app.add_plugins(crate::clip_capture::ClipCapturePlugin);
+    }
+}
+
+/// Takes a screenshot when F12 is pressed, no matter what is on screen.
+fn on_screenshot_input(
+    mut commands: Commands,
+    game_state: Res<GameState>,
+    keys: Res<ButtonInput<KeyCode>>,
+) {
+    if keys.just_pressed(KeyCode::F12) {
+        take_screenshot(&mut commands, game_state.current_level);
+    }
+}
+
+/// Captures the current frame of the primary window and saves it in the
+/// screenshots dir, named after the given level.
+///
+/// A toast with the path is shown once the screenshot has been captured, so
+/// that it doesn't end up in the screenshot itself.
+pub fn take_screenshot(commands: &mut Commands, level: u16) {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_millis())
+        .unwrap_or_default();
+    let path = screenshots_dir().join(format!("level{level:03}-{timestamp}.png"));
+
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_to_disk(path.clone()))
+        .observe(
+            move |_trigger: Trigger<ScreenshotCaptured>, mut commands: Commands| {
+                commands.trigger(ShowToast::new(format!(
+                    "Screenshot saved to {}",
+                    path.display()
+                )));
+            },
+        );
+}
+
+pub fn screenshots_dir() -> PathBuf {
+    let chunky_dir = ensure_chunky_dir();
+    let screenshots_dir = chunky_dir.join("screenshots");
+    match fs::create_dir_all(&screenshots_dir) {
+        Ok(()) => screenshots_dir,
+        Err(err) => {
+            warn!("Falling back to chunky dir ({chunky_dir:?}): {err:?}");
+            chunky_dir
+        }
+    }
+}
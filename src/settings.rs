@@ -0,0 +1,267 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use anyhow::Context;
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowMode, WindowPosition},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{background::UpdateBackgroundTransform, on_keyboard_input, utils::ensure_chunky_dir};
+
+/// Delay before saving changed settings, so that we don't write the file for
+/// every step while the window is being dragged or resized.
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 2.;
+
+/// Increment by which the UI scale is changed from the settings menu.
+const UI_SCALE_STEP: f32 = 0.25;
+
+/// Shortest delay and interval for repeating held moves, so a misconfigured
+/// setting can't make the player move every single frame.
+const MIN_MOVEMENT_REPEAT_MS: u32 = 50;
+
+/// Settings that are kept between launches.
+#[derive(Clone, Deserialize, PartialEq, Resource, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub fullscreen: bool,
+
+    /// Size of the window while it was last windowed.
+    pub window_size: Option<(u32, u32)>,
+
+    /// Position of the window while it was last windowed.
+    pub window_position: Option<(i32, i32)>,
+
+    /// Factor by which all UI nodes and text are scaled.
+    pub ui_scale: f32,
+
+    /// Address of the server to submit best times and moves to, such as
+    /// `http://example.com:8080`. Leaderboards are disabled without it.
+    pub leaderboard_url: Option<String>,
+
+    /// Name to show on the leaderboards.
+    pub player_name: String,
+
+    /// Enables the debug console in release builds. It's always enabled in
+    /// debug builds.
+    pub debug_console: bool,
+
+    /// Shows the direction keys that are pressed, so recorded runs can be
+    /// verified.
+    pub input_display: bool,
+
+    /// Turns off purely cosmetic motion and color changes, such as the
+    /// day/night cycle of the hub and the weather of levels.
+    pub reduce_motion: bool,
+
+    /// Time a direction needs to be held before the player moves again, in
+    /// milliseconds.
+    pub movement_repeat_delay_ms: u32,
+
+    /// Time between the moves that follow while the direction stays held, in
+    /// milliseconds.
+    pub movement_repeat_interval_ms: u32,
+
+    /// Pans across levels that don't fit in the window before the player
+    /// takes control, so they can scout the puzzle.
+    pub level_preview: bool,
+
+    /// Keeps the graves of the player across attempts at a level for the
+    /// rest of the session, as blocks that can be pushed around.
+    pub persistent_graves: bool,
+
+    /// Name of the theme pack in `~/.chunky/themes/` to load sprites from,
+    /// which takes effect on the next launch.
+    pub sprite_theme: Option<String>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            window_size: None,
+            window_position: None,
+            ui_scale: 1.,
+            leaderboard_url: None,
+            player_name: "Chunky".to_owned(),
+            debug_console: false,
+            input_display: false,
+            reduce_motion: false,
+            movement_repeat_delay_ms: 200,
+            movement_repeat_interval_ms: 200,
+            level_preview: false,
+            persistent_graves: false,
+            sprite_theme: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Loads the settings from disk, or returns `Self::default()` if they
+    /// could not be loaded.
+    pub fn load() -> Self {
+        let path = settings_path();
+        if !path.exists() {
+            return Self::default();
+        }
+
+        fs::read(&path)
+            .context("Can't read file")
+            .and_then(|json| serde_json::from_slice(&json).map_err(anyhow::Error::from))
+            .map(|settings: Self| Self {
+                ui_scale: settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE),
+                movement_repeat_delay_ms: settings
+                    .movement_repeat_delay_ms
+                    .max(MIN_MOVEMENT_REPEAT_MS),
+                movement_repeat_interval_ms: settings
+                    .movement_repeat_interval_ms
+                    .max(MIN_MOVEMENT_REPEAT_MS),
+                ..settings
+            })
+            .map_err(|err| error!("Can't load settings: {err}"))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        serde_json::to_vec(self)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| fs::write(settings_path(), json).context("Can't write file"))
+            .unwrap_or_else(|err| error!("Can't save settings: {err}"));
+    }
+
+    pub fn initial_window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        } else {
+            WindowMode::Windowed
+        }
+    }
+
+    pub fn initial_window_position(&self) -> WindowPosition {
+        match self.window_position {
+            Some((x, y)) => WindowPosition::At(IVec2::new(x, y)),
+            None => WindowPosition::Automatic,
+        }
+    }
+
+    pub fn movement_repeat_delay(&self) -> Duration {
+        Duration::from_millis(self.movement_repeat_delay_ms.into())
+    }
+
+    pub fn movement_repeat_interval(&self) -> Duration {
+        Duration::from_millis(self.movement_repeat_interval_ms.into())
+    }
+
+    /// Increases or decreases the UI scale by the given number of steps.
+    pub fn adjust_ui_scale(&mut self, steps: i8) {
+        self.ui_scale =
+            (self.ui_scale + steps as f32 * UI_SCALE_STEP).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+    }
+
+    /// Increases the UI scale by one step, or goes back to the smallest scale
+    /// once the largest is reached.
+    pub fn cycle_ui_scale(&mut self) {
+        self.ui_scale = if self.ui_scale >= MAX_UI_SCALE {
+            MIN_UI_SCALE
+        } else {
+            (self.ui_scale + UI_SCALE_STEP).min(MAX_UI_SCALE)
+        };
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, on_fullscreen_toggle.before(on_keyboard_input))
+            .add_systems(
+                Update,
+                (track_window_settings, save_settings)
+                    .chain()
+                    .after(on_fullscreen_toggle),
+            )
+            .add_systems(Update, apply_ui_scale);
+    }
+}
+
+fn settings_path() -> PathBuf {
+    ensure_chunky_dir().join("settings.json")
+}
+
+/// Toggles between windowed and fullscreen mode with F11 or Alt+Enter.
+fn on_fullscreen_toggle(
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    let alt_pressed = keys.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+    let toggled = keys.clear_just_pressed(KeyCode::F11)
+        || (alt_pressed && keys.clear_just_pressed(KeyCode::Enter));
+    if !toggled {
+        return;
+    }
+
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    window.mode = match window.mode {
+        WindowMode::Windowed => WindowMode::BorderlessFullscreen(MonitorSelection::Current),
+        _ => WindowMode::Windowed,
+    };
+}
+
+fn track_window_settings(
+    window_query: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut settings: ResMut<Settings>,
+) {
+    let Ok(window) = window_query.get_single() else {
+        return;
+    };
+
+    let fullscreen = window.mode != WindowMode::Windowed;
+    let mut new_settings = Settings {
+        fullscreen,
+        ..settings.clone()
+    };
+
+    // The fullscreen size and position are not worth restoring, since they
+    // follow from the monitor.
+    if !fullscreen {
+        new_settings.window_size = Some((
+            window.resolution.width().round() as u32,
+            window.resolution.height().round() as u32,
+        ));
+        if let WindowPosition::At(position) = window.position {
+            new_settings.window_position = Some((position.x, position.y));
+        }
+    }
+
+    settings.set_if_neq(new_settings);
+}
+
+fn apply_ui_scale(
+    mut background_events: EventWriter<UpdateBackgroundTransform>,
+    mut ui_scale: ResMut<UiScale>,
+    settings: Res<Settings>,
+) {
+    if settings.is_changed() && ui_scale.0 != settings.ui_scale {
+        ui_scale.0 = settings.ui_scale;
+
+        // The editor panel takes up a different amount of space now.
+        background_events.send(UpdateBackgroundTransform::Immediate);
+    }
+}
+
+fn save_settings(settings: Res<Settings>, time: Res<Time>, mut save_at: Local<Option<Duration>>) {
+    if settings.is_changed() && !settings.is_added() {
+        *save_at = Some(time.elapsed() + SAVE_DELAY);
+    }
+
+    if save_at.is_some_and(|save_at| time.elapsed() >= save_at) {
+        *save_at = None;
+        settings.save();
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    fonts::Fonts,
+    game_object::{Player, Position, Sign, TileIndex},
+    setup,
+};
+
+#[derive(Component)]
+pub struct SignDialog;
+
+#[derive(Component)]
+struct SignDialogText;
+
+pub struct SignDialogPlugin;
+
+impl Plugin for SignDialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_sign_dialog.after(setup))
+            .add_systems(PostUpdate, update_sign_dialog);
+    }
+}
+
+fn setup_sign_dialog(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            SignDialog,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                max_width: Val::Px(500.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::horizontal(Val::Auto),
+                padding: UiRect::all(Val::Px(16.)),
+                bottom: Val::Px(80.),
+                left: Val::Px(0.),
+                right: Val::Px(0.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                SignDialogText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    margin: UiRect::all(Val::Auto),
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Shows the message of a sign while the player stands on or next to it.
+fn update_sign_dialog(
+    mut dialog_query: Query<&mut Node, With<SignDialog>>,
+    mut text_query: Query<&mut Text, With<SignDialogText>>,
+    player_query: Query<Ref<Position>, With<Player>>,
+    sign_query: Query<&Sign>,
+    tile_index: Res<TileIndex>,
+) {
+    let player_position = player_query.get_single().ok();
+    if player_position
+        .as_ref()
+        .is_some_and(|position| !position.is_changed())
+    {
+        return;
+    }
+
+    let message = player_position.and_then(|position| {
+        let Position { x, y } = *position;
+        [(0, 0), (0, -1), (1, 0), (0, 1), (-1, 0)]
+            .into_iter()
+            .find_map(|(dx, dy)| {
+                sign_query
+                    .iter_many(tile_index.get(Position {
+                        x: x + dx,
+                        y: y + dy,
+                    }))
+                    .find(|sign| !sign.0.is_empty())
+            })
+    });
+
+    let Ok(mut dialog_node) = dialog_query.get_single_mut() else {
+        return;
+    };
+
+    match message {
+        Some(Sign(message)) => {
+            for mut text in &mut text_query {
+                if text.0 != *message {
+                    text.0.clone_from(message);
+                }
+            }
+            dialog_node.display = Display::Flex;
+        }
+        None if dialog_node.display != Display::None => dialog_node.display = Display::None,
+        None => {}
+    }
+}
@@ -0,0 +1,405 @@
+use std::{collections::BTreeMap, fmt::Display, time::Duration};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    background::Background,
+    editor::EditorState,
+    fonts::Fonts,
+    game_object::{
+        behaviors::*, update_tile_index, Direction, Exit, GameObjectAssets, ObjectType, Player,
+        Position, TileIndex,
+    },
+    game_state::GameState,
+    levels::{DiagonalMovement, Dimensions, Level, PushStrength},
+    menu::MenuState,
+    modifiers::MirroredControls,
+    on_game_event, on_keyboard_input, on_mouse_input,
+    photo_mode::PhotoMode,
+    spawn_level_objects, spawn_object,
+    timers::{AnimationTimer, MovementTimer, SlideTimer, TemporaryTimer, TransporterTimer},
+    ui_state::UiState,
+    GameEvent, PressedTriggers, SpawnObject,
+};
+
+/// Time that passes with every frame of a headless simulation.
+const FRAME_DURATION: Duration = Duration::from_millis(50);
+
+/// Number of frames that run after every move, which gives moving creatures
+/// the time to take a single step of their own.
+const FRAMES_PER_MOVE: u32 = 10;
+
+/// Registers the systems that make up the rules of the game: moving the
+/// player and the objects around them, and the behaviors that follow.
+///
+/// Nothing here depends on rendering or windowing, so the same rules apply to
+/// the game and to a headless [Simulation].
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DiagonalMovement>()
+            .init_resource::<Dimensions>()
+            .init_resource::<MovementTimer>()
+            .init_resource::<PressedTriggers>()
+            .init_resource::<PushStrength>()
+            .init_resource::<SlideTimer>()
+            .init_resource::<TemporaryTimer>()
+            .init_resource::<TileIndex>()
+            .init_resource::<TransporterTimer>()
+            .add_event::<GameEvent>()
+            .add_systems(PreUpdate, update_tile_index)
+            .add_systems(
+                Update,
+                (
+                    (check_for_slippery, check_for_transporter).chain(),
+                    despawn_volatile_objects,
+                    move_objects,
+                    on_game_event,
+                )
+                    .after(on_keyboard_input),
+            )
+            .add_systems(
+                Update,
+                check_for_bridges
+                    .after(move_objects)
+                    .after(on_game_event)
+                    .before(check_for_liquid),
+            )
+            // Deaths are checked right after moving, so the player is gone
+            // before the move gets recorded in the history.
+            .add_systems(
+                Update,
+                (check_for_deadly, check_for_explosive, check_for_liquid)
+                    .chain()
+                    .after(move_objects)
+                    .after(on_game_event),
+            )
+            .add_systems(
+                Update,
+                (check_for_transform_on_push, check_for_triggers)
+                    .after(check_for_liquid)
+                    .after(on_keyboard_input)
+                    .after(move_objects),
+            )
+            .add_systems(Update, count_down_timer_gates.after(check_for_triggers))
+            .add_systems(
+                Update,
+                (check_for_key, check_for_paint, check_for_teleporter)
+                    .after(move_objects)
+                    .after(on_mouse_input),
+            );
+    }
+}
+
+/// Runs a level without rendering or windowing, for tests and tools.
+///
+/// Time only passes while the simulation is stepped, so the same level and
+/// moves always lead to the same outcome. Progress is never saved, because
+/// the level is played as if it were a demo in the hub.
+pub struct Simulation {
+    app: App,
+}
+
+impl Simulation {
+    pub fn new(level: Level) -> Self {
+        let mut game_state = GameState::default();
+        game_state.is_playing_demo = true;
+
+        let mut menu_state = MenuState::default();
+        menu_state.close();
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, SimulationPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_DURATION))
+            .init_resource::<AnimationTimer>()
+            .init_resource::<EditorState>()
+            .init_resource::<Fonts>()
+            .init_resource::<GameObjectAssets>()
+            .init_resource::<MirroredControls>()
+            .init_resource::<PhotoMode>()
+            .init_resource::<UiState>()
+            .insert_resource(game_state)
+            .insert_resource(menu_state)
+            .insert_resource(level.dimensions)
+            .insert_resource(level.push_strength)
+            .insert_resource(level.diagonal_movement)
+            .add_event::<SpawnObject>()
+            .add_observer(spawn_object);
+
+        let objects: Vec<_> = level
+            .objects
+            .into_iter()
+            .flat_map(|(object_type, initial_positions)| {
+                initial_positions
+                    .into_iter()
+                    .map(move |initial_position| (object_type, initial_position))
+            })
+            .collect();
+
+        let world = app.world_mut();
+        let assets = world.resource::<GameObjectAssets>().clone();
+        let fonts = world.resource::<Fonts>().clone();
+        let mut tile_index = TileIndex::default();
+        world
+            .commands()
+            .spawn((Background, Transform::default(), Visibility::default()))
+            .with_children(|cb| {
+                spawn_level_objects(cb, &objects, |_| true, &mut tile_index, &assets, &fonts);
+            });
+        world.flush();
+        world.insert_resource(tile_index);
+
+        // The first frame doesn't advance time yet.
+        app.update();
+
+        Self { app }
+    }
+
+    /// Moves the player in the given direction and lets the level run until
+    /// the next move.
+    pub fn step(&mut self, direction: Direction) {
+        let (dx, dy) = direction.as_delta();
+        self.app
+            .world_mut()
+            .send_event(GameEvent::MovePlayer(dx, dy));
+        self.wait();
+    }
+
+    /// Lets the level run for as long as a move takes, without moving the
+    /// player.
+    pub fn wait(&mut self) {
+        for _ in 0..FRAMES_PER_MOVE {
+            self.app.update();
+        }
+    }
+
+    /// Returns the object types found at every position of the level.
+    pub fn grid(&mut self) -> BTreeMap<Position, Vec<ObjectType>> {
+        let mut grid: BTreeMap<Position, Vec<ObjectType>> = BTreeMap::new();
+        let mut query = self.app.world_mut().query::<(&ObjectType, &Position)>();
+        for (object_type, position) in query.iter(self.app.world()) {
+            grid.entry(*position).or_default().push(*object_type);
+        }
+        for object_types in grid.values_mut() {
+            object_types.sort();
+        }
+        grid
+    }
+
+    pub fn is_player_alive(&mut self) -> bool {
+        !self.player_positions().is_empty()
+    }
+
+    /// Returns whether the player is standing on an exit.
+    pub fn has_reached_exit(&mut self) -> bool {
+        let exits: Vec<Position> = self
+            .app
+            .world_mut()
+            .query_filtered::<&Position, With<Exit>>()
+            .iter(self.app.world())
+            .copied()
+            .collect();
+        self.player_positions()
+            .iter()
+            .any(|position| exits.contains(position))
+    }
+
+    fn player_positions(&mut self) -> Vec<Position> {
+        self.app
+            .world_mut()
+            .query_filtered::<&Position, With<Player>>()
+            .iter(self.app.world())
+            .copied()
+            .collect()
+    }
+}
+
+/// Outcome of playing a level with a sequence of moves.
+pub struct SimulationResult {
+    pub grid: BTreeMap<Position, Vec<ObjectType>>,
+    pub is_player_alive: bool,
+    pub has_reached_exit: bool,
+}
+
+impl Display for SimulationResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (position, object_types) in &self.grid {
+            let object_types: Vec<String> = object_types.iter().map(ToString::to_string).collect();
+            writeln!(
+                f,
+                "{},{}: {}",
+                position.x,
+                position.y,
+                object_types.join(", ")
+            )?;
+        }
+
+        if self.has_reached_exit {
+            writeln!(f, "The player reached an exit.")
+        } else if self.is_player_alive {
+            writeln!(f, "The player didn't reach an exit.")
+        } else {
+            writeln!(f, "The player died.")
+        }
+    }
+}
+
+/// Plays the given level with the given moves, stopping early once the player
+/// reaches an exit or dies.
+pub fn simulate(level: Level, moves: &[Direction]) -> SimulationResult {
+    let mut simulation = Simulation::new(level);
+    for direction in moves {
+        if simulation.has_reached_exit() || !simulation.is_player_alive() {
+            break;
+        }
+
+        simulation.step(*direction);
+    }
+
+    SimulationResult {
+        grid: simulation.grid(),
+        is_player_alive: simulation.is_player_alive(),
+        has_reached_exit: simulation.has_reached_exit(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{hint::black_box, time::Instant};
+
+    use super::*;
+    use crate::levels::LEVELS;
+
+    fn load(content: &str) -> Level {
+        Level::load(content).expect("test level should load")
+    }
+
+    /// Returns a section of a level with objects at all the given positions.
+    fn section(object_type: &str, positions: impl Iterator<Item = (i16, i16)>) -> String {
+        let positions: Vec<String> = positions.map(|(x, y)| format!("{x},{y}")).collect();
+        format!("\n\n[{object_type}]\nPosition={}", positions.join(";"))
+    }
+
+    /// Runs `f` the given number of times and prints how long it took on
+    /// average.
+    fn time<T>(name: &str, iterations: u32, mut f: impl FnMut() -> T) {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            black_box(f());
+        }
+        println!("{name}: {:?} per iteration", start.elapsed() / iterations);
+    }
+
+    #[test]
+    #[ignore = "timing test, run with `cargo test --release -- --ignored --nocapture`"]
+    fn time_pushing_among_many_objects() {
+        // The player pushes a block along the top row, while blocks fill
+        // every other tile of the rest of the level.
+        let size = 512;
+        let blocks = (1..=size)
+            .flat_map(|x| (3..=size).map(move |y| (x, y)))
+            .filter(|(x, y)| (x + y) % 2 == 0)
+            .chain([(2, 1)]);
+        let content = format!(
+            "[General]\nWidth={size}\nHeight={size}{}{}",
+            section("Player", [(1, 1)].into_iter()),
+            section("BlueBlock", blocks)
+        );
+
+        let mut simulation = Simulation::new(load(&content));
+        time("Pushing among many objects", 100, || {
+            simulation.step(Direction::Right)
+        });
+        assert!(simulation.is_player_alive());
+    }
+
+    #[test]
+    #[ignore = "timing test, run with `cargo test --release -- --ignored --nocapture`"]
+    fn time_transporters_on_a_belt_heavy_level() {
+        // Every tile is a transporter, going right and left on alternating
+        // rows, and every third tile carries a block.
+        let size = 256;
+        let tiles = || (1..=size).flat_map(move |x| (1..=size).map(move |y| (x, y)));
+        let rows = |parity| {
+            let positions: Vec<String> = tiles()
+                .filter(|(_, y)| y % 2 == parity)
+                .map(|(x, y)| format!("{x},{y}"))
+                .collect();
+            positions.join(";")
+        };
+        let content = format!(
+            "[General]\nWidth={size}\nHeight={size}{}\n\n\
+             [Transporter]\nDirection=Right\nPosition={}\nDirection=Left\nPosition={}",
+            section("BlueBlock", tiles().filter(|(x, y)| (x + y) % 3 == 0)),
+            rows(0),
+            rows(1)
+        );
+
+        let mut simulation = Simulation::new(load(&content));
+        time("Transporters on a belt-heavy level", 20, || {
+            simulation.wait()
+        });
+    }
+
+    #[test]
+    #[ignore = "timing test, run with `cargo test --release -- --ignored --nocapture`"]
+    fn time_loading_and_saving_the_hub() {
+        let (_, content) = LEVELS[0];
+        time("Loading the hub", 100, || load(content));
+        time("Loading and saving the hub", 100, || load(content).save());
+    }
+
+    #[test]
+    fn player_pushes_block() {
+        let level = load(
+            "[General]\nWidth=4\nHeight=1\n\n\
+             [Player]\nPosition=1,1\n\n\
+             [BlueBlock]\nPosition=2,1\n",
+        );
+
+        let result = simulate(level, &[Direction::Right]);
+
+        assert!(result.is_player_alive);
+        assert_eq!(
+            result.grid.get(&Position { x: 2, y: 1 }),
+            Some(&vec![ObjectType::Player])
+        );
+        assert_eq!(
+            result.grid.get(&Position { x: 3, y: 1 }),
+            Some(&vec![ObjectType::BlueBlock])
+        );
+    }
+
+    #[test]
+    fn player_reaches_exit() {
+        let level = load(
+            "[General]\nWidth=3\nHeight=1\n\n\
+             [Player]\nPosition=1,1\n\n\
+             [Exit]\nPosition=3,1\n",
+        );
+
+        let result = simulate(
+            level,
+            &[Direction::Right, Direction::Right, Direction::Right],
+        );
+
+        assert!(result.has_reached_exit);
+        assert!(result.is_player_alive);
+    }
+
+    #[test]
+    fn player_drowns() {
+        let level = load(
+            "[General]\nWidth=3\nHeight=1\n\n\
+             [Player]\nPosition=1,1\n\n\
+             [Water]\nPosition=2,1\n\n\
+             [Exit]\nPosition=3,1\n",
+        );
+
+        let result = simulate(level, &[Direction::Right, Direction::Right]);
+
+        assert!(!result.is_player_alive);
+        assert!(!result.has_reached_exit);
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    achievements::Achievement,
+    game_object::{GameObjectAssets, Player},
+    game_state::GameState,
+};
+
+/// Looks for the player to choose from in the settings. All of them are based
+/// on the same sprite, recolored to their own tint.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum PlayerSkin {
+    #[default]
+    Classic,
+    Midnight,
+    Ruby,
+    Mint,
+    Golden,
+}
+
+impl PlayerSkin {
+    pub const ALL: [Self; 5] = [
+        Self::Classic,
+        Self::Midnight,
+        Self::Ruby,
+        Self::Mint,
+        Self::Golden,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Classic => "Classic",
+            Self::Midnight => "Midnight",
+            Self::Ruby => "Ruby",
+            Self::Mint => "Mint",
+            Self::Golden => "Golden",
+        }
+    }
+
+    /// Returns the achievement that unlocks the skin, or `None` if it can be
+    /// chosen from the start.
+    pub fn required_achievement(self) -> Option<Achievement> {
+        match self {
+            Self::Classic | Self::Midnight => None,
+            Self::Ruby => Some(Achievement::PushFiveHundredBlocks),
+            Self::Mint => Some(Achievement::FindSecretExit),
+            Self::Golden => Some(Achievement::CompleteGame),
+        }
+    }
+
+    pub fn is_unlocked(self, game_state: &GameState) -> bool {
+        self.required_achievement()
+            .is_none_or(|achievement| game_state.achievements.contains(&achievement))
+    }
+
+    /// Returns the color by which the brightness of the player sprite is
+    /// multiplied, or `None` if the sprite is used as it is.
+    pub fn tint(self) -> Option<[f32; 3]> {
+        match self {
+            Self::Classic => None,
+            Self::Midnight => Some([0.55, 0.6, 1.1]),
+            Self::Ruby => Some([1.3, 0.45, 0.5]),
+            Self::Mint => Some([0.6, 1.25, 0.95]),
+            Self::Golden => Some([1.4, 1.15, 0.45]),
+        }
+    }
+}
+
+pub struct SkinsPlugin;
+
+impl Plugin for SkinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, apply_player_skin);
+    }
+}
+
+/// Swaps in the sprite of the chosen skin, both for the player that is
+/// already spawned and for players that are spawned later.
+///
+/// Skins that are no longer unlocked, such as after resetting the progress,
+/// fall back to the classic look.
+fn apply_player_skin(
+    mut player_query: Query<&mut Sprite, With<Player>>,
+    mut applied_skin: Local<Option<PlayerSkin>>,
+    mut assets: ResMut<GameObjectAssets>,
+    game_state: Res<GameState>,
+) {
+    let skin = if game_state.player_skin.is_unlocked(&game_state) {
+        game_state.player_skin
+    } else {
+        PlayerSkin::Classic
+    };
+    if *applied_skin == Some(skin) {
+        return;
+    }
+
+    assets.set_player_skin(skin);
+    for mut sprite in &mut player_query {
+        assets.player.set_frame(&mut sprite, 0);
+    }
+    *applied_skin = Some(skin);
+}
@@ -0,0 +1,242 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    constants::*, editor::Editor, fonts::Fonts, game_state::GameState, gameover::GameOver,
+    level_complete::LevelComplete, menu::MenuState, on_game_event, setup, toast::ShowToast,
+    utils::format_duration, GameEvent,
+};
+
+/// Number of recent inputs that are shown.
+const MAX_SHOWN_INPUTS: usize = 12;
+
+/// Display mode for streaming and recording gameplay, which hides the HUD
+/// and overlays in favor of a clean widget with the moves and time of the
+/// current attempt.
+///
+/// Toggling with F8 cycles from off to on, then to on with the recent inputs
+/// shown, and back to off. Menus, the editor and the game over and completion
+/// screens stay visible, since the player needs them to get anywhere.
+#[derive(Default, Resource)]
+pub struct StreamerMode {
+    is_active: bool,
+    shows_inputs: bool,
+
+    /// Recent moves, written like the moves of the attract mode's demo: `U`,
+    /// `R`, `D` and `L`, in lowercase for pulls.
+    recent_inputs: VecDeque<char>,
+}
+
+#[derive(Event)]
+pub struct ToggleStreamerMode;
+
+#[derive(Component)]
+struct StreamerWidget;
+
+#[derive(Component)]
+struct StreamerMovesText;
+
+#[derive(Component)]
+struct StreamerTimeText;
+
+#[derive(Component)]
+struct StreamerInputsText;
+
+pub struct StreamerModePlugin;
+
+impl Plugin for StreamerModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StreamerMode>()
+            .add_event::<ToggleStreamerMode>()
+            .add_observer(on_toggle_streamer_mode)
+            .add_systems(Startup, setup_streamer_widget.after(setup))
+            .add_systems(Update, record_inputs.after(on_game_event))
+            .add_systems(Update, (hide_overlays, render_streamer_widget));
+    }
+}
+
+fn setup_streamer_widget(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            StreamerWidget,
+            BackgroundColor(GRAY_BACKGROUND.with_alpha(0.8)),
+            GlobalZIndex(90),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::End,
+                padding: UiRect::all(Val::Px(16.)),
+                top: Val::Px(20.),
+                right: Val::Px(20.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                StreamerMovesText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(48.),
+            ));
+            cb.spawn((
+                StreamerTimeText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(32.),
+            ));
+            cb.spawn((
+                StreamerInputsText,
+                Text::default(),
+                TextColor(LIGHT_GRAY),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(24.),
+            ));
+        });
+}
+
+fn on_toggle_streamer_mode(
+    _trigger: Trigger<ToggleStreamerMode>,
+    mut commands: Commands,
+    mut streamer_mode: ResMut<StreamerMode>,
+) {
+    let (is_active, shows_inputs, message) =
+        match (streamer_mode.is_active, streamer_mode.shows_inputs) {
+            (false, _) => (true, false, "Streamer mode on"),
+            (true, false) => (true, true, "Streamer mode on, showing inputs"),
+            (true, true) => (false, false, "Streamer mode off"),
+        };
+
+    *streamer_mode = StreamerMode {
+        is_active,
+        shows_inputs,
+        recent_inputs: VecDeque::new(),
+    };
+    commands.trigger(ShowToast::new(message));
+}
+
+fn record_inputs(mut game_events: EventReader<GameEvent>, mut streamer_mode: ResMut<StreamerMode>) {
+    if !streamer_mode.shows_inputs {
+        game_events.clear();
+        return;
+    }
+
+    for event in game_events.read() {
+        let (dx, dy, is_pull) = match event {
+            GameEvent::MovePlayer(dx, dy) => (*dx, *dy, false),
+            GameEvent::PullPlayer(dx, dy) => (*dx, *dy, true),
+        };
+        let direction = match (dx, dy) {
+            (0, -1) => 'U',
+            (1, 0) => 'R',
+            (0, 1) => 'D',
+            (-1, 0) => 'L',
+            _ => continue,
+        };
+
+        if streamer_mode.recent_inputs.len() == MAX_SHOWN_INPUTS {
+            streamer_mode.recent_inputs.pop_front();
+        }
+        streamer_mode.recent_inputs.push_back(if is_pull {
+            direction.to_ascii_lowercase()
+        } else {
+            direction
+        });
+    }
+}
+
+/// Hides all top-level UI nodes other than the streamer widget, unless the
+/// player is in a menu.
+#[expect(clippy::type_complexity)]
+fn hide_overlays(
+    mut node_query: Query<
+        &mut Visibility,
+        (
+            With<Node>,
+            Without<Parent>,
+            Without<StreamerWidget>,
+            Without<Editor>,
+            Without<GameOver>,
+            Without<LevelComplete>,
+        ),
+    >,
+    menu_state: Res<MenuState>,
+    streamer_mode: Res<StreamerMode>,
+) {
+    if !streamer_mode.is_active && !streamer_mode.is_changed() {
+        return;
+    }
+
+    let visibility = if streamer_mode.is_active && !menu_state.is_open() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut node_visibility in &mut node_query {
+        node_visibility.set_if_neq(visibility);
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn render_streamer_widget(
+    mut widget_query: Query<&mut Node, With<StreamerWidget>>,
+    mut moves_query: Query<&mut Text, (With<StreamerMovesText>, Without<StreamerTimeText>)>,
+    mut time_query: Query<&mut Text, (With<StreamerTimeText>, Without<StreamerInputsText>)>,
+    mut inputs_query: Query<&mut Text, (With<StreamerInputsText>, Without<StreamerMovesText>)>,
+    game_state: Res<GameState>,
+    menu_state: Res<MenuState>,
+    streamer_mode: Res<StreamerMode>,
+    time: Res<Time>,
+) {
+    let Ok(mut widget_node) = widget_query.get_single_mut() else {
+        return;
+    };
+
+    let is_shown = streamer_mode.is_active && !game_state.is_in_hub() && !menu_state.is_open();
+    let display = if is_shown {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if widget_node.display != display {
+        widget_node.display = display;
+    }
+
+    if !is_shown {
+        return;
+    }
+
+    let moves = game_state.current_moves();
+    let moves_text = if moves == 1 {
+        "1 move".to_owned()
+    } else {
+        format!("{moves} moves")
+    };
+    for mut text in &mut moves_query {
+        if text.0 != moves_text {
+            text.0.clone_from(&moves_text);
+        }
+    }
+
+    let time_text = format_duration(
+        game_state
+            .current_attempt_duration(time.elapsed())
+            .as_secs_f32(),
+    );
+    for mut text in &mut time_query {
+        if text.0 != time_text {
+            text.0.clone_from(&time_text);
+        }
+    }
+
+    if streamer_mode.is_changed() {
+        let inputs_text: String = streamer_mode
+            .recent_inputs
+            .iter()
+            .map(|input| format!("{input} "))
+            .collect();
+        for mut text in &mut inputs_query {
+            text.0 = inputs_text.trim_end().to_owned();
+        }
+    }
+}
@@ -0,0 +1,400 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+use crate::{constants::*, fonts::Fonts, input_prompt::InputDevice, setup};
+
+/// Characters on the on-screen keyboard, by row. The last row holds the
+/// special keys.
+const KEY_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl:", "zxcvbnm./_-"];
+
+const SPECIAL_KEYS: [OnScreenKey; 4] = [
+    OnScreenKey::Shift,
+    OnScreenKey::Space,
+    OnScreenKey::Backspace,
+    OnScreenKey::Done,
+];
+
+/// Text the player can edit, which gets rendered into the [Text] of the same
+/// entity.
+///
+/// While editing, typing on a physical keyboard goes into the input. Players
+/// who last used a gamepad or touch screen get an on-screen keyboard instead.
+#[derive(Component)]
+#[require(Text)]
+pub struct TextInput {
+    pub value: String,
+    is_editing: bool,
+}
+
+impl TextInput {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            is_editing: false,
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.is_editing
+    }
+
+    pub fn set_editing(&mut self, is_editing: bool) {
+        self.is_editing = is_editing;
+    }
+
+    fn render(&self) -> String {
+        if self.is_editing {
+            format!("{}|", self.value)
+        } else {
+            self.value.clone()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum OnScreenKey {
+    Character(char),
+    Shift,
+    Space,
+    Backspace,
+    Done,
+}
+
+impl OnScreenKey {
+    fn label(self, is_shifted: bool) -> String {
+        match self {
+            Self::Character(character) if is_shifted => character.to_uppercase().to_string(),
+            Self::Character(character) => character.to_string(),
+            Self::Shift => "Shift".to_owned(),
+            Self::Space => "Space".to_owned(),
+            Self::Backspace => "Delete".to_owned(),
+            Self::Done => "Done".to_owned(),
+        }
+    }
+
+    fn width(self) -> f32 {
+        match self {
+            Self::Character(_) => 40.,
+            Self::Shift | Self::Space | Self::Backspace | Self::Done => 100.,
+        }
+    }
+}
+
+/// Returns the keys of the on-screen keyboard, by row.
+fn key_rows() -> Vec<Vec<OnScreenKey>> {
+    KEY_ROWS
+        .iter()
+        .map(|row| row.chars().map(OnScreenKey::Character).collect())
+        .chain([SPECIAL_KEYS.to_vec()])
+        .collect()
+}
+
+#[derive(Default, Resource)]
+struct OnScreenKeyboard {
+    /// Row and column of the selected key.
+    selection: (usize, usize),
+    is_shifted: bool,
+}
+
+impl OnScreenKeyboard {
+    fn move_selection(&mut self, dx: isize, dy: isize) {
+        let rows = key_rows();
+        let (row, column) = self.selection;
+        let row = (row as isize + dy).rem_euclid(rows.len() as isize) as usize;
+        let len = rows[row].len() as isize;
+        let column = (column.min(rows[row].len() - 1) as isize + dx).rem_euclid(len) as usize;
+        self.selection = (row, column);
+    }
+
+    fn selected_key(&self) -> OnScreenKey {
+        let (row, column) = self.selection;
+        key_rows()[row][column]
+    }
+
+    /// Applies the key to the input that is being edited.
+    fn press(&mut self, key: OnScreenKey, input: &mut TextInput) {
+        match key {
+            OnScreenKey::Character(character) if self.is_shifted => {
+                input.value.extend(character.to_uppercase());
+                self.is_shifted = false;
+            }
+            OnScreenKey::Character(character) => input.value.push(character),
+            OnScreenKey::Shift => self.is_shifted ^= true,
+            OnScreenKey::Space => input.value.push(' '),
+            OnScreenKey::Backspace => {
+                input.value.pop();
+            }
+            OnScreenKey::Done => input.is_editing = false,
+        }
+    }
+}
+
+#[derive(Component)]
+struct OnScreenKeyboardPanel;
+
+#[derive(Component)]
+struct OnScreenKeyButton {
+    key: OnScreenKey,
+    selection: (usize, usize),
+}
+
+pub struct TextInputPlugin;
+
+impl Plugin for TextInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OnScreenKeyboard>()
+            .add_systems(Startup, setup_on_screen_keyboard.after(setup))
+            .add_systems(
+                PreUpdate,
+                (on_text_input_keyboard_input, on_on_screen_keyboard_input).after(InputSystem),
+            )
+            .add_systems(Update, on_on_screen_key_interaction)
+            .add_systems(
+                Update,
+                (render_text_inputs, render_on_screen_keyboard).after(on_on_screen_key_interaction),
+            );
+    }
+}
+
+fn setup_on_screen_keyboard(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            OnScreenKeyboardPanel,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(150),
+            Node {
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::horizontal(Val::Auto),
+                padding: UiRect::all(Val::Px(10.)),
+                row_gap: Val::Px(6.),
+                bottom: Val::Px(20.),
+                left: Val::Px(0.),
+                right: Val::Px(0.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            for (row_index, row) in key_rows().into_iter().enumerate() {
+                cb.spawn(Node {
+                    column_gap: Val::Px(6.),
+                    ..default()
+                })
+                .with_children(|cb| {
+                    for (column_index, key) in row.into_iter().enumerate() {
+                        cb.spawn((
+                            OnScreenKeyButton {
+                                key,
+                                selection: (row_index, column_index),
+                            },
+                            Button,
+                            BackgroundColor(BLUE),
+                            Node {
+                                height: Val::Px(40.),
+                                width: Val::Px(key.width()),
+                                ..default()
+                            },
+                        ))
+                        .with_children(|cb| {
+                            cb.spawn((
+                                Text::new(key.label(false)),
+                                TextColor(WHITE),
+                                TextFont::from_font(fonts.poppins_light.clone())
+                                    .with_font_size(18.),
+                                Node {
+                                    margin: UiRect::all(Val::Auto),
+                                    ..default()
+                                },
+                            ));
+                        });
+                    }
+                });
+            }
+        });
+}
+
+/// Returns whether the on-screen keyboard should be shown for the device the
+/// player is using.
+fn uses_on_screen_keyboard(device: InputDevice) -> bool {
+    device != InputDevice::Keyboard
+}
+
+/// Types into the input that is being edited using a physical keyboard.
+///
+/// Key presses are consumed while editing, so the typing doesn't also act on
+/// the rest of the game.
+fn on_text_input_keyboard_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut input_query: Query<&mut TextInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    let Some(mut input) = input_query.iter_mut().find(|input| input.is_editing) else {
+        // Typing from before editing started shouldn't end up in the input.
+        keyboard_events.clear();
+        return;
+    };
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(characters) => input.value.push_str(characters),
+            Key::Space => input.value.push(' '),
+            Key::Backspace => {
+                input.value.pop();
+            }
+            Key::Enter | Key::Escape => input.is_editing = false,
+            _ => {}
+        }
+    }
+
+    let pressed_keys: Vec<KeyCode> = keys.get_just_pressed().copied().collect();
+    for key in pressed_keys {
+        keys.clear_just_pressed(key);
+    }
+}
+
+/// Lets the player pick keys from the on-screen keyboard using a gamepad.
+///
+/// The D-pad moves the selection, the south button presses the selected key,
+/// the east button deletes and the start button finishes editing.
+fn on_on_screen_keyboard_input(
+    mut gamepads: Query<&mut Gamepad>,
+    mut input_query: Query<&mut TextInput>,
+    mut keyboard: ResMut<OnScreenKeyboard>,
+    device: Res<InputDevice>,
+) {
+    let Some(mut input) = input_query.iter_mut().find(|input| input.is_editing) else {
+        return;
+    };
+
+    // The device is detected after this runs, so a gamepad that is used for
+    // the first time still gets to open the keyboard.
+    let used_gamepad = gamepads
+        .iter()
+        .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+    if !uses_on_screen_keyboard(*device) && !used_gamepad {
+        return;
+    }
+
+    for mut gamepad in &mut gamepads {
+        for button in gamepad.get_just_pressed().copied().collect::<Vec<_>>() {
+            match button {
+                GamepadButton::DPadUp => keyboard.move_selection(0, -1),
+                GamepadButton::DPadRight => keyboard.move_selection(1, 0),
+                GamepadButton::DPadDown => keyboard.move_selection(0, 1),
+                GamepadButton::DPadLeft => keyboard.move_selection(-1, 0),
+                GamepadButton::South => {
+                    let key = keyboard.selected_key();
+                    keyboard.press(key, &mut input);
+                }
+                GamepadButton::East => keyboard.press(OnScreenKey::Backspace, &mut input),
+                GamepadButton::Start => keyboard.press(OnScreenKey::Done, &mut input),
+                _ => {}
+            }
+
+            // The buttons used for typing shouldn't also act on the game.
+            gamepad.digital_mut().clear_just_pressed(button);
+        }
+    }
+}
+
+fn on_on_screen_key_interaction(
+    key_query: Query<(&Interaction, &OnScreenKeyButton), Changed<Interaction>>,
+    mut input_query: Query<&mut TextInput>,
+    mut keyboard: ResMut<OnScreenKeyboard>,
+) {
+    let Some(mut input) = input_query.iter_mut().find(|input| input.is_editing) else {
+        return;
+    };
+
+    for (interaction, button) in &key_query {
+        match *interaction {
+            Interaction::Pressed => {
+                keyboard.selection = button.selection;
+                keyboard.press(button.key, &mut input);
+            }
+            Interaction::Hovered => keyboard.selection = button.selection,
+            Interaction::None => {}
+        }
+    }
+}
+
+fn render_text_inputs(
+    mut input_query: Query<(&TextInput, &mut Text), Changed<TextInput>>,
+    mut window_query: Query<&mut Window>,
+) {
+    let mut has_changed = false;
+    for (input, mut text) in &mut input_query {
+        text.0 = input.render();
+        has_changed = true;
+    }
+
+    if !has_changed {
+        return;
+    }
+
+    // iOS only shows its on-screen keyboard while IME is enabled.
+    let show_keyboard =
+        cfg!(target_os = "ios") && input_query.iter().any(|(input, _)| input.is_editing);
+    for mut window in &mut window_query {
+        if window.ime_enabled != show_keyboard {
+            window.ime_enabled = show_keyboard;
+        }
+    }
+}
+
+fn render_on_screen_keyboard(
+    mut panel_query: Query<&mut Node, With<OnScreenKeyboardPanel>>,
+    mut button_query: Query<(&OnScreenKeyButton, &Children, &mut BackgroundColor)>,
+    mut text_query: Query<&mut Text>,
+    input_query: Query<&TextInput>,
+    keyboard: Res<OnScreenKeyboard>,
+    device: Res<InputDevice>,
+) {
+    let Ok(mut panel_node) = panel_query.get_single_mut() else {
+        return;
+    };
+
+    // The iOS keyboard is used instead, since IME gets enabled for it.
+    let is_shown = !cfg!(target_os = "ios")
+        && uses_on_screen_keyboard(*device)
+        && input_query.iter().any(TextInput::is_editing);
+    let display = if is_shown {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if panel_node.display != display {
+        panel_node.display = display;
+    }
+
+    if !keyboard.is_changed() {
+        return;
+    }
+
+    for (button, children, mut background_color) in &mut button_query {
+        *background_color = if button.selection == keyboard.selection {
+            RED
+        } else {
+            BLUE
+        }
+        .into();
+
+        let mut iter = text_query.iter_many_mut(children);
+        while let Some(mut text) = iter.fetch_next() {
+            text.0 = button.key.label(keyboard.is_shifted);
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 
 use bevy::prelude::*;
 
@@ -93,3 +96,157 @@ impl DerefMut for TransporterTimer {
         &mut self.0
     }
 }
+
+/// Drives objects sliding over slippery entities, which glide faster than
+/// transporters push.
+#[derive(Resource)]
+pub struct SlideTimer(Timer);
+
+impl Default for SlideTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.1, TimerMode::Repeating))
+    }
+}
+
+impl Deref for SlideTimer {
+    type Target = Timer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SlideTimer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Resource)]
+pub struct LevelWatchTimer(Timer);
+
+impl Default for LevelWatchTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(1., TimerMode::Repeating))
+    }
+}
+
+impl Deref for LevelWatchTimer {
+    type Target = Timer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for LevelWatchTimer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Resource)]
+pub struct LevelBannerTimer(Timer);
+
+impl Default for LevelBannerTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(2.5, TimerMode::Once))
+    }
+}
+
+impl Deref for LevelBannerTimer {
+    type Target = Timer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for LevelBannerTimer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Repeats the player's move while a direction is held, like the key repeat
+/// of a keyboard: first after an initial delay, then at a steady interval.
+#[derive(Resource)]
+pub struct MovementRepeatTimer {
+    timer: Timer,
+    delay: Duration,
+    interval: Duration,
+}
+
+impl MovementRepeatTimer {
+    pub fn new(delay: Duration, interval: Duration) -> Self {
+        Self {
+            timer: Timer::new(delay, TimerMode::Once),
+            delay,
+            interval,
+        }
+    }
+
+    /// Waits for the initial delay again, for when another direction is held.
+    pub fn restart(&mut self) {
+        self.timer = Timer::new(self.delay, TimerMode::Once);
+    }
+
+    /// Advances the timer, and returns whether the move should be repeated.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        self.timer.tick(delta);
+        if !self.timer.just_finished() {
+            return false;
+        }
+
+        if self.timer.mode() == TimerMode::Once {
+            self.timer = Timer::new(self.interval, TimerMode::Repeating);
+        }
+        true
+    }
+}
+
+#[derive(Resource)]
+pub struct RespawnTimer(Timer);
+
+impl Default for RespawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.5, TimerMode::Once))
+    }
+}
+
+impl Deref for RespawnTimer {
+    type Target = Timer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for RespawnTimer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Resource)]
+pub struct RewindTimer(Timer);
+
+impl Default for RewindTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(0.1, TimerMode::Repeating))
+    }
+}
+
+impl Deref for RewindTimer {
+    type Target = Timer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for RewindTimer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
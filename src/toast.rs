@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{constants::*, fonts::Fonts, setup};
+
+/// How long a toast remains visible before it is dismissed.
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+
+/// Shows a short notification to the player, which dismisses itself after a
+/// few seconds.
+#[derive(Event)]
+pub struct ShowToast(pub String);
+
+impl ShowToast {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+/// Container for all visible toasts, so that multiple toasts get stacked.
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component)]
+struct Toast {
+    timer: Timer,
+}
+
+pub struct ToastPlugin;
+
+impl Plugin for ToastPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_toast_container.after(setup))
+            .add_event::<ShowToast>()
+            .add_observer(on_show_toast)
+            .add_systems(Update, dismiss_toasts);
+    }
+}
+
+fn setup_toast_container(mut commands: Commands) {
+    commands.spawn((
+        ToastContainer,
+        GlobalZIndex(200),
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(10.),
+            width: Val::Percent(100.),
+            bottom: Val::Px(20.),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+    ));
+}
+
+fn on_show_toast(
+    trigger: Trigger<ShowToast>,
+    mut commands: Commands,
+    container_query: Query<Entity, With<ToastContainer>>,
+    fonts: Res<Fonts>,
+) {
+    let ShowToast(message) = trigger.event();
+
+    let Ok(container) = container_query.get_single() else {
+        return;
+    };
+
+    commands.entity(container).with_children(|cb| {
+        cb.spawn((
+            Toast {
+                timer: Timer::new(TOAST_DURATION, TimerMode::Once),
+            },
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            Node {
+                border: UiRect::all(Val::Px(2.)),
+                padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                Text::new(message.clone()),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+        });
+    });
+}
+
+fn dismiss_toasts(
+    mut commands: Commands,
+    mut toast_query: Query<(Entity, &mut Toast)>,
+    time: Res<Time>,
+) {
+    for (entity, mut toast) in &mut toast_query {
+        toast.timer.tick(time.delta());
+        if toast.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    credits::CreditsState,
+    cutscene::CutsceneState,
+    dialogue::DialogueState,
+    editor::EditorState,
+    game_object::Player,
+    game_state::GameState,
+    menu::{MenuKind, MenuState},
+    LoadRelativeLevel,
+};
+
+/// Maximum distance a touch may move to still count as a tap or long press,
+/// rather than as a drag of the joystick.
+const MAX_TAP_DISTANCE: f32 = 20.;
+
+/// Maximum duration of a touch to count as a tap.
+const MAX_TAP_DURATION: Duration = Duration::from_millis(250);
+
+/// Maximum time between two taps to count as a double tap.
+const MAX_DOUBLE_TAP_INTERVAL: Duration = Duration::from_millis(350);
+
+/// Duration a touch needs to be held to count as a long press.
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(600);
+
+/// Recognizes the gestures used on touch screens:
+/// - A double tap restarts the level.
+/// - A long press opens the menu.
+#[derive(Default, Resource)]
+struct TouchGestureState {
+    press: Option<TouchPress>,
+    last_tap: Option<(Vec2, Duration)>,
+}
+
+struct TouchPress {
+    id: u64,
+    start_position: Vec2,
+    started_at: Duration,
+    is_long_press: bool,
+}
+
+pub struct TouchGesturePlugin;
+
+impl Plugin for TouchGesturePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchGestureState>()
+            .add_systems(Update, on_touch_gesture);
+    }
+}
+
+#[expect(clippy::too_many_arguments)]
+fn on_touch_gesture(
+    mut commands: Commands,
+    mut gesture_state: ResMut<TouchGestureState>,
+    mut menu_state: ResMut<MenuState>,
+    player_query: Query<(), With<Player>>,
+    credits_state: Res<CreditsState>,
+    cutscene_state: Res<CutsceneState>,
+    dialogue_state: Res<DialogueState>,
+    editor_state: Res<EditorState>,
+    game_state: Res<GameState>,
+    time: Res<Time>,
+    touches: Res<Touches>,
+) {
+    let now = time.elapsed();
+
+    let Some(press) = gesture_state.press.as_mut() else {
+        let is_blocked = credits_state.is_playing()
+            || cutscene_state.is_playing()
+            || dialogue_state.is_open()
+            || editor_state.is_open
+            || menu_state.is_open()
+            || player_query.is_empty();
+        if is_blocked {
+            return;
+        }
+
+        if let Some(touch) = touches.iter_just_pressed().next() {
+            gesture_state.press = Some(TouchPress {
+                id: touch.id(),
+                start_position: touch.position(),
+                started_at: now,
+                is_long_press: false,
+            });
+        }
+        return;
+    };
+
+    let duration = now.saturating_sub(press.started_at);
+
+    if let Some(touch) = touches.get_pressed(press.id) {
+        let is_moved = touch.position().distance(press.start_position) > MAX_TAP_DISTANCE;
+        if !press.is_long_press && !is_moved && duration >= LONG_PRESS_DURATION {
+            press.is_long_press = true;
+            menu_state.set_open(if game_state.is_in_hub() {
+                MenuKind::Hub
+            } else {
+                MenuKind::Level
+            });
+        }
+        return;
+    }
+
+    let start_position = press.start_position;
+    let is_tap = !press.is_long_press
+        && duration <= MAX_TAP_DURATION
+        && touches.iter_just_released().any(|touch| {
+            touch.id() == press.id && touch.position().distance(start_position) <= MAX_TAP_DISTANCE
+        });
+    gesture_state.press = None;
+
+    if !is_tap {
+        return;
+    }
+
+    let is_double_tap = gesture_state.last_tap.is_some_and(|(position, tapped_at)| {
+        now.saturating_sub(tapped_at) <= MAX_DOUBLE_TAP_INTERVAL
+            && position.distance(start_position) <= 2. * MAX_TAP_DISTANCE
+    });
+    if is_double_tap {
+        gesture_state.last_tap = None;
+        commands.trigger(LoadRelativeLevel(0));
+    } else {
+        gesture_state.last_tap = Some((start_position, now));
+    }
+}
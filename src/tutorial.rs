@@ -0,0 +1,224 @@
+use bevy::prelude::*;
+
+use crate::{
+    constants::*,
+    editor::EditorState,
+    fonts::Fonts,
+    game_object::{Position, Pushable},
+    game_state::GameState,
+    on_game_event, setup, LoadLevel, LoadRelativeLevel, ResetLevel,
+};
+
+/// Hints that teach the basics of the game during the first levels.
+///
+/// Every prompt is shown at most once, until the player has performed the
+/// action it asks for.
+const TUTORIAL_PROMPTS: &[TutorialPrompt] = &[
+    TutorialPrompt {
+        id: "move",
+        level: 1,
+        text: "Use the arrow keys to move",
+        shown_after: None,
+        dismissed_by: TutorialAction::Move,
+    },
+    TutorialPrompt {
+        id: "push",
+        level: 2,
+        text: "Walk into a blue block to push it",
+        shown_after: None,
+        dismissed_by: TutorialAction::Push,
+    },
+    TutorialPrompt {
+        id: "restart",
+        level: 3,
+        text: "Stuck? Press R to restart the level",
+        shown_after: Some(TutorialAction::Push),
+        dismissed_by: TutorialAction::Restart,
+    },
+];
+
+struct TutorialPrompt {
+    /// Identifies the prompt in the seen tutorials of the [GameState].
+    id: &'static str,
+    level: u16,
+    text: &'static str,
+
+    /// Action after which the prompt appears, or `None` to show it as soon as
+    /// the level is entered.
+    shown_after: Option<TutorialAction>,
+
+    dismissed_by: TutorialAction,
+}
+
+/// Actions performed by the player that may show or dismiss a prompt.
+#[derive(Clone, Copy, Eq, Event, PartialEq)]
+pub enum TutorialAction {
+    Move,
+    Push,
+    Restart,
+}
+
+/// The prompt for the current level, if there is one the player hasn't seen
+/// yet.
+#[derive(Default, Resource)]
+struct TutorialState {
+    prompt: Option<&'static TutorialPrompt>,
+    is_shown: bool,
+}
+
+#[derive(Component)]
+struct TutorialOverlay;
+
+#[derive(Component)]
+struct TutorialText;
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_tutorial_overlay.after(setup))
+            .init_resource::<TutorialState>()
+            .add_event::<TutorialAction>()
+            .add_observer(on_level_loaded)
+            .add_observer(on_level_restarted)
+            .add_observer(on_level_reset)
+            .add_observer(on_tutorial_action)
+            .add_systems(Update, detect_tutorial_actions.after(on_game_event))
+            .add_systems(Update, render_tutorial_overlay);
+    }
+}
+
+fn setup_tutorial_overlay(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn((
+            TutorialOverlay,
+            BackgroundColor(GRAY_BACKGROUND),
+            BorderColor(RED),
+            GlobalZIndex(100),
+            Node {
+                display: Display::None,
+                max_width: Val::Px(500.),
+                border: UiRect::all(Val::Px(2.)),
+                margin: UiRect::horizontal(Val::Auto),
+                padding: UiRect::axes(Val::Px(20.), Val::Px(10.)),
+                top: Val::Px(160.),
+                left: Val::Px(0.),
+                right: Val::Px(0.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+        ))
+        .with_children(|cb| {
+            cb.spawn((
+                TutorialText,
+                Text::default(),
+                TextColor(WHITE),
+                TextFont::from_font(fonts.poppins_light.clone()).with_font_size(20.),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    margin: UiRect::all(Val::Auto),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn on_level_loaded(
+    trigger: Trigger<LoadLevel>,
+    mut tutorial_state: ResMut<TutorialState>,
+    game_state: Res<GameState>,
+) {
+    let LoadLevel(level) = trigger.event();
+
+    // The demo of the attract mode shouldn't dismiss prompts on its own.
+    let prompt = TUTORIAL_PROMPTS.iter().find(|prompt| {
+        prompt.level == *level
+            && !game_state.has_seen_tutorial(prompt.id)
+            && !game_state.is_playing_demo
+    });
+    *tutorial_state = TutorialState {
+        prompt,
+        is_shown: prompt.is_some_and(|prompt| prompt.shown_after.is_none()),
+    };
+}
+
+fn on_level_restarted(trigger: Trigger<LoadRelativeLevel>, mut commands: Commands) {
+    let LoadRelativeLevel(delta) = trigger.event();
+    if *delta == 0 {
+        commands.trigger(TutorialAction::Restart);
+    }
+}
+
+fn on_level_reset(_trigger: Trigger<ResetLevel>, mut commands: Commands) {
+    commands.trigger(TutorialAction::Restart);
+}
+
+fn on_tutorial_action(
+    trigger: Trigger<TutorialAction>,
+    mut tutorial_state: ResMut<TutorialState>,
+    mut game_state: ResMut<GameState>,
+) {
+    let action = *trigger.event();
+    let Some(prompt) = tutorial_state.prompt else {
+        return;
+    };
+
+    if !tutorial_state.is_shown {
+        if prompt.shown_after == Some(action) {
+            tutorial_state.is_shown = true;
+        }
+    } else if prompt.dismissed_by == action {
+        game_state.mark_tutorial_seen(prompt.id);
+        *tutorial_state = TutorialState::default();
+    }
+}
+
+/// Detects the moves and pushes made by the player.
+///
+/// Only changes from a move made by the player count, so objects that are
+/// reset by restarting the level don't look like they've been pushed.
+fn detect_tutorial_actions(
+    mut commands: Commands,
+    pushed_query: Query<(), (With<Pushable>, Changed<Position>)>,
+    game_state: Res<GameState>,
+    mut num_moves: Local<u32>,
+) {
+    let current_moves = game_state.current_moves();
+    if current_moves > *num_moves {
+        commands.trigger(TutorialAction::Move);
+        if !pushed_query.is_empty() {
+            commands.trigger(TutorialAction::Push);
+        }
+    }
+
+    *num_moves = current_moves;
+}
+
+fn render_tutorial_overlay(
+    mut overlay_query: Query<&mut Node, With<TutorialOverlay>>,
+    mut text_query: Query<&mut Text, With<TutorialText>>,
+    tutorial_state: Res<TutorialState>,
+    editor_state: Res<EditorState>,
+) {
+    if !tutorial_state.is_changed() && !editor_state.is_changed() {
+        return;
+    }
+
+    let prompt = tutorial_state
+        .prompt
+        .filter(|_| tutorial_state.is_shown && !editor_state.is_open);
+
+    if let Some(prompt) = prompt {
+        for mut text in &mut text_query {
+            text.0 = prompt.text.to_owned();
+        }
+    }
+
+    for mut node in &mut overlay_query {
+        node.display = if prompt.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
@@ -1,10 +1,21 @@
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 
 #[derive(Resource)]
 pub struct UiState {
     pub camera_offset: (f32, f32),
     pub drag_start: Option<(f32, f32)>,
+
+    /// Speed at which the camera keeps moving after a drag is released, in
+    /// tiles per second.
+    pub drag_velocity: (f32, f32),
+
     pub zoom_factor: f32,
+
+    /// Zoom factor the player last used in every level they visited during
+    /// this session.
+    pub zoom_factors: BTreeMap<u16, f32>,
 }
 
 impl Default for UiState {
@@ -12,7 +23,9 @@ impl Default for UiState {
         Self {
             camera_offset: (0., 0.),
             drag_start: None,
+            drag_velocity: (0., 0.),
             zoom_factor: 1.,
+            zoom_factors: BTreeMap::new(),
         }
     }
 }
@@ -30,8 +30,7 @@ pub fn level_coords_from_pointer_coords(
 }
 
 pub fn ensure_chunky_dir() -> PathBuf {
-    #[expect(deprecated)]
-    let parent_dir = std::env::home_dir().unwrap_or(PathBuf::from("/tmp"));
+    let parent_dir = home_dir();
 
     let chunky_dir = parent_dir.join(if cfg!(target_os = "ios") {
         "Library/Application support"
@@ -51,20 +50,79 @@ pub fn ensure_chunky_dir() -> PathBuf {
     }
 }
 
+pub fn home_dir() -> PathBuf {
+    #[expect(deprecated)]
+    std::env::home_dir().unwrap_or(PathBuf::from("/tmp"))
+}
+
+/// Formats a timestamp in seconds since the Unix epoch as a `YYYY-MM-DD` date
+/// (UTC).
+pub fn format_date(timestamp: u64) -> String {
+    // See: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Formats a duration in seconds as `m:ss`.
+pub fn format_duration(seconds: f32) -> String {
+    let seconds = seconds.round() as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Returns the path of a level that is bundled with the game.
 pub fn get_level_path(level_number: u16) -> String {
     format!("assets/levels/level{level_number:0>3}")
 }
 
-pub fn load_asset(bytes: &[u8]) -> Image {
-    Image::from_buffer(
-        bytes,
-        ImageType::Extension("png"),
-        CompressedImageFormats::all(),
-        true,
-        ImageSampler::Default,
-        RenderAssetUsages::all(),
-    )
-    .expect("cannot load game object asset")
+/// Returns the path that levels saved from the editor are written to.
+///
+/// The install dir isn't writable for release builds, so levels are saved in
+/// the chunky dir instead, where they override the bundled levels.
+pub fn get_user_level_path(level_number: u16) -> PathBuf {
+    user_levels_dir().join(format!("level{level_number:0>3}"))
+}
+
+/// Returns the path a level gets loaded from, which is the user's own version
+/// of it if there is one.
+pub fn find_level_path(level_number: u16) -> PathBuf {
+    let user_level_path = get_user_level_path(level_number);
+    if user_level_path.exists() {
+        user_level_path
+    } else {
+        get_level_path(level_number).into()
+    }
+}
+
+/// Returns the directory with theme packs, each of which is a directory of
+/// PNG files that replace the embedded sprites with the same names.
+pub fn themes_dir() -> PathBuf {
+    ensure_chunky_dir().join("themes")
+}
+
+pub fn user_levels_dir() -> PathBuf {
+    let chunky_dir = ensure_chunky_dir();
+    let levels_dir = chunky_dir.join("levels");
+    match fs::create_dir_all(&levels_dir) {
+        Ok(()) => levels_dir,
+        Err(err) => {
+            warn!("Falling back to chunky dir ({chunky_dir:?}): {err:?}");
+            chunky_dir
+        }
+    }
 }
 
 pub fn load_repeating_asset(bytes: &[u8]) -> Image {
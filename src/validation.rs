@@ -0,0 +1,112 @@
+use crate::{
+    game_object::ObjectType,
+    levels::{Level, LEVELS},
+};
+
+/// Validates all built-in levels and prints the problems found.
+///
+/// Returns `true` if no problems were found. Warnings are printed as well, but
+/// don't cause validation to fail.
+pub fn validate_levels() -> bool {
+    let mut num_invalid_levels = 0;
+    for (level_number, content) in LEVELS {
+        let Validation { problems, warnings } = match Level::load(content) {
+            Ok(level) => validate_level(&level),
+            Err(error) => Validation {
+                problems: vec![error.to_string()],
+                warnings: Vec::new(),
+            },
+        };
+        if problems.is_empty() && warnings.is_empty() {
+            continue;
+        }
+
+        if !problems.is_empty() {
+            num_invalid_levels += 1;
+        }
+
+        println!("Level {level_number}:");
+        for problem in problems {
+            println!("  {problem}");
+        }
+        for warning in warnings {
+            println!("  Warning: {warning}");
+        }
+    }
+
+    if num_invalid_levels == 0 {
+        println!("All {} levels are valid.", LEVELS.len());
+        true
+    } else {
+        println!(
+            "{num_invalid_levels} out of {} levels have problems.",
+            LEVELS.len()
+        );
+        false
+    }
+}
+
+struct Validation {
+    problems: Vec<String>,
+
+    /// Issues that are expected in levels that are still being worked on,
+    /// such as a missing exit.
+    warnings: Vec<String>,
+}
+
+fn validate_level(level: &Level) -> Validation {
+    let mut problems = level.warnings.clone();
+    let mut warnings = Vec::new();
+
+    let count = |object_type| level.objects.get(&object_type).map_or(0, Vec::len);
+
+    match count(ObjectType::Player) {
+        0 => problems.push("Missing player".to_owned()),
+        1 => {}
+        num_players => problems.push(format!("Found {num_players} players, expected one")),
+    }
+
+    if count(ObjectType::Exit) == 0 {
+        warnings.push("Missing exit".to_owned());
+    }
+
+    for exit in level.objects.get(&ObjectType::Exit).into_iter().flatten() {
+        if let Some(target) = exit.level {
+            if !LEVELS.iter().any(|(level, _)| *level == target) {
+                problems.push(format!(
+                    "Exit at {} leads to unknown level {target}",
+                    exit.position
+                ));
+            }
+        }
+    }
+
+    for decoration in level
+        .objects
+        .get(&ObjectType::Decoration)
+        .into_iter()
+        .flatten()
+    {
+        if let Some(target) = decoration.level {
+            if !LEVELS.iter().any(|(level, _)| *level == target) {
+                problems.push(format!(
+                    "Decoration at {} is revealed by unknown level {target}",
+                    decoration.position
+                ));
+            }
+        }
+    }
+
+    for (object_type, objects) in &level.objects {
+        for object in objects {
+            if !level.dimensions.contains(object.position) {
+                problems.push(format!(
+                    "{object_type} at {} is out of bounds",
+                    object.position
+                ));
+            }
+        }
+    }
+
+    Validation { problems, warnings }
+}
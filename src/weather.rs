@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::{
+    background::Background, constants::*, editor::EditorState, levels::Weather, settings::Settings,
+};
+
+/// Number of particles for every tile in the window. The total is capped by
+/// [MAX_PARTICLES], so zooming out shows fewer particles per tile instead of
+/// spawning thousands of them.
+const PARTICLES_PER_TILE: f32 = 0.25;
+const MAX_PARTICLES: usize = 400;
+
+/// Particles are shown above the level and its tint, but below the UI.
+const WEATHER_Z: f32 = 50.;
+
+/// Speed and size of the particles, relative to the tiles of the level.
+const RAIN_VELOCITY: Vec2 = Vec2::new(-1.5, -14.);
+const RAIN_SIZE: Vec2 = Vec2::new(0.04, 0.4);
+const RAIN_COLOR: Color = Color::srgba(0.75, 0.85, 1., 0.5);
+const SNOW_VELOCITY: Vec2 = Vec2::new(0., -1.5);
+const SNOW_SIZE: Vec2 = Vec2::new(0.1, 0.1);
+const SNOW_COLOR: Color = Color::srgba(1., 1., 1., 0.8);
+
+/// How far snowflakes drift from side to side, in tiles per second.
+const SNOW_SWAY: f32 = 0.5;
+
+#[derive(Component)]
+struct WeatherParticle {
+    /// Offset of the particle's sway, so snowflakes don't all drift in unison.
+    phase: f32,
+}
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_weather_particles, move_weather_particles).chain(),
+        );
+    }
+}
+
+/// Keeps the number of particles in line with the part of the level that is
+/// visible, and replaces them when the weather changes.
+fn spawn_weather_particles(
+    mut commands: Commands,
+    background_query: Query<&Transform, With<Background>>,
+    particle_query: Query<Entity, With<WeatherParticle>>,
+    window_query: Query<&Window>,
+    editor_state: Res<EditorState>,
+    settings: Res<Settings>,
+    weather: Res<Weather>,
+) {
+    let (Ok(background_transform), Ok(window)) =
+        (background_query.get_single(), window_query.get_single())
+    else {
+        return;
+    };
+
+    if weather.is_changed() {
+        for entity in &particle_query {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    let is_shown = *weather != Weather::Clear && !editor_state.is_open && !settings.reduce_motion;
+    let count = if is_shown {
+        let grid_size = GRID_SIZE as f32 * background_transform.scale.x;
+        let num_tiles = window.width() * window.height() / (grid_size * grid_size);
+        ((num_tiles * PARTICLES_PER_TILE) as usize).min(MAX_PARTICLES)
+    } else {
+        0
+    };
+    let current_count = if weather.is_changed() {
+        0
+    } else {
+        particle_query.iter().len()
+    };
+
+    if current_count > count {
+        for entity in particle_query.iter().take(current_count - count) {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+
+    let (size, color) = match *weather {
+        Weather::Clear => return,
+        Weather::Rain => (RAIN_SIZE, RAIN_COLOR),
+        Weather::Snow => (SNOW_SIZE, SNOW_COLOR),
+    };
+
+    let mut rng = thread_rng();
+    let half_size = 0.5 * window.size();
+    for _ in current_count..count {
+        let translation = Vec3::new(
+            rng.gen_range(-half_size.x..=half_size.x),
+            rng.gen_range(-half_size.y..=half_size.y),
+            WEATHER_Z,
+        );
+        commands.spawn((
+            WeatherParticle {
+                phase: rng.gen_range(0.0..std::f32::consts::TAU),
+            },
+            Sprite::from_color(color, size * GRID_SIZE as f32),
+            Transform::from_translation(translation),
+        ));
+    }
+}
+
+/// Moves the particles across the window, at a speed that matches the zoom
+/// of the level. Particles that leave the window come back on the other
+/// side.
+fn move_weather_particles(
+    background_query: Query<&Transform, (With<Background>, Without<WeatherParticle>)>,
+    mut particle_query: Query<(&WeatherParticle, &mut Transform)>,
+    window_query: Query<&Window>,
+    time: Res<Time>,
+    weather: Res<Weather>,
+) {
+    let (Ok(background_transform), Ok(window)) =
+        (background_query.get_single(), window_query.get_single())
+    else {
+        return;
+    };
+
+    let zoom_factor = background_transform.scale.x;
+    let grid_size = GRID_SIZE as f32 * zoom_factor;
+    let half_size = 0.5 * window.size();
+
+    for (particle, mut transform) in &mut particle_query {
+        let velocity = match *weather {
+            Weather::Clear => continue,
+            Weather::Rain => RAIN_VELOCITY,
+            Weather::Snow => {
+                let sway = SNOW_SWAY * (time.elapsed_secs() + particle.phase).sin();
+                SNOW_VELOCITY + Vec2::new(sway, 0.)
+            }
+        };
+
+        let translation =
+            transform.translation.truncate() + velocity * grid_size * time.delta_secs();
+        let wrapped = (translation + half_size).rem_euclid(window.size()) - half_size;
+        transform.translation = wrapped.extend(WEATHER_Z);
+
+        // Raindrops are slanted along their direction of travel.
+        transform.rotation = Quat::from_rotation_z(velocity.x.atan2(-velocity.y));
+        transform.scale = Vec3::new(zoom_factor, zoom_factor, 1.);
+    }
+}